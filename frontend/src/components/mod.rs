@@ -1,5 +1,7 @@
 use yew::prelude::*;
 use crate::pages::{DashboardPage, ChannelsPage, ActionsPage, RecommendationsPage, YieldsPage};
+use crate::services::{fetch_nodes, select_node, selected_node_id};
+use crate::types::NodeSummary;
 
 pub mod chart;
 
@@ -13,6 +15,9 @@ pub fn navbar() -> Html {
     let current_page = use_state(|| "dashboard".to_string());
     let is_mobile_menu_open = use_state(|| false);
 
+    let nodes = use_state(|| Vec::<NodeSummary>::new());
+    let selected_node = use_state(selected_node_id);
+
     let toggle_mobile_menu = {
         let is_mobile_menu_open = is_mobile_menu_open.clone();
         Callback::from(move |_| {
@@ -20,6 +25,39 @@ pub fn navbar() -> Html {
         })
     };
 
+    {
+        let nodes = nodes.clone();
+        use_effect_with_deps(
+            move |_| {
+                wasm_bindgen_futures::spawn_local(async move {
+                    if let Ok(list) = fetch_nodes().await {
+                        nodes.set(list);
+                    }
+                });
+                || ()
+            },
+            (),
+        );
+    }
+
+    let on_node_change = {
+        let selected_node = selected_node.clone();
+        Callback::from(move |e: Event| {
+            let select = e.target_dyn_into::<web_sys::HtmlSelectElement>().unwrap();
+            let id = select.value();
+            let selected_node = selected_node.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                if select_node(&id).await.is_ok() {
+                    selected_node.set(Some(id));
+                    // Les pages rechargent leurs données pour le nouveau nœud
+                    if let Some(window) = web_sys::window() {
+                        let _ = window.location().reload();
+                    }
+                }
+            });
+        })
+    };
+
     html! {
         <nav class="navbar">
             <div class="navbar-brand">
@@ -43,6 +81,9 @@ pub fn navbar() -> Html {
                 <a href="/yields" class={if *current_page == "yields" { "active" } else { "" }}>
                     {"Rendements"}
                 </a>
+                <a href="/routes" class={if *current_page == "routes" { "active" } else { "" }}>
+                    {"Routes"}
+                </a>
                 <a href="/actions" class={if *current_page == "actions" { "active" } else { "" }}>
                     {"Actions"}
                 </a>
@@ -50,6 +91,18 @@ pub fn navbar() -> Html {
                     {"Recommandations"}
                 </a>
             </div>
+            if nodes.len() > 1 {
+                <select class="node-switcher" onchange={on_node_change}>
+                    {for nodes.iter().map(|node| {
+                        let selected = selected_node.as_deref() == Some(node.id.as_str());
+                        html! {
+                            <option value={node.id.clone()} selected={selected}>
+                                {if node.name.is_empty() { &node.id } else { &node.name }}
+                            </option>
+                        }
+                    })}
+                </select>
+            }
         </nav>
     }
 }
@@ -1,6 +1,6 @@
 use yew::prelude::*;
 use yew_router::prelude::*;
-use pages::{DashboardPage, ChannelsPage, ActionsPage, RecommendationsPage, YieldsPage};
+use pages::{DashboardPage, ChannelsPage, ActionsPage, RecommendationsPage, RoutesPage, YieldsPage};
 
 mod pages;
 mod components;
@@ -15,6 +15,8 @@ enum Route {
     Channels,
     #[at("/yields")]
     Yields,
+    #[at("/routes")]
+    Routes,
     #[at("/recommendations")]
     Recommendations,
     #[at("/actions")]
@@ -26,6 +28,7 @@ fn switch(routes: Route) -> Html {
         Route::Dashboard => html! { <DashboardPage /> },
         Route::Channels => html! { <ChannelsPage /> },
         Route::Yields => html! { <YieldsPage /> },
+        Route::Routes => html! { <RoutesPage /> },
         Route::Recommendations => html! { <RecommendationsPage /> },
         Route::Actions => html! { <ActionsPage /> },
     }
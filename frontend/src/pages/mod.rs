@@ -1,7 +1,50 @@
+use std::rc::Rc;
 use yew::prelude::*;
 use crate::components::{Navbar, Card, Button, YieldChart};
-use crate::types::{Dashboard, Channel, Action, Recommendation, NodeStats, ChannelRecommendation, OutboundLiquidityValue, SuggestedFees};
-use crate::services::{fetch_all_data, fetch_channels, get_ai_recommendations};
+use crate::types::{Dashboard, Channel, Action, Recommendation, NodeStats, ChannelRecommendation, OutboundLiquidityValue, SuggestedFees, NodeEvent, TransactionStatus, RouteQuote, YieldReport, ChannelYield};
+use crate::services::{fetch_all_data, fetch_channels, fetch_route_quotes, fetch_yields, get_ai_recommendations, subscribe_node_events};
+
+/// Nombre d'événements affichés dans l'activité en direct
+const LIVE_EVENTS_SHOWN: usize = 10;
+
+/// Derniers événements reçus du nœud, du plus récent au plus ancien
+#[derive(Default, PartialEq)]
+struct LiveEvents(Vec<NodeEvent>);
+
+impl Reducible for LiveEvents {
+    type Action = NodeEvent;
+
+    fn reduce(self: Rc<Self>, event: NodeEvent) -> Rc<Self> {
+        let mut events = Vec::with_capacity(LIVE_EVENTS_SHOWN);
+        events.push(event);
+        events.extend(self.0.iter().take(LIVE_EVENTS_SHOWN - 1).cloned());
+        Rc::new(LiveEvents(events))
+    }
+}
+
+fn describe_event(event: &NodeEvent) -> String {
+    match event {
+        NodeEvent::InvoiceSettled { amount, .. } => format!("Facture réglée : {} sats", amount),
+        NodeEvent::ChannelOpened { remote_pubkey, capacity, .. } => {
+            format!("Canal ouvert avec {} ({} sats)", remote_pubkey, capacity)
+        }
+        NodeEvent::ChannelClosed { channel_id } => format!("Canal fermé : {}", channel_id),
+        NodeEvent::ChannelActive { channel_id } => format!("Canal actif : {}", channel_id),
+        NodeEvent::ChannelInactive { channel_id } => format!("Canal inactif : {}", channel_id),
+        NodeEvent::HtlcForwarded(forward) => format!(
+            "Routage {} → {} : {} msats de frais",
+            forward.chan_id_in, forward.chan_id_out, forward.fee_msat
+        ),
+        NodeEvent::PaymentUpdated { amount, status, .. } => {
+            let status = match status {
+                TransactionStatus::Completed => "réussi",
+                TransactionStatus::Pending => "en cours",
+                TransactionStatus::Failed => "échoué",
+            };
+            format!("Paiement {} : {} sats", status, amount)
+        }
+    }
+}
 
 #[function_component(DashboardPage)]
 pub fn dashboard_page() -> Html {
@@ -50,6 +93,42 @@ pub fn dashboard_page() -> Html {
         );
     }
 
+    let live_events = use_reducer(LiveEvents::default);
+
+    {
+        let dispatcher = live_events.dispatcher();
+        let stats = stats.clone();
+        let recommendations = recommendations.clone();
+        let liquidity = liquidity.clone();
+        let fees = fees.clone();
+
+        use_effect_with_deps(
+            move |_| {
+                let on_event = Callback::from(move |event: NodeEvent| {
+                    // Les canaux ont changé : on rafraîchit les données sans écran de chargement
+                    if event.affects_channels() {
+                        let stats = stats.clone();
+                        let recommendations = recommendations.clone();
+                        let liquidity = liquidity.clone();
+                        let fees = fees.clone();
+                        wasm_bindgen_futures::spawn_local(async move {
+                            if let Ok((node_stats, channel_recommendations, outbound_liquidity, suggested_fees)) = fetch_all_data().await {
+                                stats.set(Some(node_stats));
+                                recommendations.set(Some(channel_recommendations));
+                                liquidity.set(Some(outbound_liquidity));
+                                fees.set(Some(suggested_fees));
+                            }
+                        });
+                    }
+                    dispatcher.dispatch(event);
+                });
+                let subscription = subscribe_node_events(on_event).ok();
+                move || drop(subscription)
+            },
+            (),
+        );
+    }
+
     html! {
         <div class="dashboard">
             <Navbar />
@@ -194,6 +273,18 @@ pub fn dashboard_page() -> Html {
                                         </div>
                                     </Card>
                                 }
+
+                                <Card title="Activité en direct">
+                                    if live_events.0.is_empty() {
+                                        <p class="live-events-empty">{"En attente d'événements du nœud..."}</p>
+                                    } else {
+                                        <ul class="live-events">
+                                            {for live_events.0.iter().map(|event| html! {
+                                                <li>{describe_event(event)}</li>
+                                            })}
+                                        </ul>
+                                    }
+                                </Card>
                             </div>
                         }
                     </div>
@@ -236,6 +327,29 @@ pub fn channels_page() -> Html {
         );
     }
 
+    {
+        let channels = channels.clone();
+
+        use_effect_with_deps(
+            move |_| {
+                let on_event = Callback::from(move |event: NodeEvent| {
+                    if !event.affects_channels() {
+                        return;
+                    }
+                    let channels = channels.clone();
+                    wasm_bindgen_futures::spawn_local(async move {
+                        if let Ok(channels_data) = fetch_channels().await {
+                            channels.set(Some(channels_data));
+                        }
+                    });
+                });
+                let subscription = subscribe_node_events(on_event).ok();
+                move || drop(subscription)
+            },
+            (),
+        );
+    }
+
     html! {
         <div class="page">
             <Navbar current_page={"channels".to_string()} />
@@ -497,10 +611,10 @@ pub fn recommendations_page() -> Html {
 
 #[derive(Clone, PartialEq)]
 enum SortBy {
-    YieldRate,
-    DailyYield,
-    Capacity,
-    FeeRate,
+    AnnualizedReturn,
+    Fees,
+    Forwards,
+    Capital,
 }
 
 #[derive(Clone, PartialEq)]
@@ -510,87 +624,83 @@ enum FilterStatus {
     Inactive,
 }
 
+fn format_return(annualized_return: Option<f64>) -> String {
+    annualized_return.map_or_else(|| "-".to_string(), |r| format!("{:.2}%", r))
+}
+
+fn format_msat(msat: u64) -> String {
+    format!("{:.3} sats", msat as f64 / 1000.0)
+}
+
 #[function_component(YieldsPage)]
 pub fn yields_page() -> Html {
-    let channels = use_state(|| None::<Vec<Channel>>);
+    let report = use_state(|| None::<YieldReport>);
     let error = use_state(|| None::<String>);
     let loading = use_state(|| true);
-    let sort_by = use_state(|| SortBy::YieldRate);
+    let sort_by = use_state(|| SortBy::AnnualizedReturn);
     let filter_status = use_state(|| FilterStatus::All);
     let time_range = use_state(|| "7d".to_string());
+    // Incrémenté pour relancer le chargement sans changer de période
+    let reload = use_state(|| 0u32);
 
     {
-        let channels = channels.clone();
+        let report = report.clone();
         let error = error.clone();
         let loading = loading.clone();
 
         use_effect_with_deps(
-            move |_| {
+            move |(range, _)| {
+                let range = range.clone();
                 wasm_bindgen_futures::spawn_local(async move {
                     loading.set(true);
                     error.set(None);
-                    match fetch_channels().await {
-                        Ok(channels_data) => {
-                            channels.set(Some(channels_data));
-                            error.set(None);
-                        }
+                    match fetch_yields(&range).await {
+                        Ok(data) => report.set(Some(data)),
                         Err(e) => {
-                            error.set(Some(format!("Erreur lors de la récupération des canaux : {}", e)));
+                            error.set(Some(format!("Erreur lors de la récupération des rendements : {}", e)));
                         }
                     }
                     loading.set(false);
                 });
                 || ()
             },
-            (),
+            ((*time_range).clone(), *reload),
         );
     }
 
-    let filtered_and_sorted_channels = channels.as_ref().map(|channels_data| {
-        let mut filtered = channels_data.clone();
-        
-        // Appliquer le filtre de statut
-        filtered = filtered.into_iter()
+    let filtered_and_sorted_channels = report.as_ref().map(|data| {
+        let mut filtered: Vec<ChannelYield> = data.channels.iter()
             .filter(|c| match *filter_status {
                 FilterStatus::All => true,
                 FilterStatus::Active => c.active,
                 FilterStatus::Inactive => !c.active,
             })
+            .cloned()
             .collect();
 
-        // Trier selon le critère sélectionné
-        filtered.sort_by(|a, b| {
-            match *sort_by {
-                SortBy::YieldRate => {
-                    let yield_a = if a.capacity > 0 {
-                        (a.fee_rate as f64 * a.capacity as f64 / 1_000_000.0 / a.capacity as f64) * 100.0
-                    } else { 0.0 };
-                    let yield_b = if b.capacity > 0 {
-                        (b.fee_rate as f64 * b.capacity as f64 / 1_000_000.0 / b.capacity as f64) * 100.0
-                    } else { 0.0 };
-                    yield_b.partial_cmp(&yield_a).unwrap_or(std::cmp::Ordering::Equal)
-                },
-                SortBy::DailyYield => {
-                    let yield_a = a.fee_rate as f64 * a.capacity as f64 / 1_000_000.0;
-                    let yield_b = b.fee_rate as f64 * b.capacity as f64 / 1_000_000.0;
-                    yield_b.partial_cmp(&yield_a).unwrap_or(std::cmp::Ordering::Equal)
-                },
-                SortBy::Capacity => b.capacity.cmp(&a.capacity),
-                SortBy::FeeRate => b.fee_rate.cmp(&a.fee_rate),
-            }
+        filtered.sort_by(|a, b| match *sort_by {
+            SortBy::AnnualizedReturn => b.annualized_return.unwrap_or(0.0)
+                .partial_cmp(&a.annualized_return.unwrap_or(0.0))
+                .unwrap_or(std::cmp::Ordering::Equal),
+            SortBy::Fees => b.fee_msat.cmp(&a.fee_msat),
+            SortBy::Forwards => b.forwards.cmp(&a.forwards),
+            SortBy::Capital => b.capital.cmp(&a.capital),
         });
 
         filtered
     });
 
     // Données pour les graphiques
-    let yield_data = filtered_and_sorted_channels.as_ref().map(|channels| {
+    let channel_fees = filtered_and_sorted_channels.as_ref().map(|channels| {
         channels.iter()
             .take(10)
-            .map(|c| {
-                let daily_yield = c.fee_rate as f64 * c.capacity as f64 / 1_000_000.0;
-                (format!("Canal {}", c.pubkey[..8].to_string()), daily_yield)
-            })
+            .map(|c| (format!("Canal {}", &c.remote_pubkey[..8.min(c.remote_pubkey.len())]), c.fee_msat as f64 / 1000.0))
+            .collect::<Vec<_>>()
+    });
+    let peer_fees = report.as_ref().map(|data| {
+        data.peers.iter()
+            .take(10)
+            .map(|p| (format!("Pair {}", &p.remote_pubkey[..8.min(p.remote_pubkey.len())]), p.fee_msat as f64 / 1000.0))
             .collect::<Vec<_>>()
     });
 
@@ -599,97 +709,69 @@ pub fn yields_page() -> Html {
             <Navbar current_page={"yields".to_string()} />
             <div class="content">
                 <h1>{"Rendements des Canaux"}</h1>
-                
+
                 if *loading {
                     <div class="loading">{"Chargement des données de rendement..."}</div>
                 } else if let Some(err) = &*error {
                     <div class="error">
                         <p>{err}</p>
-                        <Button onclick={Callback::from(|_| {
-                            let channels = channels.clone();
-                            let error = error.clone();
-                            let loading = loading.clone();
-
-                            wasm_bindgen_futures::spawn_local(async move {
-                                loading.set(true);
-                                error.set(None);
-                                match fetch_channels().await {
-                                    Ok(channels_data) => {
-                                        channels.set(Some(channels_data));
-                                        error.set(None);
-                                    }
-                                    Err(e) => {
-                                        error.set(Some(format!("Erreur lors de la récupération des canaux : {}", e)));
-                                    }
-                                }
-                                loading.set(false);
-                            });
-                        })}>
+                        <Button onclick={{
+                            let reload = reload.clone();
+                            Callback::from(move |_| reload.set(*reload + 1))
+                        }}>
                             {"Réessayer"}
                         </Button>
                     </div>
-                } else if let Some(channels_data) = &*channels {
-                    // Calcul des métriques de rendement
-                    let total_yield: f64 = channels_data.iter()
-                        .map(|c| c.fee_rate as f64 * c.capacity as f64 / 1_000_000.0)
-                        .sum();
-                    
-                    let avg_yield_rate: f64 = if !channels_data.is_empty() {
-                        total_yield / channels_data.iter().map(|c| c.capacity as f64).sum::<f64>() * 100.0
-                    } else {
-                        0.0
-                    };
-
+                } else if let Some(data) = &*report {
                     <div class="yields-summary">
                         <div class="summary-item">
-                            <h3>{"Rendement total estimé"}</h3>
-                            <p>{format!("{:.2} sats/jour", total_yield)}</p>
+                            <h3>{"Frais gagnés"}</h3>
+                            <p>{format_msat(data.fee_msat)}</p>
                         </div>
                         <div class="summary-item">
-                            <h3>{"Taux de rendement moyen"}</h3>
-                            <p>{format!("{:.2}%", avg_yield_rate)}</p>
+                            <h3>{"Rendement annualisé"}</h3>
+                            <p>{format_return(data.annualized_return)}</p>
                         </div>
                         <div class="summary-item">
-                            <h3>{"Nombre de canaux actifs"}</h3>
-                            <p>{channels_data.iter().filter(|c| c.active).count()}</p>
+                            <h3>{"Capital engagé"}</h3>
+                            <p>{format!("{} sats", data.capital)}</p>
                         </div>
                         <div class="summary-item">
-                            <h3>{"Capacité totale"}</h3>
-                            <p>{format!("{} sats", channels_data.iter().map(|c| c.capacity).sum::<u64>())}</p>
+                            <h3>{"Dont canaux fermés"}</h3>
+                            <p>{format_msat(data.closed_channels_fee_msat)}</p>
                         </div>
                     </div>
 
                     <div class="yields-filters">
                         <div class="filter-group">
                             <label>{"Trier par:"}</label>
-                            <select 
+                            <select
                                 value={match *sort_by {
-                                    SortBy::YieldRate => "yield_rate",
-                                    SortBy::DailyYield => "daily_yield",
-                                    SortBy::Capacity => "capacity",
-                                    SortBy::FeeRate => "fee_rate",
+                                    SortBy::AnnualizedReturn => "annualized_return",
+                                    SortBy::Fees => "fees",
+                                    SortBy::Forwards => "forwards",
+                                    SortBy::Capital => "capital",
                                 }}
                                 onchange={Callback::from(move |e: Event| {
                                     let select = e.target_dyn_into::<web_sys::HtmlSelectElement>().unwrap();
                                     sort_by.set(match select.value().as_str() {
-                                        "yield_rate" => SortBy::YieldRate,
-                                        "daily_yield" => SortBy::DailyYield,
-                                        "capacity" => SortBy::Capacity,
-                                        "fee_rate" => SortBy::FeeRate,
-                                        _ => SortBy::YieldRate,
+                                        "fees" => SortBy::Fees,
+                                        "forwards" => SortBy::Forwards,
+                                        "capital" => SortBy::Capital,
+                                        _ => SortBy::AnnualizedReturn,
                                     });
                                 })}
                             >
-                                <option value="yield_rate">{"Taux de rendement"}</option>
-                                <option value="daily_yield">{"Rendement quotidien"}</option>
-                                <option value="capacity">{"Capacité"}</option>
-                                <option value="fee_rate">{"Taux de frais"}</option>
+                                <option value="annualized_return">{"Rendement annualisé"}</option>
+                                <option value="fees">{"Frais gagnés"}</option>
+                                <option value="forwards">{"Nombre de relais"}</option>
+                                <option value="capital">{"Capital engagé"}</option>
                             </select>
                         </div>
 
                         <div class="filter-group">
                             <label>{"Statut:"}</label>
-                            <select 
+                            <select
                                 value={match *filter_status {
                                     FilterStatus::All => "all",
                                     FilterStatus::Active => "active",
@@ -713,7 +795,7 @@ pub fn yields_page() -> Html {
 
                         <div class="filter-group">
                             <label>{"Période:"}</label>
-                            <select 
+                            <select
                                 value={(*time_range).clone()}
                                 onchange={Callback::from(move |e: Event| {
                                     let select = e.target_dyn_into::<web_sys::HtmlSelectElement>().unwrap();
@@ -728,96 +810,81 @@ pub fn yields_page() -> Html {
                     </div>
 
                     <div class="yields-charts">
-                        if let Some(data) = yield_data {
-                            <div class="chart-grid">
-                                <Card title="Top 10 des canaux par rendement quotidien">
-                                    <YieldChart 
-                                        data={data.clone()}
-                                        title="Rendement quotidien (sats)".to_string()
+                        <div class="chart-grid">
+                            if let Some(data) = channel_fees {
+                                <Card title="Top 10 des canaux par frais gagnés">
+                                    <YieldChart
+                                        data={data}
+                                        title="Frais gagnés (sats)".to_string()
                                         color="#3498db".to_string()
                                     />
                                 </Card>
-                                <Card title="Évolution des rendements">
-                                    <YieldChart 
+                            }
+                            if let Some(data) = peer_fees {
+                                <Card title="Top 10 des pairs par frais gagnés">
+                                    <YieldChart
                                         data={data}
-                                        title="Rendement cumulé (sats)".to_string()
+                                        title="Frais gagnés (sats)".to_string()
                                         color="#2ecc71".to_string()
                                     />
                                 </Card>
-                            </div>
-                        }
+                            }
+                        </div>
                     </div>
 
                     <div class="yields-grid">
                         {for filtered_and_sorted_channels.iter().flatten().map(|channel| {
-                            let daily_yield = channel.fee_rate as f64 * channel.capacity as f64 / 1_000_000.0;
-                            let yield_rate = if channel.capacity > 0 {
-                                (daily_yield / channel.capacity as f64) * 100.0
-                            } else {
-                                0.0
-                            };
-
                             html! {
-                                <Card title={format!("Canal avec {}", channel.pubkey)}>
+                                <Card title={format!("Canal avec {}", channel.remote_pubkey)}>
                                     <div class="yield-info">
                                         <div class="yield-header">
                                             <div class="yield-status">
-                                                <span class={format!("status-indicator status-{}", 
+                                                <span class={format!("status-indicator status-{}",
                                                     if channel.active { "active" } else { "inactive" }
                                                 )}></span>
                                                 <span>{if channel.active { "Actif" } else { "Inactif" }}</span>
                                             </div>
                                             <div class="yield-rate">
-                                                <span class="rate-label">{"Taux de rendement"}</span>
-                                                <span class="rate-value">{format!("{:.2}%", yield_rate)}</span>
+                                                <span class="rate-label">{"Rendement annualisé"}</span>
+                                                <span class="rate-value">{format_return(channel.annualized_return)}</span>
                                             </div>
                                         </div>
 
                                         <div class="yield-details">
                                             <div class="detail-item">
-                                                <span class="detail-label">{"Rendement quotidien"}</span>
-                                                <span class="detail-value">{format!("{:.2} sats", daily_yield)}</span>
+                                                <span class="detail-label">{"Frais gagnés"}</span>
+                                                <span class="detail-value">{format_msat(channel.fee_msat)}</span>
                                             </div>
                                             <div class="detail-item">
-                                                <span class="detail-label">{"Capacité"}</span>
-                                                <span class="detail-value">{format!("{} sats", channel.capacity)}</span>
+                                                <span class="detail-label">{"Relais sortants"}</span>
+                                                <span class="detail-value">{channel.forwards}</span>
                                             </div>
                                             <div class="detail-item">
-                                                <span class="detail-label">{"Taux de frais"}</span>
-                                                <span class="detail-value">{format!("{} ppm", channel.fee_rate)}</span>
+                                                <span class="detail-label">{"Volume sortant"}</span>
+                                                <span class="detail-value">{format_msat(channel.amt_out_msat)}</span>
                                             </div>
                                             <div class="detail-item">
-                                                <span class="detail-label">{"Frais de base"}</span>
-                                                <span class="detail-value">{format!("{} msats", channel.base_fee)}</span>
+                                                <span class="detail-label">{"Frais apportés en entrée"}</span>
+                                                <span class="detail-value">{format_msat(channel.inbound_fee_msat)}</span>
                                             </div>
                                         </div>
 
                                         <div class="yield-balance">
                                             <div class="balance-bar">
-                                                <div class="balance-fill" style={format!("width: {}%", 
+                                                <div class="balance-fill" style={format!("width: {}%",
                                                     if channel.capacity > 0 {
-                                                        (channel.local_balance as f64 / channel.capacity as f64) * 100.0
+                                                        (channel.capital as f64 / channel.capacity as f64) * 100.0
                                                     } else {
                                                         0.0
                                                     }
                                                 )}></div>
                                             </div>
                                             <div class="balance-labels">
-                                                <span>{"Balance locale"}</span>
-                                                <span>{format!("{} / {}", channel.local_balance, channel.capacity)}</span>
+                                                <span>{"Capital engagé"}</span>
+                                                <span>{format!("{} / {}", channel.capital, channel.capacity)}</span>
                                             </div>
                                         </div>
                                     </div>
-                                    <div class="yield-actions">
-                                        <Button 
-                                            label={"Optimiser les frais".to_string()}
-                                            onclick={Callback::from(|_| {})}
-                                        />
-                                        <Button 
-                                            label={"Voir l'historique".to_string()}
-                                            onclick={Callback::from(|_| {})}
-                                        />
-                                    </div>
                                 </Card>
                             }
                         })}
@@ -826,4 +893,148 @@ pub fn yields_page() -> Html {
             </div>
         </div>
     }
-} 
\ No newline at end of file
+}
+
+/// Frais d'une route en ppm du montant reçu par le destinataire
+fn fee_ppm(route: &RouteQuote) -> u64 {
+    let received = route.amount_msat.saturating_sub(route.fee_msat);
+    if received > 0 {
+        route.fee_msat * 1_000_000 / received
+    } else {
+        0
+    }
+}
+
+#[function_component(RoutesPage)]
+pub fn routes_page() -> Html {
+    let dest = use_state(String::new);
+    let amount = use_state(String::new);
+    let routes = use_state(|| None::<Vec<RouteQuote>>);
+    let error = use_state(|| None::<String>);
+    let loading = use_state(|| false);
+
+    let on_dest_input = {
+        let dest = dest.clone();
+        Callback::from(move |e: InputEvent| {
+            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            dest.set(input.value().trim().to_string());
+        })
+    };
+
+    let on_amount_input = {
+        let amount = amount.clone();
+        Callback::from(move |e: InputEvent| {
+            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            amount.set(input.value());
+        })
+    };
+
+    let on_quote = {
+        let dest = dest.clone();
+        let amount = amount.clone();
+        let routes = routes.clone();
+        let error = error.clone();
+        let loading = loading.clone();
+        Callback::from(move |_| {
+            let Ok(amt) = amount.trim().parse::<u64>() else {
+                error.set(Some("Montant invalide : un nombre de sats est attendu".to_string()));
+                return;
+            };
+            let dest = (*dest).clone();
+            let routes = routes.clone();
+            let error = error.clone();
+            let loading = loading.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                loading.set(true);
+                error.set(None);
+                match fetch_route_quotes(&dest, amt).await {
+                    Ok(quotes) => routes.set(Some(quotes)),
+                    Err(e) => {
+                        routes.set(None);
+                        error.set(Some(format!("Erreur lors de la recherche de routes : {}", e)));
+                    }
+                }
+                loading.set(false);
+            });
+        })
+    };
+
+    html! {
+        <div class="page">
+            <Navbar current_page={"routes".to_string()} />
+            <div class="content">
+                <h1>{"Devis de routes"}</h1>
+
+                <div class="route-form">
+                    <div class="filter-group">
+                        <label>{"Destinataire (pubkey)"}</label>
+                        <input type="text" value={(*dest).clone()} oninput={on_dest_input} />
+                    </div>
+                    <div class="filter-group">
+                        <label>{"Montant (sats)"}</label>
+                        <input type="number" min="1" value={(*amount).clone()} oninput={on_amount_input} />
+                    </div>
+                    <Button label={"Estimer les frais".to_string()} onclick={on_quote} />
+                </div>
+
+                if *loading {
+                    <div class="loading">{"Recherche de routes..."}</div>
+                } else if let Some(err) = &*error {
+                    <div class="error">
+                        <p>{err}</p>
+                    </div>
+                } else if let Some(routes_data) = &*routes {
+                    if routes_data.is_empty() {
+                        <div class="loading">{"Aucune route trouvée vers ce destinataire"}</div>
+                    }
+                    {for routes_data.iter().enumerate().map(|(i, route)| {
+                        html! {
+                            <Card title={format!("Route {}", i + 1)}>
+                                <div class="channels-summary">
+                                    <div class="summary-item">
+                                        <h3>{"Frais totaux"}</h3>
+                                        <p>{format!("{} msats ({} ppm)", route.fee_msat, fee_ppm(route))}</p>
+                                    </div>
+                                    <div class="summary-item">
+                                        <h3>{"Montant envoyé"}</h3>
+                                        <p>{format!("{} msats", route.amount_msat)}</p>
+                                    </div>
+                                    <div class="summary-item">
+                                        <h3>{"Sauts"}</h3>
+                                        <p>{route.hop_count}</p>
+                                    </div>
+                                    <div class="summary-item">
+                                        <h3>{"Verrou temporel"}</h3>
+                                        <p>{format!("{} blocs", route.time_lock)}</p>
+                                    </div>
+                                </div>
+                                <table class="route-hops">
+                                    <thead>
+                                        <tr>
+                                            <th>{"#"}</th>
+                                            <th>{"Nœud"}</th>
+                                            <th>{"Canal"}</th>
+                                            <th>{"Montant reçu"}</th>
+                                            <th>{"Frais"}</th>
+                                        </tr>
+                                    </thead>
+                                    <tbody>
+                                        {for route.hops.iter().enumerate().map(|(j, hop)| html! {
+                                            <tr>
+                                                <td>{j + 1}</td>
+                                                <td class="route-pubkey">{&hop.pubkey}</td>
+                                                <td>{&hop.channel_id}</td>
+                                                <td>{format!("{} msats", hop.amount_msat)}</td>
+                                                <td>{format!("{} msats", hop.fee_msat)}</td>
+                                            </tr>
+                                        })}
+                                    </tbody>
+                                </table>
+                            </Card>
+                        }
+                    })}
+                }
+            </div>
+        </div>
+    }
+}
@@ -1,14 +1,19 @@
 use gloo_net::http::Request;
 use serde::{Deserialize, Serialize};
-use crate::types::{NodeStats, ChannelRecommendation, OutboundLiquidityValue, SuggestedFees, Channel, Recommendation};
-use wasm_bindgen::JsValue;
-use web_sys::console;
+use crate::types::{NodeStats, ChannelRecommendation, OutboundLiquidityValue, SuggestedFees, Channel, Recommendation, NodeSummary, NodeInfo, NodeEvent, NodeEventMessage, RouteQuote, YieldReport};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{console, EventSource, MessageEvent};
+use yew::Callback;
 use std::sync::Once;
 use std::sync::Mutex;
 use std::collections::HashMap;
 
 const NODE_PUBKEY: &str = "02778f4a4eb3a2344b9fd8ee72e7ec5f03f803e5f5273e2e1a2af508910cf2b12b";
 const BASE_URL: &str = "https://api.sparkseer.space";
+const LIGHTDASH_API_URL: &str = "/api";
+const SELECTED_NODE_KEY: &str = "lightdash_selected_node";
+const SELECTED_PUBKEY_KEY: &str = "lightdash_selected_pubkey";
 const OPENAI_API_URL: &str = "https://api.openai.com/v1/chat/completions";
 const DEEPSEEK_API_URL: &str = "https://api.deepseek.com/v1/chat/completions";
 const MAX_RETRIES: u32 = 3;
@@ -78,8 +83,185 @@ fn create_request(url: &str) -> Request {
     request
 }
 
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+/// Identifiant du nœud sélectionné dans le sélecteur de la barre de navigation
+pub fn selected_node_id() -> Option<String> {
+    local_storage()?.get_item(SELECTED_NODE_KEY).ok()?
+}
+
+/// Clé publique du nœud sélectionné, ou celle du nœud par défaut
+fn node_pubkey() -> String {
+    local_storage()
+        .and_then(|storage| storage.get_item(SELECTED_PUBKEY_KEY).ok().flatten())
+        .unwrap_or_else(|| NODE_PUBKEY.to_string())
+}
+
+pub async fn fetch_nodes() -> Result<Vec<NodeSummary>, String> {
+    let url = format!("{}/nodes", LIGHTDASH_API_URL);
+    match Request::get(&url).send().await {
+        Ok(response) => {
+            let status = response.status();
+            match response.json().await {
+                Ok(nodes) => {
+                    log_api_call("nodes", status, None);
+                    Ok(nodes)
+                }
+                Err(e) => {
+                    log_api_call("nodes", status, Some(&e.to_string()));
+                    Err(e.to_string())
+                }
+            }
+        }
+        Err(e) => {
+            log_api_call("nodes", 0, Some(&e.to_string()));
+            Err(e.to_string())
+        }
+    }
+}
+
+/// Sélectionne un nœud : sa clé publique remplace celle utilisée pour Sparkseer
+pub async fn select_node(id: &str) -> Result<(), String> {
+    let url = format!("{}/nodes/{}/info", LIGHTDASH_API_URL, id);
+    let info: NodeInfo = match Request::get(&url).send().await {
+        Ok(response) => {
+            let status = response.status();
+            match response.json().await {
+                Ok(info) => {
+                    log_api_call("node_info", status, None);
+                    info
+                }
+                Err(e) => {
+                    log_api_call("node_info", status, Some(&e.to_string()));
+                    return Err(e.to_string());
+                }
+            }
+        }
+        Err(e) => {
+            log_api_call("node_info", 0, Some(&e.to_string()));
+            return Err(e.to_string());
+        }
+    };
+
+    let storage = local_storage().ok_or_else(|| "Stockage local indisponible".to_string())?;
+    storage
+        .set_item(SELECTED_NODE_KEY, id)
+        .and_then(|_| storage.set_item(SELECTED_PUBKEY_KEY, &info.pubkey))
+        .map_err(|_| "Impossible d'enregistrer le nœud sélectionné".to_string())
+}
+
+/// Devis des routes vers `dest` pour `amt` sats, calculé par le nœud sélectionné
+pub async fn fetch_route_quotes(dest: &str, amt: u64) -> Result<Vec<RouteQuote>, String> {
+    let url = match selected_node_id() {
+        Some(id) => format!("{}/nodes/{}/routes?dest={}&amt={}", LIGHTDASH_API_URL, id, dest, amt),
+        None => format!("{}/routes?dest={}&amt={}", LIGHTDASH_API_URL, dest, amt),
+    };
+    match Request::get(&url).send().await {
+        Ok(response) => {
+            let status = response.status();
+            if !response.ok() {
+                // Les erreurs de l'API sont des messages JSON
+                let message = response.json::<String>().await.unwrap_or_else(|e| e.to_string());
+                log_api_call("routes", status, Some(&message));
+                return Err(message);
+            }
+            match response.json().await {
+                Ok(routes) => {
+                    log_api_call("routes", status, None);
+                    Ok(routes)
+                }
+                Err(e) => {
+                    log_api_call("routes", status, Some(&e.to_string()));
+                    Err(e.to_string())
+                }
+            }
+        }
+        Err(e) => {
+            log_api_call("routes", 0, Some(&e.to_string()));
+            Err(e.to_string())
+        }
+    }
+}
+
+/// Abonnement au flux d'événements du nœud ; la connexion est fermée à sa destruction
+pub struct EventSubscription {
+    source: EventSource,
+    _onmessage: Closure<dyn FnMut(MessageEvent)>,
+}
+
+impl Drop for EventSubscription {
+    fn drop(&mut self) {
+        self.source.close();
+    }
+}
+
+/// Ouvre le flux `/api/events` du nœud sélectionné.
+///
+/// L'`EventSource` du navigateur se reconnecte seul si le serveur redémarre.
+pub fn subscribe_node_events(on_event: Callback<NodeEvent>) -> Result<EventSubscription, String> {
+    let url = match selected_node_id() {
+        Some(id) => format!("{}/events?node={}", LIGHTDASH_API_URL, id),
+        None => format!("{}/events", LIGHTDASH_API_URL),
+    };
+    let source = EventSource::new(&url)
+        .map_err(|_| "Impossible d'ouvrir le flux d'événements".to_string())?;
+
+    let onmessage = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+        let Some(data) = event.data().as_string() else {
+            return;
+        };
+        match serde_json::from_str::<NodeEventMessage>(&data) {
+            Ok(message) => on_event.emit(message.event),
+            Err(e) => console::warn_1(&JsValue::from_str(&format!(
+                "Événement illisible: {}",
+                e
+            ))),
+        }
+    });
+    source.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+
+    Ok(EventSubscription {
+        source,
+        _onmessage: onmessage,
+    })
+}
+
+/// Rendement réalisé des canaux sur `range` (`24h`, `7d`, `30d`...)
+pub async fn fetch_yields(range: &str) -> Result<YieldReport, String> {
+    let url = match selected_node_id() {
+        Some(id) => format!("{}/yields?node={}&range={}", LIGHTDASH_API_URL, id, range),
+        None => format!("{}/yields?range={}", LIGHTDASH_API_URL, range),
+    };
+    match Request::get(&url).send().await {
+        Ok(response) => {
+            let status = response.status();
+            if !response.ok() {
+                let message = response.json::<String>().await.unwrap_or_else(|e| e.to_string());
+                log_api_call("yields", status, Some(&message));
+                return Err(message);
+            }
+            match response.json().await {
+                Ok(report) => {
+                    log_api_call("yields", status, None);
+                    Ok(report)
+                }
+                Err(e) => {
+                    log_api_call("yields", status, Some(&e.to_string()));
+                    Err(e.to_string())
+                }
+            }
+        }
+        Err(e) => {
+            log_api_call("yields", 0, Some(&e.to_string()));
+            Err(e.to_string())
+        }
+    }
+}
+
 pub async fn fetch_node_stats() -> Result<NodeStats, String> {
-    let url = format!("{}/node/{}", BASE_URL, NODE_PUBKEY);
+    let url = format!("{}/node/{}", BASE_URL, node_pubkey());
     console::log_1(&JsValue::from_str(&format!("Fetching node stats from {}", url)));
     
     retry_request(|| Box::pin(async {
@@ -106,7 +288,7 @@ pub async fn fetch_node_stats() -> Result<NodeStats, String> {
 }
 
 pub async fn fetch_channel_recommendations() -> Result<Vec<ChannelRecommendation>, String> {
-    let url = format!("{}/node/{}/channel_recommendations", BASE_URL, NODE_PUBKEY);
+    let url = format!("{}/node/{}/channel_recommendations", BASE_URL, node_pubkey());
     console::log_1(&JsValue::from_str(&format!("Fetching channel recommendations from {}", url)));
     
     retry_request(|| Box::pin(async {
@@ -133,7 +315,7 @@ pub async fn fetch_channel_recommendations() -> Result<Vec<ChannelRecommendation
 }
 
 pub async fn fetch_outbound_liquidity_value() -> Result<OutboundLiquidityValue, String> {
-    let url = format!("{}/node/{}/outbound_liquidity_value", BASE_URL, NODE_PUBKEY);
+    let url = format!("{}/node/{}/outbound_liquidity_value", BASE_URL, node_pubkey());
     console::log_1(&JsValue::from_str(&format!("Fetching outbound liquidity value from {}", url)));
     
     retry_request(|| Box::pin(async {
@@ -160,7 +342,7 @@ pub async fn fetch_outbound_liquidity_value() -> Result<OutboundLiquidityValue,
 }
 
 pub async fn fetch_suggested_fees() -> Result<SuggestedFees, String> {
-    let url = format!("{}/node/{}/suggested_fees", BASE_URL, NODE_PUBKEY);
+    let url = format!("{}/node/{}/suggested_fees", BASE_URL, node_pubkey());
     console::log_1(&JsValue::from_str(&format!("Fetching suggested fees from {}", url)));
     
     retry_request(|| Box::pin(async {
@@ -187,7 +369,7 @@ pub async fn fetch_suggested_fees() -> Result<SuggestedFees, String> {
 }
 
 pub async fn fetch_channels() -> Result<Vec<Channel>, String> {
-    let url = format!("{}/node/{}/channels", BASE_URL, NODE_PUBKEY);
+    let url = format!("{}/node/{}/channels", BASE_URL, node_pubkey());
     console::log_1(&JsValue::from_str(&format!("Fetching channels from {}", url)));
     
     retry_request(|| Box::pin(async {
@@ -129,4 +129,145 @@ pub enum Priority {
     High,
     Medium,
     Low,
-} 
\ No newline at end of file
+} 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeSummary {
+    pub id: String,
+    pub name: String,
+    pub backend: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeInfo {
+    pub pubkey: String,
+    pub alias: String,
+    pub capacity: u64,
+    pub channels: u32,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TransactionStatus {
+    Completed,
+    Pending,
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForwardingEvent {
+    pub timestamp: i64,
+    pub chan_id_in: String,
+    pub chan_id_out: String,
+    pub amt_in_msat: u64,
+    pub amt_out_msat: u64,
+    pub fee_msat: u64,
+}
+
+/// Événement reçu en direct depuis `/api/events`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NodeEvent {
+    InvoiceSettled {
+        payment_hash: String,
+        amount: u64,
+    },
+    ChannelOpened {
+        channel_id: String,
+        remote_pubkey: String,
+        capacity: u64,
+    },
+    ChannelClosed {
+        channel_id: String,
+    },
+    ChannelActive {
+        channel_id: String,
+    },
+    ChannelInactive {
+        channel_id: String,
+    },
+    HtlcForwarded(ForwardingEvent),
+    PaymentUpdated {
+        payment_hash: String,
+        status: TransactionStatus,
+        amount: u64,
+        fee: u64,
+    },
+}
+
+impl NodeEvent {
+    /// Vrai si l'événement modifie la liste ou l'état des canaux
+    pub fn affects_channels(&self) -> bool {
+        matches!(
+            self,
+            NodeEvent::ChannelOpened { .. }
+                | NodeEvent::ChannelClosed { .. }
+                | NodeEvent::ChannelActive { .. }
+                | NodeEvent::ChannelInactive { .. }
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeEventMessage {
+    pub node: String,
+    pub timestamp: i64,
+    #[serde(flatten)]
+    pub event: NodeEvent,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RouteHop {
+    pub pubkey: String,
+    pub channel_id: String,
+    pub amount_msat: u64,
+    pub fee_msat: u64,
+}
+
+/// Route candidate renvoyée par `/api/routes`, frais compris
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RouteQuote {
+    pub hops: Vec<RouteHop>,
+    pub hop_count: usize,
+    pub amount_msat: u64,
+    pub fee_msat: u64,
+    pub time_lock: u32,
+}
+
+/// Rendement réalisé d'un canal, renvoyé par `/api/yields`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChannelYield {
+    pub channel_id: String,
+    pub short_channel_id: Option<String>,
+    pub remote_pubkey: String,
+    pub active: bool,
+    pub capacity: u64,
+    pub capital: u64,
+    pub fee_msat: u64,
+    pub inbound_fee_msat: u64,
+    pub forwards: u64,
+    pub amt_out_msat: u64,
+    pub annualized_return: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PeerYield {
+    pub remote_pubkey: String,
+    pub channels: u32,
+    pub capital: u64,
+    pub fee_msat: u64,
+    pub forwards: u64,
+    pub annualized_return: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct YieldReport {
+    pub from: i64,
+    pub to: i64,
+    pub capital: u64,
+    pub fee_msat: u64,
+    pub closed_channels_fee_msat: u64,
+    pub annualized_return: Option<f64>,
+    pub channels: Vec<ChannelYield>,
+    pub peers: Vec<PeerYield>,
+}
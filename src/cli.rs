@@ -0,0 +1,55 @@
+use anyhow::{bail, Context, Result};
+use std::io::Write;
+
+use crate::models::export::ExportRequest;
+use crate::services::export::{self, DEFAULT_EXPORT_RANGE};
+use crate::services::history::resolve_window;
+use crate::services::prices::PriceClient;
+use crate::services::registry::NodeRegistry;
+
+const EXPORT_USAGE: &str = "Usage: lightdash_rust export <csv|koinly|bip329> [--node ID] \
+[--range 30d | --from TIMESTAMP --to TIMESTAMP] [--fiat EUR] [--output FICHIER]";
+
+/// Commande `export` : écrit l'export demandé dans un fichier, ou sur la sortie standard
+pub async fn export(args: &[String], registry: &NodeRegistry, prices: &PriceClient) -> Result<()> {
+    let mut args = args.iter();
+    let format = args
+        .next()
+        .context(EXPORT_USAGE)?
+        .parse()
+        .map_err(anyhow::Error::msg)?;
+
+    let (mut node, mut range, mut from, mut to, mut currency, mut output) =
+        (None, None, None, None, None, None);
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .with_context(|| format!("Valeur manquante pour {}\n{}", flag, EXPORT_USAGE))?;
+        match flag.as_str() {
+            "--node" => node = Some(value.clone()),
+            "--range" => range = Some(value.clone()),
+            "--from" => from = Some(value.parse().context("--from attend un timestamp Unix")?),
+            "--to" => to = Some(value.parse().context("--to attend un timestamp Unix")?),
+            "--fiat" => currency = Some(value.to_ascii_uppercase()),
+            "--output" => output = Some(value.clone()),
+            _ => bail!("Option inconnue: {}\n{}", flag, EXPORT_USAGE),
+        }
+    }
+    let (from, to) =
+        resolve_window(&range, from, to, DEFAULT_EXPORT_RANGE).map_err(anyhow::Error::msg)?;
+
+    let request = ExportRequest {
+        format,
+        node,
+        from,
+        to,
+        currency,
+    };
+    let body = export::export(registry, prices, &request).await?;
+    match output {
+        Some(path) => std::fs::write(&path, body)
+            .with_context(|| format!("Écriture impossible: {}", path))?,
+        None => std::io::stdout().write_all(body.as_bytes())?,
+    }
+    Ok(())
+}
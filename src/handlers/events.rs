@@ -0,0 +1,132 @@
+use actix_web::http::header;
+use actix_web::web::Bytes;
+use actix_web::{rt, web, HttpRequest, HttpResponse, Responder};
+use actix_ws::Message;
+use serde::Deserialize;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::wrappers::{BroadcastStream, IntervalStream};
+use tokio_stream::StreamExt;
+use tracing::{info, instrument, warn};
+
+use crate::models::lightning::NodeEventMessage;
+use crate::services::registry::NodeRegistry;
+
+/// Intervalle des commentaires SSE qui empêchent les proxys de couper la connexion
+const SSE_KEEPALIVE: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Deserialize)]
+pub struct EventsQuery {
+    /// Limite le flux aux événements d'un seul nœud
+    node: Option<String>,
+}
+
+fn is_wanted(node: &Option<String>, message: &NodeEventMessage) -> bool {
+    node.as_ref().is_none_or(|id| *id == message.node)
+}
+
+/// Flux d'événements des nœuds.
+///
+/// Servi en WebSocket si le client demande une mise à niveau du protocole,
+/// en Server-Sent Events sinon.
+#[instrument(skip(req, body, registry))]
+pub async fn stream_events(
+    req: HttpRequest,
+    body: web::Payload,
+    registry: web::Data<NodeRegistry>,
+    query: web::Query<EventsQuery>,
+) -> impl Responder {
+    let node = query.into_inner().node;
+    if let Some(id) = &node {
+        if registry.get(id).await.is_none() {
+            return HttpResponse::NotFound().json(format!("Nœud inconnu: {}", id));
+        }
+    }
+
+    let websocket = req
+        .headers()
+        .get(header::UPGRADE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.eq_ignore_ascii_case("websocket"));
+    if websocket {
+        websocket_events(&req, body, &registry, node)
+    } else {
+        sse_events(&registry, node)
+    }
+}
+
+fn sse_events(registry: &NodeRegistry, node: Option<String>) -> HttpResponse {
+    info!("Nouvel abonné SSE");
+    let events =
+        BroadcastStream::new(registry.events().subscribe()).filter_map(
+            move |message| match message {
+                Ok(message) if is_wanted(&node, &message) => {
+                    serde_json::to_string(&message).ok().map(|json| {
+                        Ok::<_, actix_web::Error>(Bytes::from(format!("data: {}\n\n", json)))
+                    })
+                }
+                Ok(_) => None,
+                Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                    warn!(skipped = skipped, "Abonné SSE trop lent, événements perdus");
+                    None
+                }
+            },
+        );
+    let keepalive = IntervalStream::new(tokio::time::interval(SSE_KEEPALIVE))
+        .map(|_| Ok(Bytes::from_static(b": keepalive\n\n")));
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .streaming(events.merge(keepalive))
+}
+
+fn websocket_events(
+    req: &HttpRequest,
+    body: web::Payload,
+    registry: &NodeRegistry,
+    node: Option<String>,
+) -> HttpResponse {
+    let (response, mut session, mut messages) = match actix_ws::handle(req, body) {
+        Ok(handshake) => handshake,
+        Err(e) => return e.error_response(),
+    };
+    info!("Nouvel abonné WebSocket");
+
+    let mut events = registry.events().subscribe();
+    rt::spawn(async move {
+        loop {
+            tokio::select! {
+                event = events.recv() => match event {
+                    Ok(message) if is_wanted(&node, &message) => {
+                        let Ok(json) = serde_json::to_string(&message) else {
+                            continue;
+                        };
+                        if session.text(json).await.is_err() {
+                            break;
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!(skipped = skipped, "Abonné WebSocket trop lent, événements perdus");
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+                message = messages.next() => match message {
+                    Some(Ok(Message::Ping(bytes))) => {
+                        if session.pong(&bytes).await.is_err() {
+                            break;
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => {}
+                },
+            }
+        }
+        info!("Abonné WebSocket déconnecté");
+        let _ = session.close(None).await;
+    });
+
+    response
+}
@@ -0,0 +1,83 @@
+use actix_web::{web, HttpResponse, Responder};
+use serde::Deserialize;
+use tracing::{error, info, instrument};
+
+use crate::handlers::lightning::{bad_request, node_error};
+use crate::models::export::{ExportFormat, ExportRequest};
+use crate::services::export::{self, DEFAULT_EXPORT_RANGE};
+use crate::services::history::resolve_window;
+use crate::services::prices::{PriceClient, FIAT_CURRENCIES};
+use crate::services::registry::NodeRegistry;
+
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    /// Nœud exporté, tous les nœuds si absent
+    node: Option<String>,
+    range: Option<String>,
+    from: Option<i64>,
+    to: Option<i64>,
+    /// Devise de valorisation (`EUR`, `USD`...), sans colonne fiat si absente
+    fiat: Option<String>,
+}
+
+#[instrument(skip(registry, prices))]
+pub async fn export_ledger(
+    registry: web::Data<NodeRegistry>,
+    prices: web::Data<PriceClient>,
+    path: web::Path<String>,
+    query: web::Query<ExportQuery>,
+) -> impl Responder {
+    info!("Traitement de la requête export_ledger");
+    let format: ExportFormat = match path.parse() {
+        Ok(format) => format,
+        Err(message) => return bad_request(message),
+    };
+    let (from, to) =
+        match resolve_window(&query.range, query.from, query.to, DEFAULT_EXPORT_RANGE) {
+            Ok(window) => window,
+            Err(message) => return bad_request(message),
+        };
+    let currency = query.fiat.as_ref().map(|c| c.to_ascii_uppercase());
+    if let Some(currency) = currency.as_deref().filter(|c| !FIAT_CURRENCIES.contains(c)) {
+        return bad_request(format!(
+            "Devise non prise en charge: {} (attendu {})",
+            currency,
+            FIAT_CURRENCIES.join(", ")
+        ));
+    }
+    if let Some(node) = &query.node {
+        if registry.get(node).await.is_none() {
+            return HttpResponse::NotFound().json(format!("Nœud inconnu: {}", node));
+        }
+    }
+    let request = ExportRequest {
+        format,
+        node: query.node.clone(),
+        from,
+        to,
+        currency,
+    };
+
+    match export::export(&registry, &prices, &request).await {
+        Ok(body) => {
+            let filename = format!(
+                "lightdash-{}-{}-{}.{}",
+                request.node.as_deref().unwrap_or("all"),
+                from,
+                to,
+                format.extension()
+            );
+            HttpResponse::Ok()
+                .content_type(format.content_type())
+                .insert_header((
+                    "Content-Disposition",
+                    format!("attachment; filename=\"{}\"", filename),
+                ))
+                .body(body)
+        }
+        Err(e) => {
+            error!(error = %e, "Erreur lors de l'export comptable");
+            node_error(e)
+        }
+    }
+}
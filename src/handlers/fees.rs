@@ -0,0 +1,48 @@
+use actix_web::{web, HttpResponse, Responder};
+use serde::Deserialize;
+use tracing::{error, info, instrument};
+
+use crate::handlers::history::resolve_node;
+use crate::handlers::lightning::bad_request;
+use crate::services::history::resolve_window;
+use crate::services::registry::NodeRegistry;
+
+/// Fenêtre de l'historique quand ni `range` ni `from` ne sont précisés
+const DEFAULT_FEE_CHANGES_RANGE: &str = "30d";
+
+#[derive(Debug, Deserialize)]
+pub struct FeeChangesQuery {
+    node: Option<String>,
+    range: Option<String>,
+    from: Option<i64>,
+    to: Option<i64>,
+}
+
+/// Modifications de frais du gestionnaire automatique, appliquées ou simulées
+#[instrument(skip(registry))]
+pub async fn list_fee_changes(
+    registry: web::Data<NodeRegistry>,
+    query: web::Query<FeeChangesQuery>,
+) -> impl Responder {
+    info!("Traitement de la requête list_fee_changes");
+    let (from, to) =
+        match resolve_window(&query.range, query.from, query.to, DEFAULT_FEE_CHANGES_RANGE) {
+            Ok(window) => window,
+            Err(message) => return bad_request(message),
+        };
+    let node = match resolve_node(&registry, &query.node).await {
+        Ok(node) => node,
+        Err(response) => return response,
+    };
+
+    match registry.history().fee_changes(&node, from, to).await {
+        Ok(changes) => {
+            info!(count = changes.len(), "Historique des frais envoyé");
+            HttpResponse::Ok().json(changes)
+        }
+        Err(e) => {
+            error!(error = %e, "Erreur lors de la lecture des modifications de frais");
+            HttpResponse::InternalServerError().json(format!("Erreur: {}", e))
+        }
+    }
+}
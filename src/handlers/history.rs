@@ -0,0 +1,164 @@
+use actix_web::{web, HttpResponse, Responder};
+use serde::Deserialize;
+use tracing::{error, info, instrument};
+
+use crate::handlers::lightning::bad_request;
+use crate::models::history::HistoryRange;
+use crate::services::history::resolve_window;
+use crate::services::registry::NodeRegistry;
+
+/// Fenêtre renvoyée quand ni `range` ni `from` ne sont précisés
+const DEFAULT_HISTORY_RANGE: &str = "24h";
+const DEFAULT_HISTORY_POINTS: u32 = 200;
+const MAX_HISTORY_POINTS: u32 = 2000;
+const DEFAULT_FORWARDS_RANGE: &str = "30d";
+const DEFAULT_FORWARDS_LIMIT: u32 = 1000;
+const MAX_FORWARDS_LIMIT: u32 = 10_000;
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    /// Nœud concerné, le nœud par défaut si absent
+    node: Option<String>,
+    /// Fenêtre glissante terminée maintenant : `24h`, `7d`, `30d`...
+    range: Option<String>,
+    /// Bornes explicites en secondes Unix, prioritaires sur `range`
+    from: Option<i64>,
+    to: Option<i64>,
+    points: Option<u32>,
+}
+
+impl HistoryQuery {
+    fn resolve(&self) -> Result<HistoryRange, String> {
+        let (from, to) = resolve_window(&self.range, self.from, self.to, DEFAULT_HISTORY_RANGE)?;
+        let points = self.points.unwrap_or(DEFAULT_HISTORY_POINTS);
+        if points == 0 || points > MAX_HISTORY_POINTS {
+            return Err(format!(
+                "Le nombre de points doit être compris entre 1 et {}",
+                MAX_HISTORY_POINTS
+            ));
+        }
+        Ok(HistoryRange { from, to, points })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ForwardsQuery {
+    node: Option<String>,
+    range: Option<String>,
+    from: Option<i64>,
+    to: Option<i64>,
+    /// Limite aux relais entrés ou sortis par ce canal
+    channel: Option<String>,
+    /// Nombre maximal de relais listés ; les totaux couvrent toute la fenêtre
+    limit: Option<u32>,
+}
+
+/// Nœud visé par la requête, ou la réponse d'erreur à renvoyer
+pub(crate) async fn resolve_node(
+    registry: &NodeRegistry,
+    node: &Option<String>,
+) -> Result<String, HttpResponse> {
+    match node {
+        Some(id) if registry.get(id).await.is_some() => Ok(id.clone()),
+        Some(id) => Err(HttpResponse::NotFound().json(format!("Nœud inconnu: {}", id))),
+        None => registry
+            .default_node_id()
+            .await
+            .ok_or_else(|| HttpResponse::NotFound().json("Aucun nœud configuré")),
+    }
+}
+
+#[instrument(skip(registry))]
+pub async fn node_history(
+    registry: web::Data<NodeRegistry>,
+    query: web::Query<HistoryQuery>,
+) -> impl Responder {
+    info!("Traitement de la requête node_history");
+    let range = match query.resolve() {
+        Ok(range) => range,
+        Err(message) => return bad_request(message),
+    };
+    let node = match resolve_node(&registry, &query.node).await {
+        Ok(node) => node,
+        Err(response) => return response,
+    };
+
+    match registry.history().node_history(&node, range).await {
+        Ok(snapshots) => {
+            info!(count = snapshots.len(), "Historique du nœud envoyé");
+            HttpResponse::Ok().json(snapshots)
+        }
+        Err(e) => {
+            error!(error = %e, "Erreur lors de la lecture de l'historique du nœud");
+            HttpResponse::InternalServerError().json(format!("Erreur: {}", e))
+        }
+    }
+}
+
+#[instrument(skip(registry))]
+pub async fn channel_history(
+    registry: web::Data<NodeRegistry>,
+    path: web::Path<String>,
+    query: web::Query<HistoryQuery>,
+) -> impl Responder {
+    info!("Traitement de la requête channel_history");
+    let range = match query.resolve() {
+        Ok(range) => range,
+        Err(message) => return bad_request(message),
+    };
+    let node = match resolve_node(&registry, &query.node).await {
+        Ok(node) => node,
+        Err(response) => return response,
+    };
+
+    match registry.history().channel_history(&node, &path, range).await {
+        Ok(Some(snapshots)) => {
+            info!(count = snapshots.len(), "Historique du canal envoyé");
+            HttpResponse::Ok().json(snapshots)
+        }
+        Ok(None) => HttpResponse::NotFound().json(format!("Canal sans historique: {}", path)),
+        Err(e) => {
+            error!(error = %e, "Erreur lors de la lecture de l'historique du canal");
+            HttpResponse::InternalServerError().json(format!("Erreur: {}", e))
+        }
+    }
+}
+
+#[instrument(skip(registry))]
+pub async fn list_forwards(
+    registry: web::Data<NodeRegistry>,
+    query: web::Query<ForwardsQuery>,
+) -> impl Responder {
+    info!("Traitement de la requête list_forwards");
+    let (from, to) =
+        match resolve_window(&query.range, query.from, query.to, DEFAULT_FORWARDS_RANGE) {
+            Ok(window) => window,
+            Err(message) => return bad_request(message),
+        };
+    let limit = query.limit.unwrap_or(DEFAULT_FORWARDS_LIMIT);
+    if limit > MAX_FORWARDS_LIMIT {
+        return bad_request(format!(
+            "Le nombre de relais listés ne peut dépasser {}",
+            MAX_FORWARDS_LIMIT
+        ));
+    }
+    let node = match resolve_node(&registry, &query.node).await {
+        Ok(node) => node,
+        Err(response) => return response,
+    };
+
+    match registry
+        .history()
+        .forwards(&node, from, to, query.channel.clone(), limit)
+        .await
+    {
+        Ok(report) => {
+            info!(count = report.count, fee_msat = report.total_fee_msat, "Relais envoyés");
+            HttpResponse::Ok().json(report)
+        }
+        Err(e) => {
+            error!(error = %e, "Erreur lors de la lecture des relais");
+            HttpResponse::InternalServerError().json(format!("Erreur: {}", e))
+        }
+    }
+}
@@ -1,12 +1,115 @@
 use actix_web::{web, HttpResponse, Responder};
-use serde_json::json;
-use tracing::{instrument, info, error};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use tracing::{instrument, info, error, warn};
 
-use crate::services::lightning::LightningService;
-use crate::models::lightning::{Channel, NodeInfo, Transaction};
+use crate::handlers::validation::{
+    validate_amount, validate_batch_open, validate_channel_id, validate_channel_request,
+    validate_close_options, validate_custom_records, validate_funding_id, validate_invoice,
+    validate_offer, validate_payment_options, validate_policy_update, validate_psbt,
+    validate_pubkey, MAX_CHANNEL_SIZE, MAX_DESCRIPTION_LEN, MAX_INVOICE_AMOUNT, MAX_INVOICE_EXPIRY,
+    MAX_PAYMENT_AMOUNT, MIN_CHANNEL_SIZE, MIN_INVOICE_EXPIRY,
+};
+use crate::services::backends::Unsupported;
+use crate::services::{bolt11, bolt12};
+use crate::handlers::nodes::NodeService;
+use crate::models::lightning::{
+    BatchOpenRequest, ChannelRequest, CloseOptions, DecodedBolt12, PaymentOptions, PolicyUpdate,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct SendPaymentBody {
+    pub payment_request: String,
+    /// Budget en sats pour ce paiement, plafonné à `MAX_PAYMENT_AMOUNT`
+    pub max_amount: Option<u64>,
+    #[serde(flatten)]
+    pub options: PaymentOptions,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct KeysendBody {
+    pub pubkey: String,
+    pub amount: u64,
+    pub message: Option<String>,
+    /// Enregistrements TLV supplémentaires, valeurs en hexadécimal
+    #[serde(default)]
+    pub custom_records: BTreeMap<u64, String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DecodeInvoiceBody {
+    pub payment_request: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateInvoiceBody {
+    pub amount: u64,
+    #[serde(default)]
+    pub description: String,
+    pub expiry: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateOfferBody {
+    /// Montant en sats ; absent pour une offre à montant libre
+    pub amount: Option<u64>,
+    #[serde(default)]
+    pub description: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DecodeOfferBody {
+    pub bolt12: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PayOfferBody {
+    pub offer: String,
+    /// Montant en sats, requis si l'offre n'en fixe pas
+    pub amount: Option<u64>,
+    pub payer_note: Option<String>,
+    /// Budget en sats pour ce paiement, plafonné à `MAX_PAYMENT_AMOUNT`
+    pub max_amount: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RoutesQuery {
+    /// Clé publique du destinataire
+    pub dest: String,
+    /// Montant en sats
+    pub amt: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OpenChannelBody {
+    pub pubkey: String,
+    #[serde(alias = "amount")]
+    pub capacity: u64,
+}
+
+/// PSBT en base64, financée ou signée par le portefeuille externe
+#[derive(Debug, Deserialize)]
+pub struct PsbtBody {
+    pub psbt: String,
+}
+
+pub(crate) fn bad_request(message: String) -> HttpResponse {
+    warn!(error = %message, "Requête invalide");
+    HttpResponse::BadRequest().json(format!("Requête invalide: {}", message))
+}
+
+/// 501 quand le backend du nœud ne propose pas l'opération, 500 sinon
+pub(crate) fn node_error(e: anyhow::Error) -> HttpResponse {
+    match e.downcast_ref::<Unsupported>() {
+        Some(unsupported) => {
+            HttpResponse::NotImplemented().json(format!("Erreur: {}", unsupported))
+        }
+        None => HttpResponse::InternalServerError().json(format!("Erreur: {}", e)),
+    }
+}
 
 #[instrument(skip(service))]
-pub async fn get_node_info(service: web::Data<LightningService>) -> impl Responder {
+pub async fn get_node_info(service: NodeService) -> impl Responder {
     info!("Traitement de la requête get_node_info");
     match service.get_node_info().await {
         Ok(info) => {
@@ -21,7 +124,19 @@ pub async fn get_node_info(service: web::Data<LightningService>) -> impl Respond
 }
 
 #[instrument(skip(service))]
-pub async fn list_channels(service: web::Data<LightningService>) -> impl Responder {
+pub async fn list_pending_channels(service: NodeService) -> impl Responder {
+    info!("Traitement de la requête list_pending_channels");
+    match service.list_pending_channels().await {
+        Ok(pending) => HttpResponse::Ok().json(pending),
+        Err(e) => {
+            error!(error = %e, "Erreur lors de la récupération des canaux en attente");
+            node_error(e)
+        }
+    }
+}
+
+#[instrument(skip(service))]
+pub async fn list_channels(service: NodeService) -> impl Responder {
     info!("Traitement de la requête list_channels");
     match service.list_channels().await {
         Ok(channels) => {
@@ -36,7 +151,31 @@ pub async fn list_channels(service: web::Data<LightningService>) -> impl Respond
 }
 
 #[instrument(skip(service))]
-pub async fn list_transactions(service: web::Data<LightningService>) -> impl Responder {
+pub async fn query_routes(
+    service: NodeService,
+    query: web::Query<RoutesQuery>,
+) -> impl Responder {
+    info!("Traitement de la requête query_routes");
+    if let Err(e) = validate_pubkey(&query.dest)
+        .and_then(|_| validate_amount(query.amt, 1, MAX_PAYMENT_AMOUNT))
+    {
+        return bad_request(e);
+    }
+
+    match service.query_routes(&query.dest, query.amt).await {
+        Ok(routes) => {
+            info!(count = routes.len(), "Routes envoyées");
+            HttpResponse::Ok().json(routes)
+        }
+        Err(e) => {
+            error!(error = %e, "Erreur lors de la recherche de routes");
+            node_error(e)
+        }
+    }
+}
+
+#[instrument(skip(service))]
+pub async fn list_transactions(service: NodeService) -> impl Responder {
     info!("Traitement de la requête list_transactions");
     match service.list_transactions().await {
         Ok(transactions) => {
@@ -51,7 +190,7 @@ pub async fn list_transactions(service: web::Data<LightningService>) -> impl Res
 }
 
 #[instrument(skip(service))]
-pub async fn get_network_stats(service: web::Data<LightningService>) -> impl Responder {
+pub async fn get_network_stats(service: NodeService) -> impl Responder {
     info!("Traitement de la requête get_network_stats");
     match service.get_network_stats().await {
         Ok(stats) => {
@@ -63,4 +202,440 @@ pub async fn get_network_stats(service: web::Data<LightningService>) -> impl Res
             HttpResponse::InternalServerError().json(format!("Erreur: {}", e))
         }
     }
-} 
\ No newline at end of file
+}
+
+#[instrument(skip(service, body))]
+pub async fn send_payment(
+    service: NodeService,
+    body: web::Json<SendPaymentBody>,
+) -> impl Responder {
+    info!("Traitement de la requête send_payment");
+    let payment_request = bolt11::strip_scheme(&body.payment_request);
+    let invoice = match bolt11::decode(payment_request) {
+        Ok(invoice) => invoice,
+        Err(e) => return bad_request(format!("{:#}", e)),
+    };
+    let network = service.network().await.unwrap_or_else(|e| {
+        warn!(error = %e, "Réseau du nœud indisponible, contrôle ignoré");
+        None
+    });
+    let budget = body
+        .max_amount
+        .map_or(MAX_PAYMENT_AMOUNT, |max| max.min(MAX_PAYMENT_AMOUNT));
+    if let Err(e) = validate_invoice(&invoice, network, budget)
+        .and_then(|_| validate_payment_options(&body.options))
+    {
+        return bad_request(e);
+    }
+
+    match service.send_payment(payment_request, &body.options).await {
+        Ok(result) => {
+            info!(payment_hash = %result.payment_hash, "Résultat du paiement envoyé");
+            HttpResponse::Ok().json(result)
+        }
+        Err(e) => {
+            error!(error = %e, "Erreur lors de l'envoi du paiement");
+            node_error(e)
+        }
+    }
+}
+
+#[instrument(skip(service, body))]
+pub async fn send_keysend(
+    service: NodeService,
+    body: web::Json<KeysendBody>,
+) -> impl Responder {
+    info!("Traitement de la requête send_keysend");
+    if let Err(e) = validate_pubkey(&body.pubkey)
+        .and_then(|_| validate_amount(body.amount, 1, MAX_PAYMENT_AMOUNT))
+    {
+        return bad_request(e);
+    }
+    let custom_records = match validate_custom_records(&body.custom_records, body.message.as_deref())
+    {
+        Ok(records) => records,
+        Err(e) => return bad_request(e),
+    };
+
+    match service
+        .send_keysend(&body.pubkey, body.amount, custom_records, body.message.as_deref())
+        .await
+    {
+        Ok(result) => {
+            info!(payment_hash = %result.payment_hash, "Résultat du keysend envoyé");
+            HttpResponse::Ok().json(result)
+        }
+        Err(e) => {
+            error!(error = %e, "Erreur lors de l'envoi du keysend");
+            node_error(e)
+        }
+    }
+}
+
+#[instrument(skip(body))]
+pub async fn decode_invoice(body: web::Json<DecodeInvoiceBody>) -> impl Responder {
+    info!("Traitement de la requête decode_invoice");
+    match bolt11::decode(&body.payment_request) {
+        Ok(invoice) => {
+            info!(payment_hash = %invoice.payment_hash, "Facture décodée envoyée");
+            HttpResponse::Ok().json(invoice)
+        }
+        Err(e) => bad_request(format!("{:#}", e)),
+    }
+}
+
+#[instrument(skip(service, body))]
+pub async fn create_invoice(
+    service: NodeService,
+    body: web::Json<CreateInvoiceBody>,
+) -> impl Responder {
+    info!("Traitement de la requête create_invoice");
+    if let Err(e) = validate_amount(body.amount, 1, MAX_INVOICE_AMOUNT) {
+        return bad_request(e);
+    }
+    if body.description.len() > MAX_DESCRIPTION_LEN {
+        return bad_request(format!(
+            "Description trop longue ({} caractères maximum)",
+            MAX_DESCRIPTION_LEN
+        ));
+    }
+    if let Some(expiry) = body.expiry {
+        if !(MIN_INVOICE_EXPIRY..=MAX_INVOICE_EXPIRY).contains(&expiry) {
+            return bad_request(format!(
+                "Expiration invalide: {} s (attendu entre {} et {})",
+                expiry, MIN_INVOICE_EXPIRY, MAX_INVOICE_EXPIRY
+            ));
+        }
+    }
+
+    match service
+        .create_invoice(body.amount, &body.description, body.expiry)
+        .await
+    {
+        Ok(invoice) => {
+            info!(payment_hash = %invoice.payment_hash, "Facture envoyée");
+            HttpResponse::Created().json(invoice)
+        }
+        Err(e) => {
+            error!(error = %e, "Erreur lors de la création de la facture");
+            HttpResponse::InternalServerError().json(format!("Erreur: {}", e))
+        }
+    }
+}
+
+#[instrument(skip(service, body))]
+pub async fn create_offer(
+    service: NodeService,
+    body: web::Json<CreateOfferBody>,
+) -> impl Responder {
+    info!("Traitement de la requête create_offer");
+    if let Some(amount) = body.amount {
+        if let Err(e) = validate_amount(amount, 1, MAX_INVOICE_AMOUNT) {
+            return bad_request(e);
+        }
+        if body.description.is_empty() {
+            return bad_request("Description requise pour une offre avec montant".to_string());
+        }
+    }
+    if body.description.len() > MAX_DESCRIPTION_LEN {
+        return bad_request(format!(
+            "Description trop longue ({} caractères maximum)",
+            MAX_DESCRIPTION_LEN
+        ));
+    }
+
+    match service.create_offer(body.amount, &body.description).await {
+        Ok(offer) => {
+            info!(offer_id = ?offer.offer_id, "Offre envoyée");
+            HttpResponse::Created().json(offer)
+        }
+        Err(e) => {
+            error!(error = %e, "Erreur lors de la création de l'offre");
+            node_error(e)
+        }
+    }
+}
+
+#[instrument(skip(body))]
+pub async fn decode_offer(body: web::Json<DecodeOfferBody>) -> impl Responder {
+    info!("Traitement de la requête decode_offer");
+    match bolt12::decode(&body.bolt12) {
+        Ok(decoded) => {
+            info!("Chaîne BOLT12 décodée envoyée");
+            HttpResponse::Ok().json(decoded)
+        }
+        Err(e) => bad_request(format!("{:#}", e)),
+    }
+}
+
+#[instrument(skip(service, body))]
+pub async fn pay_offer(
+    service: NodeService,
+    body: web::Json<PayOfferBody>,
+) -> impl Responder {
+    info!("Traitement de la requête pay_offer");
+    let offer = bolt11::strip_scheme(&body.offer);
+    let decoded = match bolt12::decode(offer) {
+        Ok(DecodedBolt12::Offer(decoded)) => decoded,
+        Ok(DecodedBolt12::Invoice(_)) => {
+            return bad_request("Une offre BOLT12 (lno1...) est attendue".to_string())
+        }
+        Err(e) => return bad_request(format!("{:#}", e)),
+    };
+    let network = service.network().await.unwrap_or_else(|e| {
+        warn!(error = %e, "Réseau du nœud indisponible, contrôle ignoré");
+        None
+    });
+    let budget = body
+        .max_amount
+        .map_or(MAX_PAYMENT_AMOUNT, |max| max.min(MAX_PAYMENT_AMOUNT));
+    if let Err(e) = validate_offer(&decoded, network, body.amount, budget) {
+        return bad_request(e);
+    }
+    if let Some(note) = &body.payer_note {
+        if note.len() > MAX_DESCRIPTION_LEN {
+            return bad_request(format!(
+                "Note trop longue ({} caractères maximum)",
+                MAX_DESCRIPTION_LEN
+            ));
+        }
+    }
+
+    match service
+        .pay_offer(offer, body.amount, body.payer_note.as_deref())
+        .await
+    {
+        Ok(result) => {
+            info!(payment_hash = %result.payment_hash, "Résultat du paiement envoyé");
+            HttpResponse::Ok().json(result)
+        }
+        Err(e) => {
+            error!(error = %e, "Erreur lors du paiement de l'offre");
+            node_error(e)
+        }
+    }
+}
+
+#[instrument(skip(service, body))]
+pub async fn open_channel(
+    service: NodeService,
+    body: web::Json<OpenChannelBody>,
+) -> impl Responder {
+    info!("Traitement de la requête open_channel");
+    if let Err(e) = validate_pubkey(&body.pubkey)
+        .and_then(|_| validate_amount(body.capacity, MIN_CHANNEL_SIZE, MAX_CHANNEL_SIZE))
+    {
+        return bad_request(e);
+    }
+
+    match service.open_channel(&body.pubkey, body.capacity).await {
+        Ok(result) => {
+            info!(funding_txid = %result.funding_txid, "Ouverture du canal envoyée");
+            HttpResponse::Created().json(result)
+        }
+        Err(e) => {
+            error!(error = %e, "Erreur lors de l'ouverture du canal");
+            HttpResponse::InternalServerError().json(format!("Erreur: {}", e))
+        }
+    }
+}
+
+#[instrument(skip(service, body))]
+pub async fn open_channels(
+    service: NodeService,
+    body: web::Json<BatchOpenRequest>,
+) -> impl Responder {
+    info!("Traitement de la requête open_channels");
+    if let Err(e) = validate_batch_open(&body) {
+        return bad_request(e);
+    }
+
+    match service.open_channels(&body).await {
+        Ok(results) => {
+            info!(count = results.len(), "Ouverture groupée envoyée");
+            HttpResponse::Created().json(results)
+        }
+        Err(e) => {
+            error!(error = %e, "Erreur lors de l'ouverture groupée des canaux");
+            node_error(e)
+        }
+    }
+}
+
+#[instrument(skip(service, body))]
+pub async fn start_psbt_funding(
+    service: NodeService,
+    body: web::Json<ChannelRequest>,
+) -> impl Responder {
+    info!("Traitement de la requête start_psbt_funding");
+    if let Err(e) = validate_channel_request(&body) {
+        return bad_request(e);
+    }
+
+    match service.start_psbt_funding(&body).await {
+        Ok(funding) => HttpResponse::Created().json(funding),
+        Err(e) => {
+            error!(error = %e, "Erreur lors du démarrage du financement PSBT");
+            node_error(e)
+        }
+    }
+}
+
+#[instrument(skip(service, body))]
+pub async fn verify_psbt_funding(
+    service: NodeService,
+    path: web::Path<String>,
+    body: web::Json<PsbtBody>,
+) -> impl Responder {
+    info!("Traitement de la requête verify_psbt_funding");
+    if let Err(e) = validate_funding_id(&path).and_then(|_| validate_psbt(&body.psbt)) {
+        return bad_request(e);
+    }
+
+    match service.verify_psbt_funding(&path, &body.psbt).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => {
+            error!(error = %e, "Erreur lors de la vérification de la PSBT");
+            node_error(e)
+        }
+    }
+}
+
+#[instrument(skip(service, body))]
+pub async fn finalize_psbt_funding(
+    service: NodeService,
+    path: web::Path<String>,
+    body: web::Json<PsbtBody>,
+) -> impl Responder {
+    info!("Traitement de la requête finalize_psbt_funding");
+    if let Err(e) = validate_funding_id(&path).and_then(|_| validate_psbt(&body.psbt)) {
+        return bad_request(e);
+    }
+
+    match service.finalize_psbt_funding(&path, &body.psbt).await {
+        Ok(result) => {
+            info!(funding_txid = %result.funding_txid, "Ouverture du canal envoyée");
+            HttpResponse::Ok().json(result)
+        }
+        Err(e) => {
+            error!(error = %e, "Erreur lors de la finalisation du financement PSBT");
+            node_error(e)
+        }
+    }
+}
+
+#[instrument(skip(service))]
+pub async fn cancel_psbt_funding(
+    service: NodeService,
+    path: web::Path<String>,
+) -> impl Responder {
+    info!("Traitement de la requête cancel_psbt_funding");
+    if let Err(e) = validate_funding_id(&path) {
+        return bad_request(e);
+    }
+
+    match service.cancel_psbt_funding(&path).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => {
+            error!(error = %e, "Erreur lors de l'annulation du financement PSBT");
+            node_error(e)
+        }
+    }
+}
+
+#[instrument(skip(service))]
+pub async fn close_channel(
+    service: NodeService,
+    path: web::Path<String>,
+    query: web::Query<CloseOptions>,
+) -> impl Responder {
+    info!("Traitement de la requête close_channel");
+    if let Err(e) = validate_channel_id(&path).and_then(|_| validate_close_options(&query)) {
+        return bad_request(e);
+    }
+    // Une fermeture forcée bloque nos fonds plusieurs jours : elle doit être confirmée
+    if query.force && !query.confirm {
+        return bad_request(format!(
+            "Fermeture forcée non confirmée: consulter /api/channels/{}/close puis relancer avec confirm=true",
+            path
+        ));
+    }
+
+    match service.close_channel(&path, &query).await {
+        Ok(result) => {
+            info!(channel_id = %result.channel_id, "Fermeture du canal envoyée");
+            HttpResponse::Ok().json(result)
+        }
+        Err(e) => {
+            error!(error = %e, "Erreur lors de la fermeture du canal");
+            node_error(e)
+        }
+    }
+}
+
+#[instrument(skip(service))]
+pub async fn estimate_close(
+    service: NodeService,
+    path: web::Path<String>,
+    query: web::Query<CloseOptions>,
+) -> impl Responder {
+    info!("Traitement de la requête estimate_close");
+    if let Err(e) = validate_channel_id(&path).and_then(|_| validate_close_options(&query)) {
+        return bad_request(e);
+    }
+
+    match service.estimate_close(&path, &query).await {
+        Ok(Some(estimate)) => HttpResponse::Ok().json(estimate),
+        Ok(None) => HttpResponse::NotFound().json(format!("Canal inconnu: {}", path)),
+        Err(e) => {
+            error!(error = %e, "Erreur lors de l'estimation de la fermeture du canal");
+            node_error(e)
+        }
+    }
+}
+
+#[instrument(skip(service, body))]
+pub async fn update_channel_policy(
+    service: NodeService,
+    path: web::Path<String>,
+    body: web::Json<PolicyUpdate>,
+) -> impl Responder {
+    info!("Traitement de la requête update_channel_policy");
+    if let Err(e) = validate_channel_id(&path).and_then(|_| validate_policy_update(&body)) {
+        return bad_request(e);
+    }
+
+    match service.update_channel_policy(&path, &body).await {
+        Ok(Some(change)) => {
+            info!(channel_id = %change.channel_id, "Politique du canal envoyée");
+            HttpResponse::Ok().json(change)
+        }
+        Ok(None) => HttpResponse::NotFound().json(format!("Canal inconnu: {}", path)),
+        Err(e) => {
+            error!(error = %e, "Erreur lors de la modification de la politique du canal");
+            node_error(e)
+        }
+    }
+}
+
+#[instrument(skip(service, body))]
+pub async fn update_all_policies(
+    service: NodeService,
+    body: web::Json<PolicyUpdate>,
+) -> impl Responder {
+    info!("Traitement de la requête update_all_policies");
+    if let Err(e) = validate_policy_update(&body) {
+        return bad_request(e);
+    }
+
+    match service.update_all_policies(&body).await {
+        Ok(changes) => {
+            info!(count = changes.len(), "Politiques des canaux envoyées");
+            HttpResponse::Ok().json(changes)
+        }
+        Err(e) => {
+            error!(error = %e, "Erreur lors de la modification des politiques");
+            node_error(e)
+        }
+    }
+}
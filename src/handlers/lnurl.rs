@@ -0,0 +1,175 @@
+use actix_web::{web, HttpResponse, Responder};
+use serde::Deserialize;
+use tracing::{error, info, instrument, warn};
+
+use crate::handlers::lightning::bad_request;
+use crate::handlers::nodes::NodeService;
+use crate::handlers::validation::{
+    validate_amount, validate_invoice, MAX_INVOICE_AMOUNT, MAX_PAYMENT_AMOUNT,
+};
+use crate::models::lightning::{LnurlPayResult, LnurlRequest, PaymentOptions};
+use crate::services::lnurl::{self, LnurlClient};
+
+#[derive(Debug, Deserialize)]
+pub struct ResolveLnurlBody {
+    /// `lnurl1...`, URL `lnurlp://` / `lnurlw://` ou Lightning Address `nom@domaine`
+    pub target: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PayLnurlBody {
+    pub target: String,
+    /// Montant en sats, facultatif quand le destinataire impose un montant fixe
+    pub amount: Option<u64>,
+    pub comment: Option<String>,
+    /// Budget en sats pour ce paiement, plafonné à `MAX_PAYMENT_AMOUNT`
+    pub max_amount: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WithdrawLnurlBody {
+    pub target: String,
+    /// Montant en sats ; par défaut le maximum proposé par le service
+    pub amount: Option<u64>,
+}
+
+/// Résout la cible, en distinguant une saisie invalide (400) d'un serveur LNURL en erreur (502)
+async fn resolve(client: &LnurlClient, target: &str) -> Result<LnurlRequest, HttpResponse> {
+    if let Err(e) = lnurl::parse_target(target) {
+        return Err(bad_request(format!("{:#}", e)));
+    }
+    client.resolve(target).await.map_err(|e| {
+        error!(error = %e, "Erreur lors de la résolution LNURL");
+        HttpResponse::BadGateway().json(format!("Erreur: {}", e))
+    })
+}
+
+#[instrument(skip(client, body))]
+pub async fn resolve_lnurl(
+    client: web::Data<LnurlClient>,
+    body: web::Json<ResolveLnurlBody>,
+) -> impl Responder {
+    info!("Traitement de la requête resolve_lnurl");
+    match resolve(&client, &body.target).await {
+        Ok(request) => {
+            info!("Requête LNURL envoyée");
+            HttpResponse::Ok().json(request)
+        }
+        Err(response) => response,
+    }
+}
+
+#[instrument(skip(service, client, body))]
+pub async fn pay_lnurl(
+    service: NodeService,
+    client: web::Data<LnurlClient>,
+    body: web::Json<PayLnurlBody>,
+) -> impl Responder {
+    info!("Traitement de la requête pay_lnurl");
+    let pay = match resolve(&client, &body.target).await {
+        Ok(LnurlRequest::Pay(pay)) => pay,
+        Ok(LnurlRequest::Withdraw(_)) => {
+            return bad_request("Requête LNURL-withdraw, utilisez /api/lnurl/withdraw".to_string())
+        }
+        Err(response) => return response,
+    };
+    let amount_msat = match body.amount {
+        Some(amount) => {
+            if let Err(e) = validate_amount(amount, 1, MAX_PAYMENT_AMOUNT) {
+                return bad_request(e);
+            }
+            amount * 1000
+        }
+        None if pay.min_sendable == pay.max_sendable => pay.min_sendable,
+        None => {
+            return bad_request(format!(
+                "Montant requis (entre {} et {} sats)",
+                pay.min_sendable.div_ceil(1000),
+                pay.max_sendable / 1000
+            ))
+        }
+    };
+    if !(pay.min_sendable..=pay.max_sendable).contains(&amount_msat) {
+        return bad_request(format!(
+            "Montant invalide: {} sats (attendu entre {} et {})",
+            amount_msat / 1000,
+            pay.min_sendable.div_ceil(1000),
+            pay.max_sendable / 1000
+        ));
+    }
+
+    let invoice = match client
+        .request_invoice(&pay, amount_msat, body.comment.as_deref())
+        .await
+    {
+        Ok(invoice) => invoice,
+        Err(e) => {
+            error!(error = %e, "Erreur lors de la demande de facture LNURL");
+            return HttpResponse::BadGateway().json(format!("Erreur: {}", e));
+        }
+    };
+    let network = service.network().await.unwrap_or_else(|e| {
+        warn!(error = %e, "Réseau du nœud indisponible, contrôle ignoré");
+        None
+    });
+    let budget = body
+        .max_amount
+        .map_or(MAX_PAYMENT_AMOUNT, |max| max.min(MAX_PAYMENT_AMOUNT));
+    if let Err(e) = validate_invoice(&invoice.invoice, network, budget) {
+        return bad_request(e);
+    }
+
+    match service
+        .send_payment(&invoice.payment_request, &PaymentOptions::default())
+        .await
+    {
+        Ok(payment) => {
+            info!(payment_hash = %payment.payment_hash, "Résultat du paiement LNURL envoyé");
+            HttpResponse::Ok().json(LnurlPayResult {
+                payment,
+                success_action: invoice.success_action,
+            })
+        }
+        Err(e) => {
+            error!(error = %e, "Erreur lors du paiement LNURL");
+            HttpResponse::InternalServerError().json(format!("Erreur: {}", e))
+        }
+    }
+}
+
+#[instrument(skip(service, client, body))]
+pub async fn withdraw_lnurl(
+    service: NodeService,
+    client: web::Data<LnurlClient>,
+    body: web::Json<WithdrawLnurlBody>,
+) -> impl Responder {
+    info!("Traitement de la requête withdraw_lnurl");
+    let withdraw = match resolve(&client, &body.target).await {
+        Ok(LnurlRequest::Withdraw(withdraw)) => withdraw,
+        Ok(LnurlRequest::Pay(_)) => {
+            return bad_request("Requête LNURL-pay, utilisez /api/lnurl/pay".to_string())
+        }
+        Err(response) => return response,
+    };
+    let amount = body.amount.unwrap_or(withdraw.max_withdrawable / 1000);
+    if let Err(e) = validate_amount(amount, 1, MAX_INVOICE_AMOUNT).and_then(|_| {
+        validate_amount(
+            amount,
+            withdraw.min_withdrawable.div_ceil(1000),
+            withdraw.max_withdrawable / 1000,
+        )
+    }) {
+        return bad_request(e);
+    }
+
+    match client.withdraw(&service, &withdraw, amount).await {
+        Ok(invoice) => {
+            info!(payment_hash = %invoice.payment_hash, "Retrait LNURL envoyé");
+            HttpResponse::Created().json(invoice)
+        }
+        Err(e) => {
+            error!(error = %e, "Erreur lors du retrait LNURL");
+            HttpResponse::BadGateway().json(format!("Erreur: {}", e))
+        }
+    }
+}
@@ -1,2 +1,13 @@
+pub mod events;
+pub mod export;
+pub mod fees;
+pub mod history;
 pub mod index;
-pub mod lightning; 
\ No newline at end of file
+pub mod lightning; 
+pub mod lnurl;
+pub mod nodes;
+pub mod onchain;
+pub mod pnl;
+pub mod rebalance;
+pub mod validation;
+pub mod yields;
\ No newline at end of file
@@ -0,0 +1,198 @@
+use actix_web::dev::Payload;
+use actix_web::error::{ErrorInternalServerError, InternalError};
+use actix_web::{web, Either, FromRequest, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+use std::future::Future;
+use std::ops::Deref;
+use std::pin::Pin;
+use tracing::{instrument, info, error};
+
+use crate::handlers::lightning;
+use crate::services::lightning::LightningService;
+use crate::services::registry::{NodeConfig, NodeRegistry, RegistryError};
+
+/// Service du nœud visé par une requête `/api/*` : celui du paramètre `?node=<id>`,
+/// sinon le nœud par défaut.
+///
+/// Résolu dans le registre à chaque requête, pour suivre les nœuds ajoutés, modifiés
+/// ou supprimés depuis le démarrage.
+pub struct NodeService(LightningService);
+
+impl Deref for NodeService {
+    type Target = LightningService;
+
+    fn deref(&self) -> &LightningService {
+        &self.0
+    }
+}
+
+#[derive(Deserialize)]
+struct NodeParam {
+    node: Option<String>,
+}
+
+impl FromRequest for NodeService {
+    type Error = actix_web::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let registry = req.app_data::<web::Data<NodeRegistry>>().cloned();
+        let node = web::Query::<NodeParam>::from_query(req.query_string())
+            .ok()
+            .and_then(|query| query.into_inner().node);
+        Box::pin(async move {
+            let registry =
+                registry.ok_or_else(|| ErrorInternalServerError("Registre des nœuds absent"))?;
+            let service = match &node {
+                Some(id) => registry.get(id).await.ok_or_else(|| unknown_node(id)),
+                None => registry
+                    .default_node()
+                    .await
+                    .ok_or_else(|| HttpResponse::NotFound().json("Aucun nœud configuré")),
+            };
+            service
+                .map(NodeService)
+                .map_err(|response| InternalError::from_response("Nœud inconnu", response).into())
+        })
+    }
+}
+
+fn registry_error(e: RegistryError) -> HttpResponse {
+    match e {
+        RegistryError::NotFound(_) => HttpResponse::NotFound().json(e.to_string()),
+        RegistryError::AlreadyExists(_) => HttpResponse::Conflict().json(e.to_string()),
+        RegistryError::Backend(_) => HttpResponse::BadRequest().json(format!("Erreur: {}", e)),
+    }
+}
+
+fn unknown_node(id: &str) -> HttpResponse {
+    HttpResponse::NotFound().json(format!("Nœud inconnu: {}", id))
+}
+
+#[instrument(skip(registry))]
+pub async fn list_nodes(registry: web::Data<NodeRegistry>) -> impl Responder {
+    info!("Traitement de la requête list_nodes");
+    let nodes = registry.list().await;
+    info!(count = nodes.len(), "Liste des nœuds envoyée");
+    HttpResponse::Ok().json(nodes)
+}
+
+#[instrument(skip(registry, config), fields(node = %config.id))]
+pub async fn create_node(
+    registry: web::Data<NodeRegistry>,
+    config: web::Json<NodeConfig>,
+) -> impl Responder {
+    info!("Traitement de la requête create_node");
+    if let Err(e) = config.validate() {
+        return lightning::bad_request(e);
+    }
+    match registry.add(config.into_inner()).await {
+        Ok(summary) => HttpResponse::Created().json(summary),
+        Err(e) => {
+            error!(error = %e, "Erreur lors de l'ajout du nœud");
+            registry_error(e)
+        }
+    }
+}
+
+#[instrument(skip(registry, config))]
+pub async fn update_node(
+    registry: web::Data<NodeRegistry>,
+    path: web::Path<String>,
+    config: web::Json<NodeConfig>,
+) -> impl Responder {
+    info!("Traitement de la requête update_node");
+    if let Err(e) = config.validate() {
+        return lightning::bad_request(e);
+    }
+    match registry.update(&path, config.into_inner()).await {
+        Ok(summary) => HttpResponse::Ok().json(summary),
+        Err(e) => {
+            error!(error = %e, "Erreur lors de la mise à jour du nœud");
+            registry_error(e)
+        }
+    }
+}
+
+#[instrument(skip(registry))]
+pub async fn delete_node(
+    registry: web::Data<NodeRegistry>,
+    path: web::Path<String>,
+) -> impl Responder {
+    info!("Traitement de la requête delete_node");
+    match registry.remove(&path).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => {
+            error!(error = %e, "Erreur lors de la suppression du nœud");
+            registry_error(e)
+        }
+    }
+}
+
+#[instrument(skip(registry))]
+pub async fn get_portfolio(registry: web::Data<NodeRegistry>) -> impl Responder {
+    info!("Traitement de la requête get_portfolio");
+    let portfolio = registry.portfolio().await;
+    info!(nodes = portfolio.nodes.len(), "Portefeuille envoyé");
+    HttpResponse::Ok().json(portfolio)
+}
+
+#[instrument(skip(registry))]
+pub async fn get_node_info(
+    registry: web::Data<NodeRegistry>,
+    path: web::Path<String>,
+) -> impl Responder {
+    match registry.get(&path).await {
+        Some(service) => Either::Left(lightning::get_node_info(NodeService(service)).await),
+        None => Either::Right(unknown_node(&path)),
+    }
+}
+
+#[instrument(skip(registry))]
+pub async fn list_channels(
+    registry: web::Data<NodeRegistry>,
+    path: web::Path<String>,
+) -> impl Responder {
+    match registry.get(&path).await {
+        Some(service) => Either::Left(lightning::list_channels(NodeService(service)).await),
+        None => Either::Right(unknown_node(&path)),
+    }
+}
+
+#[instrument(skip(registry))]
+pub async fn list_pending_channels(
+    registry: web::Data<NodeRegistry>,
+    path: web::Path<String>,
+) -> impl Responder {
+    match registry.get(&path).await {
+        Some(service) => {
+            Either::Left(lightning::list_pending_channels(NodeService(service)).await)
+        }
+        None => Either::Right(unknown_node(&path)),
+    }
+}
+
+#[instrument(skip(registry))]
+pub async fn query_routes(
+    registry: web::Data<NodeRegistry>,
+    path: web::Path<String>,
+    query: web::Query<lightning::RoutesQuery>,
+) -> impl Responder {
+    match registry.get(&path).await {
+        Some(service) => {
+            Either::Left(lightning::query_routes(NodeService(service), query).await)
+        }
+        None => Either::Right(unknown_node(&path)),
+    }
+}
+
+#[instrument(skip(registry))]
+pub async fn list_transactions(
+    registry: web::Data<NodeRegistry>,
+    path: web::Path<String>,
+) -> impl Responder {
+    match registry.get(&path).await {
+        Some(service) => Either::Left(lightning::list_transactions(NodeService(service)).await),
+        None => Either::Right(unknown_node(&path)),
+    }
+}
@@ -0,0 +1,80 @@
+use actix_web::{web, HttpResponse, Responder};
+use serde::Deserialize;
+use tracing::{error, info, instrument};
+
+use crate::handlers::lightning::{bad_request, node_error};
+use crate::handlers::nodes::NodeService;
+use crate::handlers::validation::validate_onchain_send;
+use crate::models::onchain::{AddressType, OnchainSendRequest};
+
+#[derive(Debug, Default, Deserialize)]
+pub struct NewAddressBody {
+    #[serde(default, rename = "type")]
+    pub address_type: AddressType,
+}
+
+#[instrument(skip(service))]
+pub async fn wallet_balance(service: NodeService) -> impl Responder {
+    info!("Traitement de la requête wallet_balance");
+    match service.wallet_balance().await {
+        Ok(balance) => HttpResponse::Ok().json(balance),
+        Err(e) => {
+            error!(error = %e, "Erreur lors de la récupération du solde on-chain");
+            node_error(e)
+        }
+    }
+}
+
+#[instrument(skip(service))]
+pub async fn list_utxos(service: NodeService) -> impl Responder {
+    info!("Traitement de la requête list_utxos");
+    match service.list_utxos().await {
+        Ok(utxos) => {
+            info!(count = utxos.len(), "Liste des UTXO envoyée");
+            HttpResponse::Ok().json(utxos)
+        }
+        Err(e) => {
+            error!(error = %e, "Erreur lors de la récupération des UTXO");
+            node_error(e)
+        }
+    }
+}
+
+/// Le corps est facultatif : sans type précisé, l'adresse est en segwit v0
+#[instrument(skip(service, body))]
+pub async fn new_address(
+    service: NodeService,
+    body: Option<web::Json<NewAddressBody>>,
+) -> impl Responder {
+    info!("Traitement de la requête new_address");
+    let address_type = body.map(|b| b.address_type).unwrap_or_default();
+    match service.new_address(address_type).await {
+        Ok(address) => HttpResponse::Created().json(address),
+        Err(e) => {
+            error!(error = %e, "Erreur lors de la génération d'une adresse");
+            node_error(e)
+        }
+    }
+}
+
+#[instrument(skip(service, body))]
+pub async fn send_onchain(
+    service: NodeService,
+    body: web::Json<OnchainSendRequest>,
+) -> impl Responder {
+    info!("Traitement de la requête send_onchain");
+    if let Err(e) = validate_onchain_send(&body) {
+        return bad_request(e);
+    }
+
+    match service.send_onchain(&body).await {
+        Ok(result) => {
+            info!(txid = %result.txid, "Envoi on-chain effectué");
+            HttpResponse::Ok().json(result)
+        }
+        Err(e) => {
+            error!(error = %e, "Erreur lors de l'envoi on-chain");
+            node_error(e)
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use actix_web::{web, HttpResponse, Responder};
+use serde::Deserialize;
+use tracing::{error, info, instrument};
+
+use crate::handlers::history::resolve_node;
+use crate::handlers::lightning::{bad_request, node_error};
+use crate::services::history::resolve_window;
+use crate::services::pnl::compute_pnl;
+use crate::services::registry::NodeRegistry;
+
+/// Fenêtre du compte de résultat quand ni `range` ni `from` ne sont précisés
+const DEFAULT_PNL_RANGE: &str = "30d";
+
+#[derive(Debug, Deserialize)]
+pub struct PnlQuery {
+    node: Option<String>,
+    range: Option<String>,
+    from: Option<i64>,
+    to: Option<i64>,
+}
+
+/// Compte de résultat des canaux : frais de routage gagnés moins les coûts payés
+#[instrument(skip(registry))]
+pub async fn get_pnl(
+    registry: web::Data<NodeRegistry>,
+    query: web::Query<PnlQuery>,
+) -> impl Responder {
+    info!("Traitement de la requête get_pnl");
+    let (from, to) = match resolve_window(&query.range, query.from, query.to, DEFAULT_PNL_RANGE) {
+        Ok(window) => window,
+        Err(message) => return bad_request(message),
+    };
+    let node = match resolve_node(&registry, &query.node).await {
+        Ok(node) => node,
+        Err(response) => return response,
+    };
+    let Some(service) = registry.get(&node).await else {
+        return HttpResponse::NotFound().json(format!("Nœud inconnu: {}", node));
+    };
+
+    let history = registry.history();
+    let result = async {
+        let channels = service.list_channels().await?;
+        let costs = service.channel_costs(from).await?;
+        let forwards = history.forwards(&node, from, to, None, 0).await?;
+        let capital = history.average_local_balances(&node, from, to).await?;
+        anyhow::Ok(compute_pnl(from, to, &channels, &forwards.channels, &costs, &capital))
+    }
+    .await;
+
+    match result {
+        Ok(report) => {
+            info!(
+                net_profit_msat = report.net_profit_msat,
+                roi = ?report.roi,
+                "Compte de résultat envoyé"
+            );
+            HttpResponse::Ok().json(report)
+        }
+        Err(e) => {
+            error!(error = %e, "Erreur lors du calcul du compte de résultat");
+            node_error(e)
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use actix_web::{web, HttpResponse, Responder};
+use serde::Deserialize;
+use tracing::{error, info, instrument};
+
+use crate::handlers::history::resolve_node;
+use crate::handlers::lightning::{bad_request, node_error};
+use crate::handlers::validation::validate_rebalance_request;
+use crate::models::rebalance::{RebalanceRequest, RebalanceTrigger};
+use crate::services::history::resolve_window;
+use crate::services::rebalance::{self, RebalanceError};
+use crate::services::registry::NodeRegistry;
+
+/// Fenêtre de l'historique quand ni `range` ni `from` ne sont précisés
+const DEFAULT_REBALANCE_RANGE: &str = "30d";
+
+#[derive(Debug, Deserialize)]
+pub struct RebalanceQuery {
+    node: Option<String>,
+    range: Option<String>,
+    from: Option<i64>,
+    to: Option<i64>,
+}
+
+/// Rééquilibrage circulaire à la demande, tentatives comprises
+#[instrument(skip(registry))]
+pub async fn rebalance_channels(
+    registry: web::Data<NodeRegistry>,
+    body: web::Json<RebalanceRequest>,
+) -> impl Responder {
+    info!("Traitement de la requête rebalance_channels");
+    if let Err(e) = validate_rebalance_request(&body) {
+        return bad_request(e);
+    }
+    let node = match resolve_node(&registry, &body.node).await {
+        Ok(node) => node,
+        Err(response) => return response,
+    };
+    let Some(service) = registry.get(&node).await else {
+        return HttpResponse::NotFound().json(format!("Nœud inconnu: {}", node));
+    };
+
+    let trigger = RebalanceTrigger::Manual;
+    match rebalance::rebalance(&service, registry.history(), &node, &body, trigger).await {
+        Ok(result) => {
+            info!(
+                status = ?result.status,
+                amount = result.amount,
+                fee_msat = result.fee_msat,
+                "Rééquilibrage terminé"
+            );
+            HttpResponse::Ok().json(result)
+        }
+        Err(RebalanceError::InvalidRequest(message)) => bad_request(message),
+        Err(RebalanceError::Node(e)) => {
+            error!(error = %e, "Erreur lors du rééquilibrage");
+            node_error(e)
+        }
+    }
+}
+
+/// Tentatives de rééquilibrage enregistrées, manuelles ou automatiques
+#[instrument(skip(registry))]
+pub async fn list_rebalances(
+    registry: web::Data<NodeRegistry>,
+    query: web::Query<RebalanceQuery>,
+) -> impl Responder {
+    info!("Traitement de la requête list_rebalances");
+    let (from, to) =
+        match resolve_window(&query.range, query.from, query.to, DEFAULT_REBALANCE_RANGE) {
+            Ok(window) => window,
+            Err(message) => return bad_request(message),
+        };
+    let node = match resolve_node(&registry, &query.node).await {
+        Ok(node) => node,
+        Err(response) => return response,
+    };
+
+    match registry.history().rebalances(&node, from, to).await {
+        Ok(attempts) => {
+            info!(count = attempts.len(), "Historique des rééquilibrages envoyé");
+            HttpResponse::Ok().json(attempts)
+        }
+        Err(e) => {
+            error!(error = %e, "Erreur lors de la lecture des rééquilibrages");
+            HttpResponse::InternalServerError().json(format!("Erreur: {}", e))
+        }
+    }
+}
@@ -0,0 +1,483 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use std::collections::{BTreeMap, HashSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::models::lightning::{
+    BatchOpenRequest, ChannelRequest, CloseOptions, DecodedInvoice, DecodedOffer, Network,
+    PaymentOptions, PolicyUpdate,
+};
+use crate::models::onchain::OnchainSendRequest;
+use crate::models::rebalance::RebalanceRequest;
+use crate::services::keysend::{KEYSEND_PREIMAGE_RECORD, MESSAGE_RECORD, MIN_CUSTOM_RECORD};
+use crate::services::policy::{validate_rebalance_limits, MAX_REBALANCE_AMOUNT};
+
+/// Taille minimale d'un canal acceptée par défaut par les implémentations
+pub const MIN_CHANNEL_SIZE: u64 = 20_000;
+/// Taille maximale d'un canal (10 BTC), au-delà on suppose une erreur de saisie
+pub const MAX_CHANNEL_SIZE: u64 = 1_000_000_000;
+/// Montant maximal d'une facture en sats (1 BTC)
+pub const MAX_INVOICE_AMOUNT: u64 = 100_000_000;
+/// Montant maximal d'un paiement sortant en sats (0,1 BTC)
+pub const MAX_PAYMENT_AMOUNT: u64 = 10_000_000;
+/// Longueur maximale d'une description de facture BOLT11
+pub const MAX_DESCRIPTION_LEN: usize = 639;
+/// Taille cumulée maximale des enregistrements TLV d'un keysend, message compris :
+/// l'onion d'un paiement ne laisse guère plus de place au dernier saut
+pub const MAX_CUSTOM_RECORDS_SIZE: usize = 1000;
+/// Bornes de l'expiration d'une facture, en secondes
+pub const MIN_INVOICE_EXPIRY: u64 = 60;
+pub const MAX_INVOICE_EXPIRY: u64 = 31_536_000;
+/// Bornes du delta CLTV d'un canal : 18 blocs est le minimum accepté par LND
+pub const MIN_TIME_LOCK_DELTA: u32 = 18;
+pub const MAX_TIME_LOCK_DELTA: u32 = 2016;
+/// Nombre maximal de parties d'un paiement multi-chemins
+pub const MAX_PAYMENT_PARTS: u32 = 64;
+/// Délai maximal d'un paiement en secondes, au-delà des HTLC resteraient bloqués trop longtemps
+pub const MAX_PAYMENT_TIMEOUT: u32 = 600;
+/// Objectif de confirmation maximal, celui des estimateurs de frais de Bitcoin Core
+pub const MAX_TARGET_CONF: u32 = 1008;
+/// Taux de frais on-chain maximal en sat/vB, au-delà on suppose une erreur de saisie
+pub const MAX_FEE_RATE: u64 = 1000;
+/// Montant minimal d'un envoi on-chain : en dessous, la sortie serait de la poussière
+pub const MIN_ONCHAIN_AMOUNT: u64 = 546;
+/// Montant maximal d'un envoi on-chain (10 BTC), au-delà on suppose une erreur de saisie
+pub const MAX_ONCHAIN_AMOUNT: u64 = 1_000_000_000;
+/// Nombre maximal de canaux financés par une même transaction
+pub const MAX_BATCH_CHANNELS: usize = 20;
+
+fn is_hex(value: &str, len: usize) -> bool {
+    value.len() == len && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Clé publique compressée : 33 octets en hexadécimal, préfixe 02 ou 03
+pub fn validate_pubkey(pubkey: &str) -> Result<(), String> {
+    if !is_hex(pubkey, 66) || !(pubkey.starts_with("02") || pubkey.starts_with("03")) {
+        return Err(format!("Clé publique invalide: {}", pubkey));
+    }
+    Ok(())
+}
+
+pub fn validate_amount(amount: u64, min: u64, max: u64) -> Result<(), String> {
+    if amount < min || amount > max {
+        return Err(format!(
+            "Montant invalide: {} sats (attendu entre {} et {})",
+            amount, min, max
+        ));
+    }
+    Ok(())
+}
+
+/// Accepte les identifiants de canal des différents backends :
+/// channel point LND (`txid:index`), short channel id (`800000x1x0`)
+/// ou channel id de 32 octets (CLN, Eclair).
+pub fn validate_channel_id(channel_id: &str) -> Result<(), String> {
+    let valid = match channel_id.split_once(':') {
+        Some((txid, index)) => is_hex(txid, 64) && index.parse::<u32>().is_ok(),
+        None => {
+            is_hex(channel_id, 64) || {
+                let parts: Vec<&str> = channel_id.split('x').collect();
+                parts.len() == 3 && parts.iter().all(|p| p.parse::<u64>().is_ok())
+            }
+        }
+    };
+    if !valid {
+        return Err(format!("Identifiant de canal invalide: {}", channel_id));
+    }
+    Ok(())
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// Contrôles avant paiement d'une facture décodée : réseau du nœud,
+/// expiration et budget en sats. Un réseau inconnu n'est pas vérifié.
+pub fn validate_invoice(
+    invoice: &DecodedInvoice,
+    network: Option<Network>,
+    budget: u64,
+) -> Result<(), String> {
+    if let Some(network) = network {
+        if invoice.network != network {
+            return Err(format!(
+                "Facture émise pour le réseau {}, le nœud est sur {}",
+                invoice.network, network
+            ));
+        }
+    }
+
+    let now = now();
+    if invoice.expires_at() <= now {
+        return Err(format!(
+            "Facture expirée depuis {} s",
+            now - invoice.expires_at()
+        ));
+    }
+
+    let amount_msat = invoice
+        .amount_msat
+        .ok_or_else(|| "Facture sans montant non prise en charge".to_string())?;
+    let amount = amount_msat.div_ceil(1000);
+    if amount > budget {
+        return Err(format!(
+            "Montant de {} sats supérieur au budget de {} sats",
+            amount, budget
+        ));
+    }
+    Ok(())
+}
+
+/// Contrôles avant paiement d'une offre BOLT12 décodée.
+///
+/// `amount` est le montant choisi par le payeur en sats : requis quand l'offre
+/// n'a pas de montant ou le libelle dans une autre devise, au moins égal à celui
+/// de l'offre sinon.
+pub fn validate_offer(
+    offer: &DecodedOffer,
+    network: Option<Network>,
+    amount: Option<u64>,
+    budget: u64,
+) -> Result<(), String> {
+    if let Some(network) = network {
+        if !offer.chains.contains(&network) {
+            return Err(format!("Offre non valable sur le réseau {} du nœud", network));
+        }
+    }
+
+    if let Some(expiry) = offer.absolute_expiry {
+        let now = now();
+        if expiry <= now {
+            return Err(format!("Offre expirée depuis {} s", now - expiry));
+        }
+    }
+
+    if let Some(amount) = amount {
+        validate_amount(amount, 1, MAX_PAYMENT_AMOUNT)?;
+    }
+    let amount = match (amount, offer.amount, &offer.currency) {
+        (None, Some(_), Some(currency)) => {
+            return Err(format!(
+                "Montant en sats requis pour une offre libellée en {}",
+                currency
+            ))
+        }
+        (Some(amount), Some(offer_msat), None)
+            if amount.checked_mul(1000).is_some_and(|msat| msat < offer_msat) =>
+        {
+            return Err(format!(
+                "Montant de {} sats inférieur à celui de l'offre ({} sats)",
+                amount,
+                offer_msat.div_ceil(1000)
+            ))
+        }
+        (Some(amount), _, _) => amount,
+        (None, Some(offer_msat), None) => offer_msat.div_ceil(1000),
+        (None, None, _) => return Err("Montant requis pour une offre à montant libre".to_string()),
+    };
+    if amount > budget {
+        return Err(format!(
+            "Montant de {} sats supérieur au budget de {} sats",
+            amount, budget
+        ));
+    }
+    Ok(())
+}
+
+/// Enregistrements TLV d'un keysend, reçus en hexadécimal : types personnalisés
+/// uniquement, sans collision avec la preimage ni avec `message`.
+pub fn validate_custom_records(
+    records: &BTreeMap<u64, String>,
+    message: Option<&str>,
+) -> Result<BTreeMap<u64, Vec<u8>>, String> {
+    let mut decoded = BTreeMap::new();
+    for (record, value) in records {
+        if *record < MIN_CUSTOM_RECORD || *record == KEYSEND_PREIMAGE_RECORD {
+            return Err(format!("Type d'enregistrement TLV réservé: {}", record));
+        }
+        if *record == MESSAGE_RECORD && message.is_some() {
+            return Err(format!(
+                "Enregistrement {} fourni en double avec le message",
+                record
+            ));
+        }
+        let value = hex::decode(value)
+            .map_err(|_| format!("Valeur hexadécimale invalide pour l'enregistrement {}", record))?;
+        decoded.insert(*record, value);
+    }
+
+    let size = decoded.values().map(Vec::len).sum::<usize>() + message.map_or(0, str::len);
+    if size > MAX_CUSTOM_RECORDS_SIZE {
+        return Err(format!(
+            "Enregistrements TLV trop volumineux: {} octets ({} maximum)",
+            size, MAX_CUSTOM_RECORDS_SIZE
+        ));
+    }
+    Ok(decoded)
+}
+
+/// Contraintes d'un paiement : bornes des valeurs et format des identifiants
+pub fn validate_payment_options(options: &PaymentOptions) -> Result<(), String> {
+    if let Some(ppm) = options.max_fee_ppm {
+        validate_amount(ppm as u64, 0, 1_000_000)
+            .map_err(|_| format!("Plafond de frais invalide: {} ppm (1 000 000 maximum)", ppm))?;
+    }
+    if let Some(parts) = options.max_parts {
+        validate_amount(parts as u64, 1, MAX_PAYMENT_PARTS as u64).map_err(|_| {
+            format!(
+                "Nombre de parties invalide: {} (attendu entre 1 et {})",
+                parts, MAX_PAYMENT_PARTS
+            )
+        })?;
+    }
+    if let Some(timeout) = options.timeout {
+        validate_amount(timeout as u64, 1, MAX_PAYMENT_TIMEOUT as u64).map_err(|_| {
+            format!(
+                "Délai de paiement invalide: {} s (attendu entre 1 et {})",
+                timeout, MAX_PAYMENT_TIMEOUT
+            )
+        })?;
+    }
+    if let Some(channel_id) = &options.outgoing_channel {
+        validate_channel_id(channel_id)?;
+    }
+    if let Some(last_hop) = &options.last_hop {
+        validate_pubkey(last_hop)?;
+    }
+    Ok(())
+}
+
+/// Modification de politique de routage : au moins un champ, valeurs dans leurs bornes
+pub fn validate_policy_update(update: &PolicyUpdate) -> Result<(), String> {
+    if update.is_empty() {
+        return Err("Aucun champ de politique à modifier".to_string());
+    }
+    if let Some(ppm) = update.fee_rate_ppm {
+        validate_amount(ppm as u64, 0, 1_000_000)
+            .map_err(|_| format!("Taux de frais invalide: {} ppm (1 000 000 maximum)", ppm))?;
+    }
+    if let Some(delta) = update.time_lock_delta {
+        validate_amount(delta as u64, MIN_TIME_LOCK_DELTA as u64, MAX_TIME_LOCK_DELTA as u64)
+            .map_err(|_| {
+                format!(
+                    "Delta CLTV invalide: {} (attendu entre {} et {})",
+                    delta, MIN_TIME_LOCK_DELTA, MAX_TIME_LOCK_DELTA
+                )
+            })?;
+    }
+    if let (Some(min), Some(max)) = (update.min_htlc_msat, update.max_htlc_msat) {
+        if min > max {
+            return Err(format!(
+                "HTLC minimal ({} msat) supérieur au HTLC maximal ({} msat)",
+                min, max
+            ));
+        }
+    }
+    if update.max_htlc_msat == Some(0) {
+        return Err("HTLC maximal nul: le canal ne pourrait plus relayer".to_string());
+    }
+    // Seules les remises sont acceptées : LND refuse les frais entrants positifs par défaut
+    if update.inbound_base_fee_msat.is_some_and(|fee| fee > 0)
+        || update.inbound_fee_rate_ppm.is_some_and(|ppm| ppm > 0)
+    {
+        return Err(
+            "Les frais entrants doivent être des remises (valeurs négatives ou nulles)".to_string(),
+        );
+    }
+    Ok(())
+}
+
+/// Adresse Bitcoin bech32 (`bc1`, `tb1`, `bcrt1`) ou base58, sans vérifier la somme de contrôle
+pub fn validate_address(address: &str) -> Result<(), String> {
+    let lower = address.to_lowercase();
+    let valid = if let Some(data) = ["bc1", "tb1", "bcrt1"]
+        .iter()
+        .find_map(|hrp| lower.strip_prefix(hrp))
+    {
+        // Une adresse bech32 est entièrement en minuscules ou entièrement en majuscules
+        (address == lower || address == address.to_uppercase())
+            && (39..=87).contains(&address.len())
+            && data.chars().all(|c| "qpzry9x8gf2tvdw0s3jn54khce6mua7l".contains(c))
+    } else {
+        (26..=35).contains(&address.len())
+            && address.starts_with(['1', '3', 'm', 'n', '2'])
+            && address.chars().all(|c| c.is_ascii_alphanumeric() && !"0OIl".contains(c))
+    };
+    if !valid {
+        return Err(format!("Adresse Bitcoin invalide: {}", address));
+    }
+    Ok(())
+}
+
+fn validate_fee_rate(rate: u64) -> Result<(), String> {
+    validate_amount(rate, 1, MAX_FEE_RATE).map_err(|_| {
+        format!(
+            "Taux de frais invalide: {} sat/vB (attendu entre 1 et {})",
+            rate, MAX_FEE_RATE
+        )
+    })
+}
+
+/// Frais d'une transaction on-chain : objectif de confirmation ou taux explicite, pas les deux
+pub fn validate_fee_target(
+    target_conf: Option<u32>,
+    sat_per_vbyte: Option<u64>,
+) -> Result<(), String> {
+    if target_conf.is_some() && sat_per_vbyte.is_some() {
+        return Err("Préciser soit target_conf, soit sat_per_vbyte, pas les deux".to_string());
+    }
+    if let Some(target_conf) = target_conf {
+        validate_amount(target_conf as u64, 1, MAX_TARGET_CONF as u64).map_err(|_| {
+            format!(
+                "Objectif de confirmation invalide: {} blocs (attendu entre 1 et {})",
+                target_conf, MAX_TARGET_CONF
+            )
+        })?;
+    }
+    if let Some(rate) = sat_per_vbyte {
+        validate_fee_rate(rate)?;
+    }
+    Ok(())
+}
+
+/// Options de fermeture : une fermeture forcée utilise les frais fixés par la
+/// transaction d'engagement et ne se négocie pas, elle n'accepte donc aucune préférence
+pub fn validate_close_options(options: &CloseOptions) -> Result<(), String> {
+    if options.force
+        && (options.target_conf.is_some()
+            || options.sat_per_vbyte.is_some()
+            || options.max_fee_per_vbyte.is_some()
+            || options.delivery_address.is_some())
+    {
+        return Err(
+            "Une fermeture forcée n'accepte ni frais ni adresse de réception".to_string(),
+        );
+    }
+    validate_fee_target(options.target_conf, options.sat_per_vbyte)?;
+    if let Some(max_fee) = options.max_fee_per_vbyte {
+        validate_fee_rate(max_fee)?;
+    }
+    if let (Some(rate), Some(max_fee)) = (options.sat_per_vbyte, options.max_fee_per_vbyte) {
+        if rate > max_fee {
+            return Err(format!(
+                "Taux de frais ({} sat/vB) supérieur au plafond ({} sat/vB)",
+                rate, max_fee
+            ));
+        }
+    }
+    if let Some(address) = &options.delivery_address {
+        validate_address(address)?;
+    }
+    Ok(())
+}
+
+/// Canal à ouvrir : pair, capacité, montant offert et politique initiale
+pub fn validate_channel_request(request: &ChannelRequest) -> Result<(), String> {
+    validate_pubkey(&request.pubkey)?;
+    validate_amount(request.amount, MIN_CHANNEL_SIZE, MAX_CHANNEL_SIZE)?;
+    if request.push_amount >= request.amount {
+        return Err(format!(
+            "Montant offert au pair ({} sats) supérieur ou égal à la capacité ({} sats)",
+            request.push_amount, request.amount
+        ));
+    }
+    if let Some(ppm) = request.fee_rate_ppm {
+        validate_amount(ppm as u64, 0, 1_000_000)
+            .map_err(|_| format!("Taux de frais invalide: {} ppm (1 000 000 maximum)", ppm))?;
+    }
+    Ok(())
+}
+
+/// Ouverture groupée : au plus un canal par pair, les nœuds refusant les doublons
+pub fn validate_batch_open(request: &BatchOpenRequest) -> Result<(), String> {
+    if request.channels.is_empty() || request.channels.len() > MAX_BATCH_CHANNELS {
+        return Err(format!(
+            "Nombre de canaux invalide: {} (attendu entre 1 et {})",
+            request.channels.len(),
+            MAX_BATCH_CHANNELS
+        ));
+    }
+    let mut peers = HashSet::new();
+    for channel in &request.channels {
+        validate_channel_request(channel)?;
+        if !peers.insert(channel.pubkey.as_str()) {
+            return Err(format!("Pair présent plusieurs fois: {}", channel.pubkey));
+        }
+    }
+    validate_fee_target(request.target_conf, request.sat_per_vbyte)
+}
+
+/// PSBT encodée en base64, reconnue à son préfixe magique `psbt\xff`
+pub fn validate_psbt(psbt: &str) -> Result<(), String> {
+    let valid = BASE64
+        .decode(psbt)
+        .is_ok_and(|bytes| bytes.starts_with(b"psbt\xff"));
+    if !valid {
+        return Err("PSBT invalide: base64 attendu".to_string());
+    }
+    Ok(())
+}
+
+/// Identifiant de financement PSBT : `pending_chan_id` de LND (32 octets)
+/// ou clé publique du pair pour CLN
+pub fn validate_funding_id(funding_id: &str) -> Result<(), String> {
+    if !is_hex(funding_id, 64) && validate_pubkey(funding_id).is_err() {
+        return Err(format!("Identifiant de financement invalide: {}", funding_id));
+    }
+    Ok(())
+}
+
+/// Sortie de transaction `txid:index`
+pub fn validate_outpoint(outpoint: &str) -> Result<(), String> {
+    let valid = outpoint
+        .split_once(':')
+        .is_some_and(|(txid, index)| is_hex(txid, 64) && index.parse::<u32>().is_ok());
+    if !valid {
+        return Err(format!("UTXO invalide: {} (attendu txid:index)", outpoint));
+    }
+    Ok(())
+}
+
+/// Envoi on-chain : un montant ou `send_all`, des frais et des UTXO bien formés
+pub fn validate_onchain_send(request: &OnchainSendRequest) -> Result<(), String> {
+    validate_address(&request.address)?;
+    match (request.amount, request.send_all) {
+        (Some(_), true) => {
+            return Err("Préciser soit amount, soit send_all, pas les deux".to_string())
+        }
+        (None, false) => return Err("Montant de l'envoi requis".to_string()),
+        (Some(amount), false) => validate_amount(amount, MIN_ONCHAIN_AMOUNT, MAX_ONCHAIN_AMOUNT)?,
+        (None, true) => {}
+    }
+    validate_fee_target(request.target_conf, request.sat_per_vbyte)?;
+    let mut outpoints = HashSet::new();
+    for outpoint in &request.utxos {
+        validate_outpoint(outpoint)?;
+        if !outpoints.insert(outpoint.as_str()) {
+            return Err(format!("UTXO présent plusieurs fois: {}", outpoint));
+        }
+    }
+    Ok(())
+}
+
+/// Rééquilibrage à la demande : deux canaux distincts, un montant et des frais bornés
+pub fn validate_rebalance_request(request: &RebalanceRequest) -> Result<(), String> {
+    validate_channel_id(&request.outgoing_channel)?;
+    validate_channel_id(&request.incoming_channel)?;
+    if request.outgoing_channel == request.incoming_channel {
+        return Err("Les canaux de sortie et d'entrée doivent différer".to_string());
+    }
+    validate_amount(request.amount, 1, MAX_REBALANCE_AMOUNT)?;
+    validate_rebalance_limits(request.max_fee_ppm, &request.retry)?;
+    if let Some(timeout) = request.timeout {
+        validate_amount(timeout as u64, 1, MAX_PAYMENT_TIMEOUT as u64).map_err(|_| {
+            format!(
+                "Délai de paiement invalide: {} s (attendu entre 1 et {})",
+                timeout, MAX_PAYMENT_TIMEOUT
+            )
+        })?;
+    }
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use actix_web::{web, HttpResponse, Responder};
+use serde::Deserialize;
+use tracing::{error, info, instrument};
+
+use crate::handlers::history::resolve_node;
+use crate::handlers::lightning::bad_request;
+use crate::services::history::resolve_window;
+use crate::services::registry::NodeRegistry;
+use crate::services::yields::compute_yields;
+
+/// Fenêtre des rendements quand ni `range` ni `from` ne sont précisés
+const DEFAULT_YIELDS_RANGE: &str = "30d";
+
+#[derive(Debug, Deserialize)]
+pub struct YieldsQuery {
+    node: Option<String>,
+    range: Option<String>,
+    from: Option<i64>,
+    to: Option<i64>,
+}
+
+/// Rendement réalisé des canaux, calculé à partir des relais enregistrés
+#[instrument(skip(registry))]
+pub async fn get_yields(
+    registry: web::Data<NodeRegistry>,
+    query: web::Query<YieldsQuery>,
+) -> impl Responder {
+    info!("Traitement de la requête get_yields");
+    let (from, to) = match resolve_window(&query.range, query.from, query.to, DEFAULT_YIELDS_RANGE)
+    {
+        Ok(window) => window,
+        Err(message) => return bad_request(message),
+    };
+    let node = match resolve_node(&registry, &query.node).await {
+        Ok(node) => node,
+        Err(response) => return response,
+    };
+    let Some(service) = registry.get(&node).await else {
+        return HttpResponse::NotFound().json(format!("Nœud inconnu: {}", node));
+    };
+
+    let history = registry.history();
+    let result = async {
+        let channels = service.list_channels().await?;
+        let forwards = history.forwards(&node, from, to, None, 0).await?;
+        let capital = history.average_local_balances(&node, from, to).await?;
+        anyhow::Ok(compute_yields(from, to, &channels, &forwards.channels, &capital))
+    }
+    .await;
+
+    match result {
+        Ok(report) => {
+            info!(
+                fee_msat = report.fee_msat,
+                annualized_return = ?report.annualized_return,
+                "Rendements envoyés"
+            );
+            HttpResponse::Ok().json(report)
+        }
+        Err(e) => {
+            error!(error = %e, "Erreur lors du calcul des rendements");
+            HttpResponse::InternalServerError().json(format!("Erreur: {}", e))
+        }
+    }
+}
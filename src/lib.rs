@@ -1,3 +1,4 @@
+pub mod cli;
 pub mod models;
 pub mod services;
 pub mod handlers;
@@ -9,10 +10,15 @@ use tera::Tera;
 use std::sync::Arc;
 use dotenv::dotenv;
 use std::env;
-use actix_web::middleware;
 
-use handlers::{index, lightning};
-use services::lightning::LightningService;
+use handlers::{
+    events, export, fees, history, index, lightning, lnurl, nodes, onchain, pnl, rebalance,
+    yields,
+};
+use services::history::HistoryStore;
+use services::lnurl::LnurlClient;
+use services::prices::PriceClient;
+use services::registry::NodeRegistry;
 use telemetry::{init_telemetry, get_tracing_middleware};
 
 pub async fn run_server() -> std::io::Result<()> {
@@ -59,30 +65,23 @@ pub async fn run_server() -> std::io::Result<()> {
         }
         Err(e) => {
             tracing::error!(error = %e, "Erreur lors de l'initialisation du template engine");
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                format!("Erreur d'initialisation des templates: {}", e),
-            ));
+            return Err(std::io::Error::other(format!(
+                "Erreur d'initialisation des templates: {}",
+                e
+            )));
         }
     };
     let tera = Arc::new(tera);
 
-    // Configuration des services externes
-    let sparkseer_url = env::var("API_URL")
-        .unwrap_or_else(|_| "https://api.sparkseer.space".to_string());
-    let ml_url = env::var("ML_URL")
-        .unwrap_or_else(|_| "https://1ml.com".to_string());
-    tracing::info!(sparkseer_url = %sparkseer_url, ml_url = %ml_url, "URLs des services externes configurées");
-
-    // Configuration du service Lightning
-    let lightning_url = env::var("LIGHTNING_URL")
-        .unwrap_or_else(|_| "http://localhost:8080".to_string());
-    tracing::info!(lightning_url = %lightning_url, "URL du service Lightning configurée");
-    let lightning_service = web::Data::new(LightningService::new(
-        lightning_url,
-        sparkseer_url,
-        ml_url,
-    ));
+    let registry = load_registry(true).await?;
+    let default_node = registry.default_node().await.ok_or_else(|| {
+        tracing::error!("Aucun nœud Lightning configuré");
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, "Aucun nœud Lightning configuré")
+    })?;
+    tracing::info!(backend = %default_node.backend_kind(), "Nœud par défaut configuré");
+    let registry = web::Data::new(registry);
+    let lnurl_client = web::Data::new(LnurlClient::new());
+    let price_client = web::Data::new(price_client());
 
     // Configuration du serveur
     tracing::info!(bind_address = %bind_address, "Démarrage du serveur");
@@ -90,7 +89,9 @@ pub async fn run_server() -> std::io::Result<()> {
         App::new()
             .wrap(get_tracing_middleware())
             .app_data(web::Data::new(tera.clone()))
-            .app_data(lightning_service.clone())
+            .app_data(registry.clone())
+            .app_data(lnurl_client.clone())
+            .app_data(price_client.clone())
             .service(fs::Files::new("/static", "static").show_files_listing())
             .route("/favicon.ico", web::get().to(|| async {
                 match std::fs::read("static/favicon.ico") {
@@ -105,8 +106,60 @@ pub async fn run_server() -> std::io::Result<()> {
             .route("/transactions", web::get().to(index::transactions))
             .route("/api/node/info", web::get().to(lightning::get_node_info))
             .route("/api/channels", web::get().to(lightning::list_channels))
+            .route("/api/channels", web::post().to(lightning::open_channel))
+            .route("/api/channels/batch", web::post().to(lightning::open_channels))
+            .route("/api/channels/pending", web::get().to(lightning::list_pending_channels))
+            .route("/api/channels/psbt", web::post().to(lightning::start_psbt_funding))
+            .route("/api/channels/psbt/{id}", web::delete().to(lightning::cancel_psbt_funding))
+            .route("/api/channels/psbt/{id}/verify", web::post().to(lightning::verify_psbt_funding))
+            .route(
+                "/api/channels/psbt/{id}/finalize",
+                web::post().to(lightning::finalize_psbt_funding),
+            )
+            .route("/api/channels/policy", web::put().to(lightning::update_all_policies))
+            .route("/api/channels/{id}", web::delete().to(lightning::close_channel))
+            .route("/api/channels/{id}/close", web::get().to(lightning::estimate_close))
+            .route("/api/channels/{id}/policy", web::put().to(lightning::update_channel_policy))
             .route("/api/transactions", web::get().to(lightning::list_transactions))
+            .route("/api/onchain/balance", web::get().to(onchain::wallet_balance))
+            .route("/api/onchain/utxos", web::get().to(onchain::list_utxos))
+            .route("/api/onchain/addresses", web::post().to(onchain::new_address))
+            .route("/api/onchain/send", web::post().to(onchain::send_onchain))
+            .route("/api/routes", web::get().to(lightning::query_routes))
+            .route("/api/payments", web::post().to(lightning::send_payment))
+            .route("/api/keysend", web::post().to(lightning::send_keysend))
+            .route("/api/invoices", web::post().to(lightning::create_invoice))
+            .route("/api/invoices/decode", web::post().to(lightning::decode_invoice))
+            .route("/api/offers", web::post().to(lightning::create_offer))
+            .route("/api/offers/decode", web::post().to(lightning::decode_offer))
+            .route("/api/offers/pay", web::post().to(lightning::pay_offer))
+            .route("/api/lnurl/resolve", web::post().to(lnurl::resolve_lnurl))
+            .route("/api/lnurl/pay", web::post().to(lnurl::pay_lnurl))
+            .route("/api/lnurl/withdraw", web::post().to(lnurl::withdraw_lnurl))
             .route("/api/network/stats", web::get().to(lightning::get_network_stats))
+            .route("/api/events", web::get().to(events::stream_events))
+            .route("/api/history/node", web::get().to(history::node_history))
+            .route("/api/forwards", web::get().to(history::list_forwards))
+            .route("/api/yields", web::get().to(yields::get_yields))
+            .route("/api/pnl", web::get().to(pnl::get_pnl))
+            .route("/api/rebalance", web::post().to(rebalance::rebalance_channels))
+            .route("/api/rebalance", web::get().to(rebalance::list_rebalances))
+            .route("/api/fees/changes", web::get().to(fees::list_fee_changes))
+            .route("/api/export/{format}", web::get().to(export::export_ledger))
+            .route("/api/history/channels/{id}", web::get().to(history::channel_history))
+            .route("/api/nodes", web::get().to(nodes::list_nodes))
+            .route("/api/nodes", web::post().to(nodes::create_node))
+            .route("/api/nodes/{id}", web::put().to(nodes::update_node))
+            .route("/api/nodes/{id}", web::delete().to(nodes::delete_node))
+            .route("/api/nodes/{id}/info", web::get().to(nodes::get_node_info))
+            .route("/api/nodes/{id}/channels", web::get().to(nodes::list_channels))
+            .route(
+                "/api/nodes/{id}/channels/pending",
+                web::get().to(nodes::list_pending_channels),
+            )
+            .route("/api/nodes/{id}/transactions", web::get().to(nodes::list_transactions))
+            .route("/api/nodes/{id}/routes", web::get().to(nodes::query_routes))
+            .route("/api/portfolio", web::get().to(nodes::get_portfolio))
             .default_service(web::route().to(|| async {
                 HttpResponse::NotFound().body("Page non trouvée")
             }))
@@ -114,4 +167,66 @@ pub async fn run_server() -> std::io::Result<()> {
     .bind(&bind_address)?
     .run()
     .await
-} 
\ No newline at end of file
+} 
+
+/// Ouvre l'historique et charge les nœuds ; `background` lance leurs tâches de fond,
+/// inutiles aux commandes ponctuelles de la CLI
+async fn load_registry(background: bool) -> std::io::Result<NodeRegistry> {
+    // Configuration des services externes
+    let sparkseer_url = env::var("API_URL")
+        .unwrap_or_else(|_| "https://api.sparkseer.space".to_string());
+    let ml_url = env::var("ML_URL")
+        .unwrap_or_else(|_| "https://1ml.com".to_string());
+    tracing::info!(sparkseer_url = %sparkseer_url, ml_url = %ml_url, "URLs des services externes configurées");
+
+    // Historique des relevés des nœuds
+    let history_path = env::var("HISTORY_DB_PATH").unwrap_or_else(|_| "lightdash.db".to_string());
+    let snapshot_interval = env::var("SNAPSHOT_INTERVAL_SECS")
+        .ok()
+        .and_then(|secs| secs.parse().ok())
+        .unwrap_or(300);
+    let history_store = HistoryStore::open(
+        std::path::Path::new(&history_path),
+        std::time::Duration::from_secs(snapshot_interval),
+    )
+    .map_err(|e| {
+        tracing::error!(error = %e, "Erreur lors de l'ouverture de l'historique");
+        std::io::Error::other(format!("Historique indisponible: {:#}", e))
+    })?;
+    tracing::info!(
+        path = %history_path,
+        interval_secs = snapshot_interval,
+        "Historique des relevés ouvert"
+    );
+
+    // Configuration des nœuds Lightning
+    let nodes_file = env::var("LIGHTNING_NODES_FILE").ok().map(std::path::PathBuf::from);
+    let registry = if background {
+        NodeRegistry::load(nodes_file, sparkseer_url, ml_url, history_store).await
+    } else {
+        NodeRegistry::load_passive(nodes_file, sparkseer_url, ml_url, history_store).await
+    };
+    registry.map_err(|e| {
+        tracing::error!(error = %e, "Erreur lors du chargement des nœuds Lightning");
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Configuration des nœuds invalide: {:#}", e),
+        )
+    })
+}
+
+/// Client des cours historiques du bitcoin, pour la valorisation des exports
+fn price_client() -> PriceClient {
+    PriceClient::new(
+        env::var("PRICE_API_URL").unwrap_or_else(|_| "https://mempool.space".to_string()),
+    )
+}
+
+/// Écrit un export comptable sans démarrer le serveur : `lightdash_rust export <format> ...`
+pub async fn run_export(args: &[String]) -> std::io::Result<()> {
+    dotenv().ok();
+    let registry = load_registry(false).await?;
+    cli::export(args, &registry, &price_client())
+        .await
+        .map_err(|e| std::io::Error::other(format!("{:#}", e)))
+}
@@ -1,6 +1,10 @@
-use lightdash_rust::run_server;
+use lightdash_rust::{run_export, run_server};
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    run_server().await
-} 
\ No newline at end of file
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("export") => run_export(&args[1..]).await,
+        _ => run_server().await,
+    }
+}
@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Format d'un export comptable
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// Grand livre générique, une ligne par mouvement
+    Csv,
+    /// CSV au format universel de Koinly
+    Koinly,
+    /// Étiquettes de transactions on-chain au format BIP-329 (JSON Lines)
+    Bip329,
+}
+
+impl ExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv | ExportFormat::Koinly => "text/csv; charset=utf-8",
+            ExportFormat::Bip329 => "application/jsonl; charset=utf-8",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv | ExportFormat::Koinly => "csv",
+            ExportFormat::Bip329 => "jsonl",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "koinly" => Ok(ExportFormat::Koinly),
+            "bip329" => Ok(ExportFormat::Bip329),
+            _ => Err(format!(
+                "Format d'export inconnu: {} (attendu csv, koinly ou bip329)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Koinly => "koinly",
+            ExportFormat::Bip329 => "bip329",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Nature d'un mouvement du grand livre
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryKind {
+    Payment,
+    Invoice,
+    Keysend,
+    /// Frais gagnés sur un relais
+    Forward,
+    ChannelOpen,
+    ChannelClose,
+    Onchain,
+}
+
+impl EntryKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EntryKind::Payment => "payment",
+            EntryKind::Invoice => "invoice",
+            EntryKind::Keysend => "keysend",
+            EntryKind::Forward => "forward",
+            EntryKind::ChannelOpen => "channel_open",
+            EntryKind::ChannelClose => "channel_close",
+            EntryKind::Onchain => "onchain",
+        }
+    }
+
+    /// Vrai pour les mouvements portés par une transaction on-chain
+    pub fn is_onchain(&self) -> bool {
+        matches!(self, EntryKind::ChannelOpen | EntryKind::ChannelClose | EntryKind::Onchain)
+    }
+}
+
+/// Mouvement réglé du nœud, vu de son propre bilan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    pub timestamp: i64,
+    pub node: String,
+    pub kind: EntryKind,
+    /// Montant reçu (positif) ou envoyé (négatif), frais exclus
+    pub amount_msat: i64,
+    /// Frais payés par le nœud
+    pub fee_msat: u64,
+    /// Hash du paiement, txid ou canaux entrant et sortant d'un relais
+    pub reference: String,
+    pub description: Option<String>,
+    /// Cours d'un bitcoin le jour du mouvement, si une devise est demandée
+    pub fiat_price: Option<f64>,
+}
+
+impl LedgerEntry {
+    /// Contre-valeur de `msat` au cours du jour du mouvement
+    pub fn fiat_value(&self, msat: i64) -> Option<f64> {
+        self.fiat_price.map(|price| msat as f64 / 100_000_000_000.0 * price)
+    }
+}
+
+/// Export demandé : format, nœud (tous si absent), fenêtre et devise de valorisation
+#[derive(Debug, Clone)]
+pub struct ExportRequest {
+    pub format: ExportFormat,
+    pub node: Option<String>,
+    pub from: i64,
+    pub to: i64,
+    pub currency: Option<String>,
+}
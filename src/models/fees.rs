@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+
+/// Frais suggérés par Sparkseer pour les pairs de nos canaux
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuggestedFees {
+    pub channel_peers: Vec<ChannelPeerFees>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelPeerFees {
+    pub pubkey: String,
+    pub suggested_fees: Vec<SuggestedFee>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SuggestedFee {
+    pub passive_fee_rate: u32,
+    pub active_fee_rate: u32,
+}
+
+/// Suggestion Sparkseer suivie par la stratégie `sparkseer`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SparkseerMode {
+    /// Frais qui attirent les relais sans chercher à les maximiser
+    Passive,
+    /// Frais plus agressifs, pour les canaux bien placés
+    Active,
+}
+
+/// Calcul du taux de frais de chaque canal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FeeStrategy {
+    /// Suit les frais suggérés par Sparkseer pour le pair du canal
+    Sparkseer { mode: SparkseerMode },
+    /// Frais d'autant plus élevés que la part locale du canal est faible
+    Balance { min_ppm: u32, max_ppm: u32 },
+    /// Relève les frais des canaux dont le volume sortant progresse d'une fenêtre
+    /// à l'autre et baisse ceux dont il recule ou qui ne routent rien, de `step_percent`
+    /// à chaque passe
+    Volume {
+        min_ppm: u32,
+        max_ppm: u32,
+        step_percent: u32,
+        /// Durée de chacune des deux fenêtres comparées, en secondes
+        window_secs: u64,
+    },
+}
+
+impl FeeStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeeStrategy::Sparkseer { .. } => "sparkseer",
+            FeeStrategy::Balance { .. } => "balance",
+            FeeStrategy::Volume { .. } => "volume",
+        }
+    }
+}
+
+impl Default for FeeStrategy {
+    fn default() -> Self {
+        FeeStrategy::Sparkseer {
+            mode: SparkseerMode::Passive,
+        }
+    }
+}
+
+/// Configuration du gestionnaire de frais d'un nœud
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FeePolicy {
+    pub strategy: FeeStrategy,
+    /// Écart en dessous duquel le taux d'un canal n'est pas modifié, en ppm
+    pub min_step_ppm: u32,
+    /// Délai minimal entre deux modifications d'un même canal, en secondes
+    pub cooldown_secs: u64,
+    /// Consigne les modifications sans les appliquer au nœud
+    pub dry_run: bool,
+    /// Période entre deux passes, en secondes
+    pub interval_secs: u64,
+}
+
+impl Default for FeePolicy {
+    fn default() -> Self {
+        Self {
+            strategy: FeeStrategy::default(),
+            min_step_ppm: 10,
+            cooldown_secs: 86_400,
+            dry_run: true,
+            interval_secs: 3600,
+        }
+    }
+}
+
+/// Modification du taux de frais d'un canal, telle qu'enregistrée dans l'historique
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeChange {
+    pub timestamp: i64,
+    pub channel_id: String,
+    pub remote_pubkey: String,
+    /// Stratégie qui a proposé la modification
+    pub strategy: String,
+    pub old_fee_rate_ppm: u32,
+    pub new_fee_rate_ppm: u32,
+    pub reason: String,
+    /// Faux pour une modification seulement simulée (`dry_run`)
+    pub applied: bool,
+}
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::models::lightning::{ChannelStatus, ForwardingEvent};
+
+/// Relevé de l'état global d'un nœud, moyenné sur l'intervalle qu'il représente
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NodeSnapshot {
+    /// Horodatage du dernier relevé de l'intervalle
+    pub timestamp: i64,
+    pub capacity: u64,
+    pub channels: u32,
+    pub local_balance: u64,
+    pub remote_balance: u64,
+    /// Statistiques Sparkseer du dernier relevé, absentes si l'API était injoignable
+    pub stats: Option<Value>,
+}
+
+/// Relevé d'un canal, moyenné sur l'intervalle qu'il représente.
+///
+/// L'état et les frais sont ceux du dernier relevé de l'intervalle.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChannelSnapshot {
+    pub timestamp: i64,
+    pub capacity: u64,
+    pub local_balance: u64,
+    pub remote_balance: u64,
+    pub status: ChannelStatus,
+    pub base_fee_msat: Option<u64>,
+    pub fee_rate_ppm: Option<u32>,
+    pub remote_base_fee_msat: Option<u64>,
+    pub remote_fee_rate_ppm: Option<u32>,
+}
+
+/// Fenêtre d'historique demandée, bornes incluses (secondes Unix)
+#[derive(Debug, Clone, Copy)]
+pub struct HistoryRange {
+    pub from: i64,
+    pub to: i64,
+    /// Nombre maximal de points renvoyés ; les relevés sont regroupés au-delà
+    pub points: u32,
+}
+
+impl HistoryRange {
+    /// Durée couverte par chaque point renvoyé
+    pub fn bucket(&self) -> i64 {
+        let span = (self.to - self.from).max(1);
+        let points = i64::from(self.points.max(1));
+        (span + points - 1) / points
+    }
+}
+
+/// Activité de routage d'un canal sur une fenêtre
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChannelForwards {
+    pub channel_id: String,
+    /// Relais entrés par ce canal
+    pub forwards_in: u64,
+    /// Relais sortis par ce canal
+    pub forwards_out: u64,
+    pub amt_in_msat: u64,
+    pub amt_out_msat: u64,
+    /// Frais des relais sortis par ce canal, qui rémunère la liquidité sortante
+    pub fee_msat: u64,
+    /// Frais des relais entrés par ce canal
+    pub inbound_fee_msat: u64,
+}
+
+/// Relais enregistrés sur une fenêtre, avec leurs totaux par canal
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ForwardsReport {
+    pub from: i64,
+    pub to: i64,
+    /// Nombre total de relais de la fenêtre, au-delà de ceux listés
+    pub count: u64,
+    pub total_fee_msat: u64,
+    pub total_amt_out_msat: u64,
+    /// Relais les plus récents d'abord
+    pub forwards: Vec<ForwardingEvent>,
+    /// Canaux classés par frais décroissants
+    pub channels: Vec<ChannelForwards>,
+}
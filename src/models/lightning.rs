@@ -1,22 +1,147 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Channel {
     pub id: String,
+    /// Identifiant `bloc x tx x sortie` utilisé par l'historique de routage,
+    /// absent tant que le canal n'est pas confirmé
+    #[serde(default)]
+    pub short_channel_id: Option<String>,
     pub capacity: u64,
     pub local_balance: u64,
     pub remote_balance: u64,
     pub remote_pubkey: String,
     pub status: ChannelStatus,
+    /// Politique que nous appliquons aux paiements sortant par ce canal
+    #[serde(default)]
+    pub local_policy: Option<ChannelPolicy>,
+    /// Politique annoncée par le pair, inconnue tant que le canal n'est pas annoncé
+    #[serde(default)]
+    pub remote_policy: Option<ChannelPolicy>,
+    /// Blocs d'attente imposés à nos fonds après une fermeture forcée
+    #[serde(default)]
+    pub csv_delay: Option<u32>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum ChannelStatus {
     Active,
     Inactive,
     Pending,
 }
 
+/// Étape d'un canal qui attend une transaction on-chain
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PendingState {
+    /// Transaction de financement pas encore confirmée
+    PendingOpen,
+    /// Fermeture coopérative en cours de négociation
+    PendingClose,
+    /// Transaction de fermeture diffusée, en attente de confirmation
+    WaitingClose,
+    /// Fermeture forcée confirmée, fonds bloqués jusqu'à maturité
+    ForceClosing,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingChannel {
+    pub channel_id: String,
+    pub remote_pubkey: String,
+    pub capacity: u64,
+    pub state: PendingState,
+    pub funding_txid: String,
+    pub closing_txid: Option<String>,
+    /// Confirmations de la transaction attendue : financement à l'ouverture, fermeture sinon
+    pub confirmations: Option<u32>,
+    /// Blocs restant avant que nos fonds redeviennent disponibles
+    pub blocks_til_maturity: Option<u32>,
+    /// Hauteur de bloc à laquelle nos fonds redeviennent disponibles
+    pub maturity_height: Option<u32>,
+    /// Solde bloqué tant que le canal est en attente, en sats
+    pub limbo_balance: u64,
+}
+
+/// Canaux en attente, avec la hauteur de bloc à laquelle ils ont été lus
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PendingChannels {
+    pub block_height: u32,
+    pub channels: Vec<PendingChannel>,
+}
+
+/// Politique de routage d'un côté d'un canal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChannelPolicy {
+    pub base_fee_msat: u64,
+    pub fee_rate_ppm: u32,
+    /// Delta CLTV exigé pour relayer un HTLC, en blocs
+    pub time_lock_delta: u32,
+    pub min_htlc_msat: u64,
+    pub max_htlc_msat: u64,
+    /// Frais entrants, négatifs pour une remise sur les paiements arrivant par ce canal
+    #[serde(default)]
+    pub inbound_base_fee_msat: i32,
+    #[serde(default)]
+    pub inbound_fee_rate_ppm: i32,
+    #[serde(default)]
+    pub disabled: bool,
+}
+
+/// Modification d'une politique de routage : seuls les champs renseignés changent
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PolicyUpdate {
+    pub base_fee_msat: Option<u64>,
+    pub fee_rate_ppm: Option<u32>,
+    pub time_lock_delta: Option<u32>,
+    pub min_htlc_msat: Option<u64>,
+    pub max_htlc_msat: Option<u64>,
+    pub inbound_base_fee_msat: Option<i32>,
+    pub inbound_fee_rate_ppm: Option<i32>,
+}
+
+impl PolicyUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.base_fee_msat.is_none()
+            && self.fee_rate_ppm.is_none()
+            && self.time_lock_delta.is_none()
+            && self.min_htlc_msat.is_none()
+            && self.max_htlc_msat.is_none()
+            && self.inbound_base_fee_msat.is_none()
+            && self.inbound_fee_rate_ppm.is_none()
+    }
+
+    /// Politique obtenue en appliquant la modification à `policy`
+    pub fn apply(&self, policy: &ChannelPolicy) -> ChannelPolicy {
+        ChannelPolicy {
+            base_fee_msat: self.base_fee_msat.unwrap_or(policy.base_fee_msat),
+            fee_rate_ppm: self.fee_rate_ppm.unwrap_or(policy.fee_rate_ppm),
+            time_lock_delta: self.time_lock_delta.unwrap_or(policy.time_lock_delta),
+            min_htlc_msat: self.min_htlc_msat.unwrap_or(policy.min_htlc_msat),
+            max_htlc_msat: self.max_htlc_msat.unwrap_or(policy.max_htlc_msat),
+            inbound_base_fee_msat: self
+                .inbound_base_fee_msat
+                .unwrap_or(policy.inbound_base_fee_msat),
+            inbound_fee_rate_ppm: self
+                .inbound_fee_rate_ppm
+                .unwrap_or(policy.inbound_fee_rate_ppm),
+            disabled: policy.disabled,
+        }
+    }
+}
+
+/// Politique locale d'un canal avant et après modification
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PolicyChange {
+    pub channel_id: String,
+    pub before: ChannelPolicy,
+    /// Politique relue sur le nœud après la modification
+    pub after: Option<ChannelPolicy>,
+    /// Refus du nœud pour ce canal, les autres canaux étant tout de même modifiés
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Transaction {
     pub id: String,
@@ -24,19 +149,30 @@ pub struct Transaction {
     pub fee: u64,
     pub timestamp: i64,
     pub type_: TransactionType,
+    /// Vrai pour un paiement envoyé ou une transaction débitant le portefeuille on-chain
+    #[serde(default)]
+    pub outgoing: bool,
     pub status: TransactionStatus,
+    /// Mémo de la facture, ou message joint à un keysend
     pub description: Option<String>,
+    /// Enregistrements TLV personnalisés du paiement, valeurs en hexadécimal
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub custom_records: BTreeMap<u64, String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub enum TransactionType {
     Payment,
     Invoice,
+    /// Paiement spontané reçu sans facture
+    Keysend,
     ChannelOpen,
     ChannelClose,
+    /// Transaction du portefeuille on-chain, hors ouverture et fermeture de canal
+    Onchain,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum TransactionStatus {
     Completed,
     Pending,
@@ -50,4 +186,493 @@ pub struct NodeInfo {
     pub capacity: u64,
     pub channels: u32,
     pub version: String,
-} 
\ No newline at end of file
+    /// `None` si le nœud annonce un réseau que Lightdash ne connaît pas
+    pub network: Option<Network>,
+    pub capabilities: NodeCapabilities,
+}
+
+/// Fonctionnalités optionnelles offertes par le backend du nœud
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct NodeCapabilities {
+    /// Création d'offres BOLT12
+    pub bolt12_offers: bool,
+    /// Paiement d'offres BOLT12
+    pub bolt12_payments: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Network {
+    Bitcoin,
+    Testnet,
+    Signet,
+    Regtest,
+    Simnet,
+}
+
+impl std::fmt::Display for Network {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Network::Bitcoin => write!(f, "bitcoin"),
+            Network::Testnet => write!(f, "testnet"),
+            Network::Signet => write!(f, "signet"),
+            Network::Regtest => write!(f, "regtest"),
+            Network::Simnet => write!(f, "simnet"),
+        }
+    }
+}
+
+impl std::str::FromStr for Network {
+    type Err = String;
+
+    /// Accepte les noms employés par LND (`mainnet`), Core Lightning et Eclair
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "bitcoin" | "mainnet" => Ok(Network::Bitcoin),
+            "testnet" | "testnet3" | "testnet4" => Ok(Network::Testnet),
+            "signet" => Ok(Network::Signet),
+            "regtest" => Ok(Network::Regtest),
+            "simnet" => Ok(Network::Simnet),
+            other => Err(format!("Réseau inconnu: {}", other)),
+        }
+    }
+}
+
+/// HTLC relayé par le nœud, d'un canal entrant vers un canal sortant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForwardingEvent {
+    /// Date du règlement, en secondes Unix
+    pub timestamp: i64,
+    pub chan_id_in: String,
+    pub chan_id_out: String,
+    /// Montant reçu sur le canal entrant, en msat
+    pub amt_in_msat: u64,
+    /// Montant transmis sur le canal sortant, en msat
+    pub amt_out_msat: u64,
+    /// Frais gagnés (`amt_in_msat - amt_out_msat`), en msat
+    pub fee_msat: u64,
+}
+
+/// Nœud déclaré dans le registre, tel que listé par l'API (sans ses identifiants)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NodeSummary {
+    pub id: String,
+    pub name: String,
+    pub backend: String,
+    pub url: String,
+}
+
+/// Part d'un nœud dans le portefeuille : somme de ses canaux et de ses frais.
+///
+/// Capacité, soldes et frais payés sont en sats ; les frais de routage gagnés, en msat,
+/// couvrent les 30 derniers jours. Un nœud injoignable garde des montants nuls et `error`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NodePortfolio {
+    pub id: String,
+    pub name: String,
+    pub pubkey: Option<String>,
+    pub capacity: u64,
+    pub local_balance: u64,
+    pub remote_balance: u64,
+    pub fees_paid: u64,
+    pub fees_earned_msat: u64,
+    pub error: Option<String>,
+}
+
+/// Portefeuille de tous les nœuds du registre : les totaux additionnent les montants
+/// de chaque nœud, dans les mêmes unités (sats, sauf `total_fees_earned_msat`)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Portfolio {
+    pub nodes: Vec<NodePortfolio>,
+    pub total_capacity: u64,
+    pub total_local_balance: u64,
+    pub total_remote_balance: u64,
+    pub total_fees_paid: u64,
+    pub total_fees_earned_msat: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PaymentResult {
+    pub payment_hash: String,
+    pub preimage: Option<String>,
+    pub status: TransactionStatus,
+    pub amount: u64,
+    pub fee: u64,
+    pub failure_reason: Option<String>,
+    /// Routes des parties abouties, une par partie d'un paiement multi-chemins
+    #[serde(default)]
+    pub routes: Vec<PaymentRoute>,
+    /// Nombre de tentatives (HTLC envoyés), 0 si le backend ne le communique pas
+    #[serde(default)]
+    pub attempts: u32,
+}
+
+/// Route empruntée par une partie d'un paiement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentRoute {
+    pub hops: Vec<RouteHop>,
+    /// Montant envoyé sur la route, frais compris, en msat
+    pub amount_msat: u64,
+    pub fee_msat: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteHop {
+    pub pubkey: String,
+    /// Canal emprunté pour atteindre ce nœud, au format `bloc x tx x sortie`
+    pub channel_id: String,
+    /// Montant transmis à ce nœud, en msat
+    pub amount_msat: u64,
+    /// Frais facturés par ce nœud pour relayer le paiement, en msat
+    pub fee_msat: u64,
+}
+
+/// Route candidate proposée par le nœud, avant tout paiement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteQuote {
+    pub hops: Vec<RouteHop>,
+    pub hop_count: usize,
+    /// Montant envoyé sur la route, frais compris, en msat
+    pub amount_msat: u64,
+    pub fee_msat: u64,
+    /// Délai CLTV cumulé de la route, en blocs à partir de la hauteur actuelle
+    pub time_lock: u32,
+}
+
+impl RouteQuote {
+    pub fn new(hops: Vec<RouteHop>, amount_msat: u64, fee_msat: u64, time_lock: u32) -> Self {
+        Self {
+            hop_count: hops.len(),
+            hops,
+            amount_msat,
+            fee_msat,
+            time_lock,
+        }
+    }
+}
+
+/// Contraintes d'un paiement sortant ; un champ absent laisse le nœud décider
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PaymentOptions {
+    /// Frais maximaux en sats
+    pub max_fee: Option<u64>,
+    /// Frais maximaux en millionièmes du montant ; le plus strict des deux plafonds s'applique
+    pub max_fee_ppm: Option<u32>,
+    /// Nombre maximal de parties ; 1 désactive le paiement multi-chemins
+    pub max_parts: Option<u32>,
+    /// Délai maximal pour aboutir le paiement, en secondes
+    pub timeout: Option<u32>,
+    /// Canal de sortie imposé, tel qu'identifié par `/api/channels`
+    pub outgoing_channel: Option<String>,
+    /// Clé publique du dernier nœud avant le destinataire
+    pub last_hop: Option<String>,
+}
+
+impl PaymentOptions {
+    /// Plafond de frais en msat pour un paiement de `amount_msat`, s'il y en a un
+    pub fn fee_limit_msat(&self, amount_msat: u64) -> Option<u64> {
+        let fixed = self.max_fee.map(|fee| fee * 1000);
+        let proportional = self
+            .max_fee_ppm
+            .map(|ppm| amount_msat * ppm as u64 / 1_000_000);
+        match (fixed, proportional) {
+            (Some(fixed), Some(proportional)) => Some(fixed.min(proportional)),
+            (fixed, proportional) => fixed.or(proportional),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Invoice {
+    pub payment_hash: String,
+    pub payment_request: String,
+    pub amount: u64,
+    pub description: String,
+    pub expiry: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChannelOpenResult {
+    pub funding_txid: String,
+    pub output_index: Option<u32>,
+}
+
+/// Canal à ouvrir dans une ouverture groupée ou financée par PSBT
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChannelRequest {
+    pub pubkey: String,
+    /// Montant engagé en sats
+    pub amount: u64,
+    /// Sats offerts au pair à l'ouverture
+    #[serde(default)]
+    pub push_amount: u64,
+    /// Canal non annoncé au réseau
+    #[serde(default)]
+    pub private: bool,
+    /// Politique de routage initiale ; le nœud applique ses valeurs par défaut sinon
+    pub base_fee_msat: Option<u64>,
+    pub fee_rate_ppm: Option<u32>,
+}
+
+/// Ouverture de plusieurs canaux financés par une seule transaction
+#[derive(Debug, Clone, Deserialize)]
+pub struct BatchOpenRequest {
+    pub channels: Vec<ChannelRequest>,
+    /// Frais de la transaction de financement ; le nœud choisit sinon
+    pub target_conf: Option<u32>,
+    pub sat_per_vbyte: Option<u64>,
+}
+
+/// Financement par PSBT en attente de la transaction signée par un portefeuille externe
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PsbtFunding {
+    /// Identifiant à rappeler pour vérifier, finaliser ou annuler le financement
+    pub funding_id: String,
+    pub funding_address: String,
+    /// Montant de la sortie de financement en sats
+    pub amount: u64,
+    /// PSBT non signée contenant la sortie de financement, quand le nœud la fournit
+    pub psbt: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChannelCloseResult {
+    pub channel_id: String,
+    pub closing_txid: Option<String>,
+    #[serde(default)]
+    pub force: bool,
+    /// Blocs pendant lesquels nos fonds restent bloqués après une fermeture forcée
+    #[serde(default)]
+    pub csv_delay: Option<u32>,
+}
+
+/// Options de fermeture d'un canal ; sans frais précisés, le nœud choisit lui-même
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CloseOptions {
+    /// Fermeture unilatérale, sans la coopération du pair
+    #[serde(default)]
+    pub force: bool,
+    /// Confirmation explicite, exigée pour une fermeture forcée
+    #[serde(default)]
+    pub confirm: bool,
+    /// Objectif de confirmation en blocs
+    pub target_conf: Option<u32>,
+    pub sat_per_vbyte: Option<u64>,
+    /// Adresse recevant nos fonds
+    pub delivery_address: Option<String>,
+    /// Taux maximal accepté pendant la négociation avec le pair
+    pub max_fee_per_vbyte: Option<u64>,
+}
+
+/// Coût on-chain estimé d'une fermeture, avant de la lancer
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CloseEstimate {
+    pub channel_id: String,
+    pub force: bool,
+    pub sat_per_vbyte: u64,
+    /// Taille estimée des transactions à notre charge, en vbytes
+    pub vsize: u64,
+    /// Frais estimés en sats
+    pub fee: u64,
+    /// Solde récupéré une fois les frais estimés déduits
+    pub local_balance: u64,
+    pub csv_delay: Option<u32>,
+}
+
+/// Événement survenu sur un nœud, diffusé en direct sur `/api/events`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NodeEvent {
+    InvoiceSettled {
+        payment_hash: String,
+        amount: u64,
+    },
+    ChannelOpened {
+        channel_id: String,
+        remote_pubkey: String,
+        capacity: u64,
+    },
+    ChannelClosed {
+        channel_id: String,
+    },
+    ChannelActive {
+        channel_id: String,
+    },
+    ChannelInactive {
+        channel_id: String,
+    },
+    HtlcForwarded(ForwardingEvent),
+    PaymentUpdated {
+        payment_hash: String,
+        status: TransactionStatus,
+        amount: u64,
+        fee: u64,
+    },
+}
+
+/// Événement accompagné du nœud qui l'a émis
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeEventMessage {
+    pub node: String,
+    pub timestamp: i64,
+    #[serde(flatten)]
+    pub event: NodeEvent,
+}
+
+/// Facture BOLT11 décodée
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DecodedInvoice {
+    pub network: Network,
+    /// Montant demandé, absent pour une facture « montant libre »
+    pub amount_msat: Option<u64>,
+    pub timestamp: i64,
+    /// Durée de validité en secondes à partir de `timestamp`
+    pub expiry: u64,
+    pub payment_hash: String,
+    pub payment_secret: Option<String>,
+    /// Clé publique du destinataire, lue dans la facture ou retrouvée depuis la signature
+    pub payee: String,
+    pub description: Option<String>,
+    pub description_hash: Option<String>,
+    pub min_final_cltv_expiry: u64,
+    pub route_hints: Vec<Vec<RouteHintHop>>,
+    /// Numéros des bits de fonctionnalité positionnés
+    pub features: Vec<u16>,
+}
+
+impl DecodedInvoice {
+    pub fn expires_at(&self) -> i64 {
+        self.timestamp.saturating_add(self.expiry as i64)
+    }
+}
+
+/// Saut d'une indication de route privée (champ `r`)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RouteHintHop {
+    pub pubkey: String,
+    pub short_channel_id: String,
+    pub fee_base_msat: u32,
+    pub fee_proportional_millionths: u32,
+    pub cltv_expiry_delta: u16,
+}
+
+/// Offre BOLT12 créée par le nœud
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Offer {
+    pub offer_id: Option<String>,
+    pub bolt12: String,
+    /// Montant en sats, absent pour une offre à montant libre
+    pub amount: Option<u64>,
+    pub description: String,
+}
+
+/// Offre BOLT12 décodée (`lno1...`)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DecodedOffer {
+    /// Réseaux acceptés ; une offre sans champ `offer_chains` vaut pour Bitcoin.
+    /// Les chaînes inconnues de Lightdash sont ignorées.
+    pub chains: Vec<Network>,
+    /// En msat, ou dans la plus petite unité de `currency` quand elle est présente
+    pub amount: Option<u64>,
+    /// Devise ISO 4217 quand le montant n'est pas exprimé en bitcoin
+    pub currency: Option<String>,
+    pub description: Option<String>,
+    pub issuer: Option<String>,
+    pub issuer_id: Option<String>,
+    pub absolute_expiry: Option<i64>,
+    pub quantity_max: Option<u64>,
+    /// Nombre de chemins aveuglés menant à l'émetteur
+    pub paths: usize,
+    pub features: Vec<u16>,
+}
+
+/// Facture BOLT12 décodée (`lni1...`), avec l'offre dont elle découle
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DecodedBolt12Invoice {
+    pub offer: DecodedOffer,
+    pub payer_note: Option<String>,
+    pub quantity: Option<u64>,
+    pub amount_msat: u64,
+    pub created_at: i64,
+    /// Durée de validité en secondes à partir de `created_at`
+    pub relative_expiry: u64,
+    pub payment_hash: String,
+    pub node_id: String,
+    pub paths: usize,
+    pub features: Vec<u16>,
+}
+
+impl DecodedBolt12Invoice {
+    pub fn expires_at(&self) -> i64 {
+        self.created_at.saturating_add(self.relative_expiry as i64)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DecodedBolt12 {
+    Offer(DecodedOffer),
+    Invoice(DecodedBolt12Invoice),
+}
+
+/// Requête LNURL-pay (LUD-06), éventuellement issue d'une Lightning Address (LUD-16)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LnurlPayRequest {
+    pub domain: String,
+    pub callback: String,
+    /// Bornes du montant en msat
+    pub min_sendable: u64,
+    pub max_sendable: u64,
+    /// Métadonnées brutes, dont le hash doit figurer dans la facture
+    pub metadata: String,
+    pub description: String,
+    /// Adresse annoncée dans les métadonnées (`text/identifier` ou `text/email`)
+    pub identifier: Option<String>,
+    /// Longueur maximale du commentaire accepté par le destinataire (LUD-12)
+    pub comment_allowed: u16,
+}
+
+/// Requête LNURL-withdraw (LUD-03)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LnurlWithdrawRequest {
+    pub domain: String,
+    pub callback: String,
+    pub k1: String,
+    pub default_description: String,
+    /// Bornes du montant en msat
+    pub min_withdrawable: u64,
+    pub max_withdrawable: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LnurlRequest {
+    Pay(LnurlPayRequest),
+    Withdraw(LnurlWithdrawRequest),
+}
+
+/// Action proposée par le destinataire une fois le paiement réussi (LUD-09)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "tag", rename_all = "lowercase")]
+pub enum SuccessAction {
+    Message {
+        message: String,
+    },
+    Url {
+        description: String,
+        url: String,
+    },
+    /// Message chiffré avec la preimage du paiement, transmis tel quel
+    Aes {
+        description: String,
+        ciphertext: String,
+        iv: String,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LnurlPayResult {
+    #[serde(flatten)]
+    pub payment: PaymentResult,
+    pub success_action: Option<SuccessAction>,
+}
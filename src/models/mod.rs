@@ -1 +1,8 @@
-pub mod lightning; 
\ No newline at end of file
+pub mod export;
+pub mod fees;
+pub mod history;
+pub mod lightning;
+pub mod onchain;
+pub mod pnl;
+pub mod rebalance;
+pub mod yields;
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+/// Soldes du portefeuille on-chain du nœud, en sats
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct WalletBalance {
+    pub confirmed: u64,
+    pub unconfirmed: u64,
+    /// Fonds réservés par le nœud (financement en cours, réserve des canaux à ancres)
+    pub locked: u64,
+}
+
+/// Sortie non dépensée du portefeuille
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Utxo {
+    pub txid: String,
+    pub output_index: u32,
+    pub address: String,
+    pub amount: u64,
+    pub confirmations: u32,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AddressType {
+    /// Segwit v0 (`bc1q...`)
+    #[default]
+    P2wkh,
+    /// Taproot (`bc1p...`)
+    P2tr,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OnchainAddress {
+    pub address: String,
+    pub address_type: AddressType,
+}
+
+/// Envoi on-chain depuis le portefeuille du nœud
+#[derive(Debug, Clone, Deserialize)]
+pub struct OnchainSendRequest {
+    pub address: String,
+    /// Montant en sats, absent quand `send_all` vide le portefeuille
+    pub amount: Option<u64>,
+    #[serde(default)]
+    pub send_all: bool,
+    pub target_conf: Option<u32>,
+    pub sat_per_vbyte: Option<u64>,
+    /// Sorties à dépenser (`txid:index`) ; le nœud choisit lui-même sinon
+    #[serde(default)]
+    pub utxos: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OnchainSendResult {
+    pub txid: String,
+}
@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+
+/// Nature d'un coût imputé à un canal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CostKind {
+    /// Frais on-chain de la transaction de financement, payés par l'ouvreur
+    OpeningFee,
+    /// Frais on-chain de la transaction de fermeture
+    ClosingFee,
+    /// Frais de routage d'un paiement circulaire ayant rechargé le canal
+    Rebalance,
+    /// Prix d'un bail de liquidité entrante
+    Lease,
+}
+
+/// Coût payé pour un canal, tel que le rapporte le nœud
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelCost {
+    pub timestamp: i64,
+    /// Short channel id quand il est connu, identifiant du canal sinon
+    pub channel_id: String,
+    /// Pair du canal, utile pour les canaux déjà fermés
+    pub remote_pubkey: Option<String>,
+    pub kind: CostKind,
+    pub amount_msat: u64,
+    /// Transaction ou hash du paiement à l'origine du coût
+    pub reference: String,
+}
+
+/// Compte de résultat d'un canal sur une fenêtre
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChannelPnl {
+    pub channel_id: String,
+    pub remote_pubkey: String,
+    /// Faux pour un canal fermé depuis, qui n'apparaît que par ses relais ou ses coûts
+    pub open: bool,
+    pub capital: u64,
+    pub routing_fee_msat: u64,
+    pub rebalance_fee_msat: u64,
+    pub opening_fee_msat: u64,
+    pub closing_fee_msat: u64,
+    pub lease_fee_msat: u64,
+    pub net_profit_msat: i64,
+    /// Bénéfice net rapporté au capital engagé sur la fenêtre, en pourcentage
+    pub roi: Option<f64>,
+}
+
+/// Compte de résultat de l'ensemble des canaux ouverts avec un pair
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PeerPnl {
+    pub remote_pubkey: String,
+    pub channels: u32,
+    pub capital: u64,
+    pub routing_fee_msat: u64,
+    pub costs_msat: u64,
+    pub net_profit_msat: i64,
+    pub roi: Option<f64>,
+}
+
+/// Compte de résultat du nœud sur une fenêtre
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PnlReport {
+    pub from: i64,
+    pub to: i64,
+    pub capital: u64,
+    pub routing_fee_msat: u64,
+    pub rebalance_fee_msat: u64,
+    pub opening_fee_msat: u64,
+    pub closing_fee_msat: u64,
+    pub lease_fee_msat: u64,
+    pub net_profit_msat: i64,
+    pub roi: Option<f64>,
+    /// Canaux classés par bénéfice net décroissant
+    pub channels: Vec<ChannelPnl>,
+    /// Pairs classés par bénéfice net décroissant
+    pub peers: Vec<PeerPnl>,
+}
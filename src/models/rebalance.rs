@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::lightning::TransactionStatus;
+
+/// Conduite à tenir quand un rééquilibrage échoue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryStrategy {
+    /// Nombre maximal de paiements tentés, le premier compris
+    pub attempts: u32,
+    /// Divise le montant par deux après chaque échec
+    pub halve_on_failure: bool,
+    /// Montant en dessous duquel on renonce plutôt que de diviser encore, en sats
+    pub min_amount: u64,
+}
+
+impl Default for RetryStrategy {
+    fn default() -> Self {
+        Self {
+            attempts: 3,
+            halve_on_failure: true,
+            min_amount: 10_000,
+        }
+    }
+}
+
+/// Déplacement de liquidité d'un canal vers un autre par un paiement circulaire
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RebalanceRequest {
+    /// Nœud concerné, le nœud par défaut si absent
+    #[serde(default)]
+    pub node: Option<String>,
+    /// Canal dont la liquidité locale est déplacée, tel qu'identifié par `/api/channels`
+    pub outgoing_channel: String,
+    /// Canal qui reçoit la liquidité
+    pub incoming_channel: String,
+    /// Montant à déplacer, en sats
+    pub amount: u64,
+    /// Frais maximaux, en millionièmes du montant
+    pub max_fee_ppm: u32,
+    #[serde(default)]
+    pub retry: RetryStrategy,
+    /// Délai maximal de chaque tentative, en secondes
+    #[serde(default)]
+    pub timeout: Option<u32>,
+}
+
+/// Origine d'un rééquilibrage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RebalanceTrigger {
+    /// Demandé via `POST /api/rebalance`
+    Manual,
+    /// Lancé par la tâche de fond d'une `RebalancePolicy`
+    Policy,
+}
+
+impl RebalanceTrigger {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RebalanceTrigger::Manual => "manual",
+            RebalanceTrigger::Policy => "policy",
+        }
+    }
+}
+
+/// Tentative de rééquilibrage, telle qu'enregistrée dans l'historique
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RebalanceAttempt {
+    pub timestamp: i64,
+    pub trigger: RebalanceTrigger,
+    pub outgoing_channel: String,
+    pub incoming_channel: String,
+    pub amount: u64,
+    pub max_fee_ppm: u32,
+    pub status: TransactionStatus,
+    /// Frais payés, nuls pour une tentative échouée
+    pub fee_msat: u64,
+    pub payment_hash: Option<String>,
+    pub failure_reason: Option<String>,
+}
+
+/// Issue d'un rééquilibrage, toutes tentatives confondues
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RebalanceResult {
+    pub status: TransactionStatus,
+    /// Montant effectivement déplacé, en sats
+    pub amount: u64,
+    pub fee_msat: u64,
+    pub attempts: Vec<RebalanceAttempt>,
+}
+
+/// Objectif de la tâche de rééquilibrage automatique d'un nœud.
+///
+/// Les canaux dont la part locale dépasse `max_local_ratio` alimentent ceux
+/// qui passent sous `min_local_ratio`, vers le milieu de la fourchette.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RebalancePolicy {
+    pub min_local_ratio: f64,
+    pub max_local_ratio: f64,
+    pub max_fee_ppm: u32,
+    /// Montant maximal d'un rééquilibrage, en sats
+    pub max_amount: u64,
+    pub retry: RetryStrategy,
+    /// Période entre deux passes, en secondes
+    pub interval_secs: u64,
+}
+
+impl Default for RebalancePolicy {
+    fn default() -> Self {
+        Self {
+            min_local_ratio: 0.3,
+            max_local_ratio: 0.7,
+            max_fee_ppm: 500,
+            max_amount: 1_000_000,
+            retry: RetryStrategy::default(),
+            interval_secs: 3600,
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+/// Rendement réalisé d'un canal sur une fenêtre
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelYield {
+    pub channel_id: String,
+    pub short_channel_id: Option<String>,
+    pub remote_pubkey: String,
+    pub active: bool,
+    pub capacity: u64,
+    /// Capital engagé : solde local moyen sur la fenêtre, ou solde actuel sans historique
+    pub capital: u64,
+    /// Frais gagnés sur les relais sortis par ce canal
+    pub fee_msat: u64,
+    /// Frais des relais entrés par ce canal, gagnés sur un autre canal
+    pub inbound_fee_msat: u64,
+    pub forwards: u64,
+    pub amt_out_msat: u64,
+    /// Rendement annualisé du capital engagé, en pourcentage
+    pub annualized_return: Option<f64>,
+}
+
+/// Rendement réalisé de l'ensemble des canaux ouverts avec un pair
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerYield {
+    pub remote_pubkey: String,
+    pub channels: u32,
+    pub capital: u64,
+    pub fee_msat: u64,
+    pub forwards: u64,
+    pub annualized_return: Option<f64>,
+}
+
+/// Rendement réalisé du nœud sur une fenêtre, calculé à partir des relais enregistrés
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct YieldReport {
+    pub from: i64,
+    pub to: i64,
+    pub capital: u64,
+    /// Frais gagnés sur la fenêtre, canaux fermés compris
+    pub fee_msat: u64,
+    /// Part de `fee_msat` gagnée par des canaux fermés depuis
+    pub closed_channels_fee_msat: u64,
+    pub annualized_return: Option<f64>,
+    /// Canaux classés par frais gagnés décroissants
+    pub channels: Vec<ChannelYield>,
+    /// Pairs classés par frais gagnés décroissants
+    pub peers: Vec<PeerYield>,
+}
@@ -0,0 +1,1287 @@
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{info, instrument};
+
+use super::{
+    reject_options, u64_from_any, BackendKind, ForwardingPage, NodeBackend, DEFAULT_INVOICE_EXPIRY,
+};
+use crate::models::lightning::{
+    BatchOpenRequest, Channel, ChannelCloseResult, ChannelOpenResult, ChannelPolicy,
+    ChannelRequest, ChannelStatus, CloseOptions, ForwardingEvent, Invoice, NodeCapabilities,
+    NodeInfo, Offer, PaymentOptions, PaymentResult, PendingChannel, PendingChannels,
+    PendingState, PolicyUpdate, PsbtFunding, RouteHop, RouteQuote, Transaction, TransactionStatus, TransactionType,
+};
+use crate::models::onchain::{AddressType, OnchainSendRequest, Utxo, WalletBalance};
+use crate::models::pnl::{ChannelCost, CostKind};
+use crate::services::bolt11;
+
+/// Canal de communication avec un nœud Core Lightning.
+///
+/// Le plugin `clnrest` et la socket `lightning-rpc` exposent les mêmes
+/// commandes avec les mêmes réponses : seul le transport change.
+#[async_trait]
+pub trait ClnTransport: Send + Sync {
+    fn kind(&self) -> BackendKind;
+
+    async fn call_raw(&self, method: &str, params: Value) -> Result<Value>;
+}
+
+/// Backend Core Lightning, quel que soit le transport utilisé
+pub struct ClnBackend<T: ClnTransport> {
+    transport: T,
+}
+
+/// Backend pour le plugin `clnrest` de Core Lightning
+pub type ClnRestBackend = ClnBackend<ClnRest>;
+
+/// Transport HTTP vers le plugin `clnrest`
+pub struct ClnRest {
+    client: Client,
+    base_url: String,
+    rune: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClnGetInfo {
+    id: String,
+    #[serde(default)]
+    alias: String,
+    version: String,
+    #[serde(default)]
+    network: String,
+    #[serde(default)]
+    num_active_channels: u32,
+    #[serde(default)]
+    num_inactive_channels: u32,
+    #[serde(default)]
+    num_pending_channels: u32,
+    #[serde(default)]
+    blockheight: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClnFunds {
+    #[serde(default)]
+    outputs: Vec<ClnFundOutput>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClnFundOutput {
+    txid: String,
+    output: u32,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    amount_msat: u64,
+    #[serde(default)]
+    address: String,
+    /// `unconfirmed`, `confirmed`, `spent` ou `immature`
+    status: String,
+    /// Réservée par une opération en cours (financement, PSBT)
+    #[serde(default)]
+    reserved: bool,
+    blockheight: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClnWithdrawResponse {
+    txid: String,
+}
+
+/// Événements comptables du plugin `bookkeeper`, livré par défaut avec CLN
+#[derive(Debug, Deserialize)]
+struct ClnAccountEvents {
+    #[serde(default)]
+    events: Vec<ClnAccountEvent>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClnAccountEvent {
+    account: String,
+    #[serde(rename = "type")]
+    type_: String,
+    #[serde(default)]
+    tag: String,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    credit_msat: u64,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    debit_msat: u64,
+    /// Transaction dépensant la sortie, quand l'événement est une dépense
+    txid: Option<String>,
+    outpoint: Option<String>,
+    #[serde(default)]
+    timestamp: i64,
+    blockheight: Option<u32>,
+    /// Frais de routage payés, portés par l'événement débiteur d'un paiement
+    #[serde(deserialize_with = "u64_from_any", default)]
+    fees_msat: u64,
+    /// Vrai pour les deux jambes d'un paiement circulaire vers notre nœud
+    #[serde(default)]
+    is_rebalance: bool,
+    payment_id: Option<String>,
+}
+
+impl ClnAccountEvent {
+    fn txid(&self) -> Option<&str> {
+        self.txid
+            .as_deref()
+            .or_else(|| self.outpoint.as_deref()?.split(':').next())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ClnPeerChannels {
+    #[serde(default)]
+    channels: Vec<ClnPeerChannel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClnPeerChannel {
+    peer_id: String,
+    #[serde(default)]
+    peer_connected: bool,
+    state: String,
+    short_channel_id: Option<String>,
+    channel_id: Option<String>,
+    /// Sens du canal vu de notre nœud, tel qu'attendu par l'option `exclude` de `pay`
+    direction: Option<u8>,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    total_msat: u64,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    to_us_msat: u64,
+    #[serde(default)]
+    updates: ClnChannelUpdates,
+    /// Blocs d'attente de nos fonds après une fermeture unilatérale
+    our_to_self_delay: Option<u32>,
+    #[serde(default)]
+    funding_txid: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClnTransactions {
+    #[serde(default)]
+    transactions: Vec<ClnTransaction>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClnTransaction {
+    hash: String,
+    /// 0 tant que la transaction n'est pas confirmée
+    #[serde(default)]
+    blockheight: u32,
+}
+
+/// Politiques du canal (`updates` de `listpeerchannels`, CLN 24.02 et suivants)
+#[derive(Debug, Default, Deserialize)]
+struct ClnChannelUpdates {
+    local: Option<ClnChannelPolicy>,
+    remote: Option<ClnChannelPolicy>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClnChannelPolicy {
+    #[serde(deserialize_with = "u64_from_any", default)]
+    htlc_minimum_msat: u64,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    htlc_maximum_msat: u64,
+    #[serde(default)]
+    cltv_expiry_delta: u32,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    fee_base_msat: u64,
+    #[serde(default)]
+    fee_proportional_millionths: u32,
+}
+
+impl From<ClnChannelPolicy> for ChannelPolicy {
+    fn from(policy: ClnChannelPolicy) -> Self {
+        ChannelPolicy {
+            base_fee_msat: policy.fee_base_msat,
+            fee_rate_ppm: policy.fee_proportional_millionths,
+            time_lock_delta: policy.cltv_expiry_delta,
+            min_htlc_msat: policy.htlc_minimum_msat,
+            max_htlc_msat: policy.htlc_maximum_msat,
+            // CLN n'applique pas de frais entrants
+            inbound_base_fee_msat: 0,
+            inbound_fee_rate_ppm: 0,
+            disabled: false,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ClnPays {
+    #[serde(default)]
+    pays: Vec<ClnPay>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClnPay {
+    payment_hash: String,
+    status: String,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    amount_msat: u64,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    amount_sent_msat: u64,
+    #[serde(default)]
+    created_at: i64,
+    description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClnInvoices {
+    #[serde(default)]
+    invoices: Vec<ClnInvoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClnInvoice {
+    #[serde(default)]
+    label: String,
+    payment_hash: String,
+    status: String,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    amount_msat: u64,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    amount_received_msat: u64,
+    paid_at: Option<i64>,
+    #[serde(default)]
+    expires_at: i64,
+    description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClnPayResponse {
+    payment_hash: String,
+    payment_preimage: Option<String>,
+    status: String,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    amount_msat: u64,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    amount_sent_msat: u64,
+}
+
+impl From<ClnPayResponse> for PaymentResult {
+    fn from(pay: ClnPayResponse) -> Self {
+        PaymentResult {
+            payment_hash: pay.payment_hash,
+            preimage: pay.payment_preimage,
+            status: match pay.status.as_str() {
+                "complete" => TransactionStatus::Completed,
+                "failed" => TransactionStatus::Failed,
+                _ => TransactionStatus::Pending,
+            },
+            amount: pay.amount_msat / 1000,
+            fee: pay.amount_sent_msat.saturating_sub(pay.amount_msat) / 1000,
+            failure_reason: None,
+            // `pay` ne détaille ni les routes empruntées ni les tentatives
+            routes: Vec::new(),
+            attempts: 0,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ClnGetRoute {
+    #[serde(default)]
+    route: Vec<ClnRouteHop>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClnRouteHop {
+    id: String,
+    channel: String,
+    /// Montant qui doit parvenir à ce nœud, frais des sauts suivants compris
+    #[serde(deserialize_with = "u64_from_any", default)]
+    amount_msat: u64,
+    #[serde(default)]
+    delay: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClnInvoiceResponse {
+    payment_hash: String,
+    bolt11: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClnOfferResponse {
+    offer_id: String,
+    bolt12: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClnFetchInvoiceResponse {
+    invoice: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClnFundChannelResponse {
+    txid: String,
+    outnum: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClnMultiFundChannelResponse {
+    txid: String,
+    #[serde(default)]
+    channel_ids: Vec<ClnFundedChannel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClnFundedChannel {
+    id: String,
+    channel_id: String,
+    outnum: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClnFundChannelStart {
+    funding_address: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClnSendPsbtResponse {
+    txid: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClnCloseResponse {
+    txid: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClnFeerates {
+    perkb: ClnFeeratesPerKb,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClnFeeratesPerKb {
+    #[serde(default)]
+    estimates: Vec<ClnFeerateEstimate>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClnFeerateEstimate {
+    blockcount: u32,
+    feerate: u64,
+}
+
+/// Traduit l'état d'un canal CLN vers le statut exposé par l'API
+pub(crate) fn cln_channel_status(state: &str, peer_connected: bool) -> ChannelStatus {
+    match state {
+        "CHANNELD_NORMAL" if peer_connected => ChannelStatus::Active,
+        "OPENINGD" | "CHANNELD_AWAITING_LOCKIN" | "DUALOPEND_OPEN_INIT"
+        | "DUALOPEND_AWAITING_LOCKIN" => ChannelStatus::Pending,
+        _ => ChannelStatus::Inactive,
+    }
+}
+
+/// État d'attente d'un canal CLN, `None` pour un canal ouvert ou définitivement fermé
+fn cln_pending_state(state: &str) -> Option<PendingState> {
+    match state {
+        "OPENINGD" | "CHANNELD_AWAITING_LOCKIN" | "DUALOPEND_OPEN_INIT"
+        | "DUALOPEND_AWAITING_LOCKIN" => Some(PendingState::PendingOpen),
+        "CHANNELD_SHUTTING_DOWN" | "CLOSINGD_SIGEXCHANGE" => Some(PendingState::PendingClose),
+        "CLOSINGD_COMPLETE" | "AWAITING_UNILATERAL" | "FUNDING_SPEND_SEEN" => {
+            Some(PendingState::WaitingClose)
+        }
+        "ONCHAIN" => Some(PendingState::ForceClosing),
+        _ => None,
+    }
+}
+
+/// Facteur de risque de `getroute` : coût annuel en pourcentage attribué au blocage des fonds
+const ROUTE_RISK_FACTOR: u32 = 10;
+
+/// Génère un label unique, exigé par CLN pour chaque facture
+pub(crate) fn invoice_label() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    format!("lightdash-{}", nanos)
+}
+
+#[derive(Debug, Deserialize)]
+struct ClnForwards {
+    #[serde(default)]
+    forwards: Vec<ClnForward>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClnForward {
+    /// Renuméroté à chaque changement d'état : un relais réglé après la lecture
+    /// précédente réapparaît ainsi au-delà du curseur
+    #[serde(default)]
+    updated_index: u64,
+    in_channel: String,
+    out_channel: Option<String>,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    in_msat: u64,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    out_msat: u64,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    fee_msat: u64,
+    status: String,
+    #[serde(default)]
+    received_time: f64,
+}
+
+impl ClnRestBackend {
+    pub fn new(base_url: String, rune: String) -> Self {
+        info!(base_url = %base_url, "Création du backend Core Lightning REST");
+        ClnBackend::with_transport(ClnRest {
+            client: Client::new(),
+            base_url,
+            rune,
+        })
+    }
+}
+
+#[async_trait]
+impl ClnTransport for ClnRest {
+    fn kind(&self) -> BackendKind {
+        BackendKind::Cln
+    }
+
+    async fn call_raw(&self, method: &str, params: Value) -> Result<Value> {
+        let url = format!("{}/v1/{}", self.base_url, method);
+        let response = self
+            .client
+            .post(&url)
+            .header("Rune", &self.rune)
+            .json(&params)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.json().await?)
+    }
+}
+
+impl<T: ClnTransport> ClnBackend<T> {
+    pub fn with_transport(transport: T) -> Self {
+        Self { transport }
+    }
+
+    async fn call<R: DeserializeOwned>(&self, method: &str, params: Value) -> Result<R> {
+        let response = self.transport.call_raw(method, params).await?;
+        Ok(serde_json::from_value(response)?)
+    }
+
+    /// Canaux à exclure (`scid/sens`) pour que le paiement sorte par `channel_id` :
+    /// `pay` ne sait pas imposer le premier saut autrement.
+    async fn other_channels(&self, channel_id: &str) -> Result<Vec<String>> {
+        let response: ClnPeerChannels = self.call("listpeerchannels", json!({})).await?;
+        let (selected, others): (Vec<_>, Vec<_>) =
+            response.channels.into_iter().partition(|c| {
+                c.short_channel_id.as_deref() == Some(channel_id)
+                    || c.channel_id.as_deref() == Some(channel_id)
+            });
+        if selected.is_empty() {
+            bail!("Canal {} introuvable parmi les canaux du nœud", channel_id);
+        }
+        Ok(others
+            .into_iter()
+            .filter_map(|c| Some(format!("{}/{}", c.short_channel_id?, c.direction?)))
+            .collect())
+    }
+}
+
+#[async_trait]
+impl<T: ClnTransport> NodeBackend for ClnBackend<T> {
+    fn kind(&self) -> BackendKind {
+        self.transport.kind()
+    }
+
+    fn capabilities(&self) -> NodeCapabilities {
+        NodeCapabilities {
+            bolt12_offers: true,
+            bolt12_payments: true,
+        }
+    }
+
+    #[instrument(skip(self), err)]
+    async fn get_node_info(&self) -> Result<NodeInfo> {
+        let info: ClnGetInfo = self.call("getinfo", json!({})).await?;
+        let capacity = self
+            .list_channels()
+            .await?
+            .iter()
+            .map(|c| c.capacity)
+            .sum();
+        Ok(NodeInfo {
+            pubkey: info.id,
+            alias: info.alias,
+            capacity,
+            channels: info.num_active_channels
+                + info.num_inactive_channels
+                + info.num_pending_channels,
+            version: info.version,
+            network: info.network.parse().ok(),
+            capabilities: self.capabilities(),
+        })
+    }
+
+    #[instrument(skip(self), err)]
+    async fn list_channels(&self) -> Result<Vec<Channel>> {
+        let response: ClnPeerChannels = self.call("listpeerchannels", json!({})).await?;
+        Ok(response
+            .channels
+            .into_iter()
+            .map(|c| Channel {
+                id: c
+                    .short_channel_id
+                    .clone()
+                    .or(c.channel_id)
+                    .unwrap_or_default(),
+                short_channel_id: c.short_channel_id,
+                capacity: c.total_msat / 1000,
+                local_balance: c.to_us_msat / 1000,
+                remote_balance: c.total_msat.saturating_sub(c.to_us_msat) / 1000,
+                remote_pubkey: c.peer_id,
+                status: cln_channel_status(&c.state, c.peer_connected),
+                local_policy: c.updates.local.map(Into::into),
+                remote_policy: c.updates.remote.map(Into::into),
+                csv_delay: c.our_to_self_delay,
+            })
+            .collect())
+    }
+
+    async fn list_pending_channels(&self) -> Result<PendingChannels> {
+        let info: ClnGetInfo = self.call("getinfo", json!({})).await?;
+        let response: ClnPeerChannels = self.call("listpeerchannels", json!({})).await?;
+        let transactions: ClnTransactions = self.call("listtransactions", json!({})).await?;
+        let heights: HashMap<String, u32> = transactions
+            .transactions
+            .into_iter()
+            .map(|t| (t.hash, t.blockheight))
+            .collect();
+
+        // CLN n'expose ni la transaction de fermeture ni la hauteur de maturité
+        let channels = response
+            .channels
+            .into_iter()
+            .filter_map(|c| {
+                let state = cln_pending_state(&c.state)?;
+                let confirmations = heights.get(&c.funding_txid).map(|&height| {
+                    if height == 0 {
+                        0
+                    } else {
+                        info.blockheight.saturating_sub(height) + 1
+                    }
+                });
+                Some(PendingChannel {
+                    channel_id: c.short_channel_id.or(c.channel_id).unwrap_or_default(),
+                    remote_pubkey: c.peer_id,
+                    capacity: c.total_msat / 1000,
+                    state,
+                    funding_txid: c.funding_txid,
+                    closing_txid: None,
+                    confirmations,
+                    blocks_til_maturity: None,
+                    maturity_height: None,
+                    limbo_balance: c.to_us_msat / 1000,
+                })
+            })
+            .collect();
+
+        Ok(PendingChannels {
+            block_height: info.blockheight,
+            channels,
+        })
+    }
+
+    async fn wallet_balance(&self) -> Result<WalletBalance> {
+        let funds: ClnFunds = self.call("listfunds", json!({})).await?;
+        let mut balance = WalletBalance::default();
+        for output in funds.outputs.iter().filter(|o| o.status != "spent") {
+            let amount = output.amount_msat / 1000;
+            if output.reserved {
+                balance.locked += amount;
+            } else if output.status == "confirmed" {
+                balance.confirmed += amount;
+            } else {
+                balance.unconfirmed += amount;
+            }
+        }
+        Ok(balance)
+    }
+
+    async fn list_utxos(&self) -> Result<Vec<Utxo>> {
+        let info: ClnGetInfo = self.call("getinfo", json!({})).await?;
+        let funds: ClnFunds = self.call("listfunds", json!({})).await?;
+        Ok(funds
+            .outputs
+            .into_iter()
+            .filter(|o| o.status != "spent")
+            .map(|o| Utxo {
+                confirmations: o
+                    .blockheight
+                    .map_or(0, |height| info.blockheight.saturating_sub(height) + 1),
+                txid: o.txid,
+                output_index: o.output,
+                address: o.address,
+                amount: o.amount_msat / 1000,
+            })
+            .collect())
+    }
+
+    async fn new_address(&self, address_type: AddressType) -> Result<String> {
+        let cln_type = match address_type {
+            AddressType::P2wkh => "bech32",
+            AddressType::P2tr => "p2tr",
+        };
+        let response: Value = self
+            .call("newaddr", json!({ "addresstype": cln_type }))
+            .await?;
+        Ok(response[cln_type]
+            .as_str()
+            .context("Adresse absente de la réponse CLN")?
+            .to_string())
+    }
+
+    async fn send_onchain(&self, request: &OnchainSendRequest) -> Result<String> {
+        let mut params = json!({
+            "destination": request.address,
+            "satoshi": match request.amount {
+                Some(amount) if !request.send_all => json!(amount),
+                _ => json!("all"),
+            },
+        });
+        if let Some(rate) = request.sat_per_vbyte {
+            params["feerate"] = json!(format!("{}perkb", rate * 1000));
+        } else if let Some(blocks) = request.target_conf {
+            params["feerate"] = json!(format!("{}blocks", blocks));
+        }
+        if !request.utxos.is_empty() {
+            params["utxos"] = json!(request.utxos);
+        }
+        let response: ClnWithdrawResponse = self.call("withdraw", params).await?;
+        Ok(response.txid)
+    }
+
+    /// Reconstitue les transactions on-chain à partir des événements du plugin
+    /// `bookkeeper` : les mouvements des comptes de canal priment sur ceux du portefeuille
+    async fn list_onchain_transactions(&self) -> Result<Vec<Transaction>> {
+        let response: ClnAccountEvents = self.call("bkpr-listaccountevents", json!({})).await?;
+        let mut transactions: BTreeMap<String, Transaction> = BTreeMap::new();
+        let mut wallet_net: HashMap<String, i64> = HashMap::new();
+        let mut fees: HashMap<String, u64> = HashMap::new();
+
+        for event in &response.events {
+            let Some(txid) = event.txid() else { continue };
+            let type_ = match (event.type_.as_str(), event.tag.as_str()) {
+                ("onchain_fee", _) => {
+                    *fees.entry(txid.to_string()).or_default() +=
+                        event.credit_msat.saturating_sub(event.debit_msat) / 1000;
+                    continue;
+                }
+                ("chain", "channel_open") => TransactionType::ChannelOpen,
+                ("chain", "channel_close") => TransactionType::ChannelClose,
+                ("chain", "deposit" | "withdrawal") if event.account == "wallet" => {
+                    *wallet_net.entry(txid.to_string()).or_default() +=
+                        event.credit_msat as i64 - event.debit_msat as i64;
+                    TransactionType::Onchain
+                }
+                _ => continue,
+            };
+            let channel_event = !matches!(type_, TransactionType::Onchain);
+            let transaction = transactions.entry(txid.to_string()).or_insert_with(|| Transaction {
+                id: txid.to_string(),
+                amount: 0,
+                fee: 0,
+                timestamp: event.timestamp,
+                type_: TransactionType::Onchain,
+                status: TransactionStatus::Pending,
+                description: None,
+                outgoing: false,
+                custom_records: BTreeMap::new(),
+            });
+            if event.blockheight.unwrap_or(0) > 0 {
+                transaction.status = TransactionStatus::Completed;
+            }
+            if channel_event {
+                transaction.outgoing = matches!(type_, TransactionType::ChannelOpen);
+                transaction.type_ = type_;
+                transaction.amount = event.credit_msat.max(event.debit_msat) / 1000;
+            }
+        }
+
+        let mut transactions: Vec<Transaction> = transactions
+            .into_values()
+            .map(|mut t| {
+                if matches!(t.type_, TransactionType::Onchain) {
+                    let net = wallet_net.get(&t.id).copied().unwrap_or(0);
+                    t.amount = net.unsigned_abs() / 1000;
+                    t.outgoing = net < 0;
+                }
+                t.fee = fees.get(&t.id).copied().unwrap_or(0);
+                t
+            })
+            .collect();
+        transactions.sort_by_key(|t| Reverse(t.timestamp));
+        Ok(transactions)
+    }
+
+    /// Coûts tirés des comptes de canal du plugin `bookkeeper` : frais on-chain des
+    /// transactions d'ouverture et de fermeture, frais de bail et de rééquilibrage
+    #[instrument(skip(self), err)]
+    async fn channel_costs(&self, since: i64) -> Result<Vec<ChannelCost>> {
+        let response: ClnAccountEvents = self.call("bkpr-listaccountevents", json!({})).await?;
+        let peers: ClnPeerChannels = self.call("listpeerchannels", json!({})).await?;
+        // Les comptes de canal sont nommés d'après l'identifiant long du canal
+        let channels: HashMap<String, (String, String)> = peers
+            .channels
+            .into_iter()
+            .filter_map(|c| {
+                let id = c.channel_id?;
+                let scid = c.short_channel_id.unwrap_or_else(|| id.clone());
+                Some((id, (scid, c.peer_id)))
+            })
+            .collect();
+        let channel = |account: &str| match channels.get(account) {
+            Some((scid, peer)) => (scid.clone(), Some(peer.clone())),
+            None => (account.to_string(), None),
+        };
+
+        let mut fees: HashMap<&str, u64> = HashMap::new();
+        for event in response.events.iter().filter(|e| e.type_ == "onchain_fee") {
+            if let Some(txid) = event.txid() {
+                *fees.entry(txid).or_default() +=
+                    event.credit_msat.saturating_sub(event.debit_msat);
+            }
+        }
+
+        let mut costs = Vec::new();
+        // Paiements circulaires : compte rechargé et frais payés, par paiement
+        let mut rebalances: HashMap<&str, (Option<&ClnAccountEvent>, u64, i64)> = HashMap::new();
+        for event in &response.events {
+            if event.timestamp < since || event.account == "wallet" || event.account == "external"
+            {
+                continue;
+            }
+            let kind = match (event.type_.as_str(), event.tag.as_str()) {
+                ("chain", "channel_open") => CostKind::OpeningFee,
+                ("chain", "channel_close") => CostKind::ClosingFee,
+                ("channel", "lease_fee") if event.debit_msat > 0 => {
+                    let (channel_id, remote_pubkey) = channel(&event.account);
+                    costs.push(ChannelCost {
+                        timestamp: event.timestamp,
+                        channel_id,
+                        remote_pubkey,
+                        kind: CostKind::Lease,
+                        amount_msat: event.debit_msat,
+                        reference: event.account.clone(),
+                    });
+                    continue;
+                }
+                ("channel", _) if event.is_rebalance => {
+                    let Some(payment_id) = event.payment_id.as_deref() else { continue };
+                    let entry = rebalances.entry(payment_id).or_insert((None, 0, event.timestamp));
+                    if event.credit_msat > 0 {
+                        entry.0 = Some(event);
+                    }
+                    entry.1 += event.fees_msat;
+                    continue;
+                }
+                _ => continue,
+            };
+            let Some(txid) = event.txid() else { continue };
+            let Some(&fee) = fees.get(txid).filter(|&&fee| fee > 0) else { continue };
+            let (channel_id, remote_pubkey) = channel(&event.account);
+            costs.push(ChannelCost {
+                timestamp: event.timestamp,
+                channel_id,
+                remote_pubkey,
+                kind,
+                amount_msat: fee,
+                reference: txid.to_string(),
+            });
+        }
+
+        for (payment_id, (credited, fee_msat, timestamp)) in rebalances {
+            let Some(credited) = credited.filter(|_| fee_msat > 0) else { continue };
+            let (channel_id, remote_pubkey) = channel(&credited.account);
+            costs.push(ChannelCost {
+                timestamp,
+                channel_id,
+                remote_pubkey,
+                kind: CostKind::Rebalance,
+                amount_msat: fee_msat,
+                reference: payment_id.to_string(),
+            });
+        }
+
+        Ok(costs)
+    }
+
+    #[instrument(skip(self), err)]
+    async fn list_transactions(&self) -> Result<Vec<Transaction>> {
+        let pays: ClnPays = self.call("listpays", json!({})).await?;
+        let invoices: ClnInvoices = self.call("listinvoices", json!({})).await?;
+
+        let mut transactions: Vec<Transaction> = pays
+            .pays
+            .into_iter()
+            .map(|p| Transaction {
+                id: p.payment_hash,
+                amount: p.amount_msat / 1000,
+                fee: p.amount_sent_msat.saturating_sub(p.amount_msat) / 1000,
+                timestamp: p.created_at,
+                type_: TransactionType::Payment,
+                status: match p.status.as_str() {
+                    "complete" => TransactionStatus::Completed,
+                    "failed" => TransactionStatus::Failed,
+                    _ => TransactionStatus::Pending,
+                },
+                description: p.description,
+                outgoing: true,
+                custom_records: BTreeMap::new(),
+            })
+            .collect();
+
+        transactions.extend(invoices.invoices.into_iter().map(|i| Transaction {
+            id: i.payment_hash,
+            amount: if i.amount_received_msat > 0 {
+                i.amount_received_msat / 1000
+            } else {
+                i.amount_msat / 1000
+            },
+            fee: 0,
+            // CLN ne renvoie pas la date de création d'une facture
+            timestamp: i.paid_at.unwrap_or(i.expires_at),
+            // Le plugin keysend enregistre chaque paiement reçu comme une facture
+            // « keysend-... » ; ses enregistrements TLV ne sont pas conservés.
+            type_: if i.label.starts_with("keysend-") {
+                TransactionType::Keysend
+            } else {
+                TransactionType::Invoice
+            },
+            status: match i.status.as_str() {
+                "paid" => TransactionStatus::Completed,
+                "expired" => TransactionStatus::Failed,
+                _ => TransactionStatus::Pending,
+            },
+            description: i.description,
+            outgoing: false,
+            custom_records: BTreeMap::new(),
+        }));
+
+        transactions.sort_by_key(|t| Reverse(t.timestamp));
+        Ok(transactions)
+    }
+
+    #[instrument(skip(self), err)]
+    async fn forwarding_history(
+        &self,
+        start_time: i64,
+        offset: u64,
+        limit: u32,
+    ) -> Result<ForwardingPage> {
+        let response: ClnForwards = self
+            .call(
+                "listforwards",
+                json!({ "index": "updated", "start": offset, "limit": limit }),
+            )
+            .await?;
+
+        let next_offset = response
+            .forwards
+            .iter()
+            .map(|f| f.updated_index + 1)
+            .max()
+            .unwrap_or(offset);
+
+        Ok(ForwardingPage {
+            events: response
+                .forwards
+                .into_iter()
+                .filter(|f| f.status == "settled" && f.received_time as i64 >= start_time)
+                .map(|f| ForwardingEvent {
+                    timestamp: f.received_time as i64,
+                    chan_id_in: f.in_channel,
+                    chan_id_out: f.out_channel.unwrap_or_default(),
+                    amt_in_msat: f.in_msat,
+                    amt_out_msat: f.out_msat,
+                    fee_msat: f.fee_msat,
+                })
+                .collect(),
+            next_offset,
+        })
+    }
+
+    async fn send_payment(
+        &self,
+        payment_request: &str,
+        options: &PaymentOptions,
+    ) -> Result<PaymentResult> {
+        reject_options(
+            self.kind(),
+            &[
+                (options.max_parts.is_some(), "La limite du nombre de parties d'un paiement"),
+                (options.last_hop.is_some(), "Le choix du dernier saut d'un paiement"),
+            ],
+        )?;
+
+        let mut params = json!({ "bolt11": payment_request });
+        let amount_msat = bolt11::decode(payment_request)?.amount_msat.unwrap_or(0);
+        if let Some(max_fee) = options.fee_limit_msat(amount_msat) {
+            params["maxfee"] = json!(max_fee);
+        }
+        if let Some(timeout) = options.timeout {
+            params["retry_for"] = json!(timeout);
+        }
+        if let Some(channel_id) = &options.outgoing_channel {
+            params["exclude"] = json!(self.other_channels(channel_id).await?);
+        }
+        let pay: ClnPayResponse = self.call("pay", params).await?;
+        Ok(pay.into())
+    }
+
+    async fn send_keysend(
+        &self,
+        pubkey: &str,
+        amount: u64,
+        custom_records: &BTreeMap<u64, Vec<u8>>,
+    ) -> Result<PaymentResult> {
+        let extratlvs: BTreeMap<String, String> = custom_records
+            .iter()
+            .map(|(record, value)| (record.to_string(), hex::encode(value)))
+            .collect();
+        let pay: ClnPayResponse = self
+            .call(
+                "keysend",
+                json!({
+                    "destination": pubkey,
+                    "amount_msat": amount * 1000,
+                    "extratlvs": extratlvs,
+                }),
+            )
+            .await?;
+        Ok(pay.into())
+    }
+
+    /// `getroute` ne propose qu'une route : la moins chère selon le facteur de risque
+    async fn query_routes(&self, destination: &str, amount: u64) -> Result<Vec<RouteQuote>> {
+        let response: ClnGetRoute = self
+            .call(
+                "getroute",
+                json!({
+                    "id": destination,
+                    "amount_msat": amount * 1000,
+                    "riskfactor": ROUTE_RISK_FACTOR,
+                }),
+            )
+            .await?;
+        let Some(first) = response.route.first() else {
+            return Ok(Vec::new());
+        };
+        let (amount_msat, time_lock) = (first.amount_msat, first.delay);
+
+        // Les frais d'un nœud sont l'écart entre ce qu'il reçoit et ce qu'il transmet
+        let next_amounts: Vec<u64> = response
+            .route
+            .iter()
+            .skip(1)
+            .map(|hop| hop.amount_msat)
+            .collect();
+        let hops: Vec<RouteHop> = response
+            .route
+            .into_iter()
+            .enumerate()
+            .map(|(i, hop)| RouteHop {
+                fee_msat: next_amounts
+                    .get(i)
+                    .map_or(0, |next| hop.amount_msat.saturating_sub(*next)),
+                pubkey: hop.id,
+                channel_id: hop.channel,
+                amount_msat: hop.amount_msat,
+            })
+            .collect();
+        Ok(vec![RouteQuote::new(
+            hops,
+            amount_msat,
+            amount_msat.saturating_sub(amount * 1000),
+            time_lock,
+        )])
+    }
+
+    async fn create_invoice(
+        &self,
+        amount: u64,
+        description: &str,
+        expiry: Option<u64>,
+    ) -> Result<Invoice> {
+        let expiry = expiry.unwrap_or(DEFAULT_INVOICE_EXPIRY);
+        let invoice: ClnInvoiceResponse = self
+            .call(
+                "invoice",
+                json!({
+                    "amount_msat": amount * 1000,
+                    "label": invoice_label(),
+                    "description": description,
+                    "expiry": expiry,
+                }),
+            )
+            .await?;
+        Ok(Invoice {
+            payment_hash: invoice.payment_hash,
+            payment_request: invoice.bolt11,
+            amount,
+            description: description.to_string(),
+            expiry,
+        })
+    }
+
+    async fn create_offer(&self, amount: Option<u64>, description: &str) -> Result<Offer> {
+        let offer: ClnOfferResponse = self
+            .call(
+                "offer",
+                json!({
+                    "amount": amount.map_or("any".to_string(), |a| format!("{}msat", a * 1000)),
+                    "description": description,
+                }),
+            )
+            .await?;
+        Ok(Offer {
+            offer_id: Some(offer.offer_id),
+            bolt12: offer.bolt12,
+            amount,
+            description: description.to_string(),
+        })
+    }
+
+    /// Demande une facture à l'émetteur de l'offre, puis la paie
+    async fn pay_offer(
+        &self,
+        offer: &str,
+        amount: Option<u64>,
+        payer_note: Option<&str>,
+    ) -> Result<PaymentResult> {
+        let mut params = json!({ "offer": offer });
+        if let Some(amount) = amount {
+            let amount_msat = amount
+                .checked_mul(1000)
+                .with_context(|| format!("Montant hors limites: {} sats", amount))?;
+            params["amount_msat"] = json!(amount_msat);
+        }
+        if let Some(note) = payer_note {
+            params["payer_note"] = json!(note);
+        }
+        let fetched: ClnFetchInvoiceResponse = self.call("fetchinvoice", params).await?;
+        // `pay` accepte aussi bien les factures BOLT11 que BOLT12
+        let pay: ClnPayResponse = self.call("pay", json!({ "bolt11": fetched.invoice })).await?;
+        Ok(pay.into())
+    }
+
+    async fn open_channel(&self, pubkey: &str, amount: u64) -> Result<ChannelOpenResult> {
+        let response: ClnFundChannelResponse = self
+            .call("fundchannel", json!({ "id": pubkey, "amount": amount }))
+            .await?;
+        Ok(ChannelOpenResult {
+            funding_txid: response.txid,
+            output_index: Some(response.outnum),
+        })
+    }
+
+    /// La politique initiale est appliquée par `setchannel` une fois le financement diffusé
+    async fn open_channels(&self, request: &BatchOpenRequest) -> Result<Vec<ChannelOpenResult>> {
+        let destinations: Vec<Value> = request
+            .channels
+            .iter()
+            .map(|channel| {
+                json!({
+                    "id": channel.pubkey,
+                    "amount": channel.amount,
+                    "announce": !channel.private,
+                    "push_msat": channel.push_amount * 1000,
+                })
+            })
+            .collect();
+        let mut params = json!({ "destinations": destinations });
+        if let Some(rate) = request.sat_per_vbyte {
+            params["feerate"] = json!(format!("{}perkb", rate * 1000));
+        } else if let Some(blocks) = request.target_conf {
+            params["feerate"] = json!(format!("{}blocks", blocks));
+        }
+        let response: ClnMultiFundChannelResponse =
+            self.call("multifundchannel", params).await?;
+
+        for channel in &request.channels {
+            if channel.base_fee_msat.is_none() && channel.fee_rate_ppm.is_none() {
+                continue;
+            }
+            let Some(funded) = response.channel_ids.iter().find(|f| f.id == channel.pubkey) else {
+                continue;
+            };
+            let mut params = json!({ "id": funded.channel_id });
+            if let Some(base_fee) = channel.base_fee_msat {
+                params["feebase"] = json!(base_fee);
+            }
+            if let Some(fee_rate) = channel.fee_rate_ppm {
+                params["feeppm"] = json!(fee_rate);
+            }
+            let _: Value = self.call("setchannel", params).await?;
+        }
+
+        Ok(response
+            .channel_ids
+            .iter()
+            .map(|funded| ChannelOpenResult {
+                funding_txid: response.txid.clone(),
+                output_index: Some(funded.outnum),
+            })
+            .collect())
+    }
+
+    /// Financement v1 de CLN : la PSBT est construite par le portefeuille externe
+    /// vers l'adresse de financement, et le financement est identifié par le pair
+    async fn start_psbt_funding(&self, request: &ChannelRequest) -> Result<PsbtFunding> {
+        reject_options(
+            self.kind(),
+            &[(
+                request.base_fee_msat.is_some() || request.fee_rate_ppm.is_some(),
+                "La politique initiale d'un canal financé par PSBT",
+            )],
+        )?;
+        let start: ClnFundChannelStart = self
+            .call(
+                "fundchannel_start",
+                json!({
+                    "id": request.pubkey,
+                    "amount": request.amount,
+                    "announce": !request.private,
+                    "push_msat": request.push_amount * 1000,
+                }),
+            )
+            .await?;
+        Ok(PsbtFunding {
+            funding_id: request.pubkey.clone(),
+            funding_address: start.funding_address,
+            amount: request.amount,
+            psbt: None,
+        })
+    }
+
+    async fn verify_psbt_funding(&self, funding_id: &str, psbt: &str) -> Result<()> {
+        // Le pair signe la transaction d'engagement : la diffusion devient sans risque
+        let _: Value = self
+            .call("fundchannel_complete", json!({ "id": funding_id, "psbt": psbt }))
+            .await?;
+        Ok(())
+    }
+
+    async fn finalize_psbt_funding(
+        &self,
+        _funding_id: &str,
+        signed_psbt: &str,
+    ) -> Result<ChannelOpenResult> {
+        let response: ClnSendPsbtResponse = self
+            .call("sendpsbt", json!({ "psbt": signed_psbt }))
+            .await?;
+        Ok(ChannelOpenResult {
+            funding_txid: response.txid,
+            output_index: None,
+        })
+    }
+
+    async fn cancel_psbt_funding(&self, funding_id: &str) -> Result<()> {
+        let _: Value = self
+            .call("fundchannel_cancel", json!({ "id": funding_id }))
+            .await?;
+        Ok(())
+    }
+
+    async fn close_channel(
+        &self,
+        channel_id: &str,
+        options: &CloseOptions,
+    ) -> Result<ChannelCloseResult> {
+        // Sans délai, CLN bascule en fermeture unilatérale au bout de 48 h :
+        // une fermeture coopérative ne doit jamais devenir forcée à notre insu
+        let mut params = json!({
+            "id": channel_id,
+            "unilateraltimeout": if options.force { 1 } else { 0 },
+        });
+        if let Some(address) = &options.delivery_address {
+            params["destination"] = json!(address);
+        }
+
+        // `feerange` borne le taux négocié avec le pair ; CLN exige les deux bornes
+        let perkb = |rate: u64| format!("{}perkb", rate * 1000);
+        let feerange = match (
+            options.sat_per_vbyte,
+            options.target_conf,
+            options.max_fee_per_vbyte,
+        ) {
+            (Some(rate), _, max) => Some((perkb(rate), perkb(max.unwrap_or(rate)))),
+            (None, Some(blocks), max) => Some((
+                format!("{}blocks", blocks),
+                max.map_or_else(|| "urgent".to_string(), perkb),
+            )),
+            (None, None, Some(max)) => Some(("slow".to_string(), perkb(max))),
+            (None, None, None) => None,
+        };
+        if let Some((low, high)) = feerange {
+            params["feerange"] = json!([low, high]);
+        }
+
+        let response: ClnCloseResponse = self.call("close", params).await?;
+        Ok(ChannelCloseResult {
+            channel_id: channel_id.to_string(),
+            closing_txid: response.txid,
+            force: options.force,
+            csv_delay: None,
+        })
+    }
+
+    async fn estimate_fee_rate(&self, target_conf: u32) -> Result<u64> {
+        let feerates: ClnFeerates = self.call("feerates", json!({ "style": "perkb" })).await?;
+        // Estimation la plus lente qui confirme encore dans le délai demandé
+        let estimates = feerates.perkb.estimates;
+        let estimate = estimates
+            .iter()
+            .filter(|e| e.blockcount <= target_conf)
+            .max_by_key(|e| e.blockcount)
+            .or_else(|| estimates.iter().min_by_key(|e| e.blockcount))
+            .context("Aucune estimation de frais disponible sur le nœud")?;
+        Ok(estimate.feerate.div_ceil(1000))
+    }
+
+    /// Le delta CLTV est un réglage global de CLN, et les frais entrants n'existent pas
+    async fn set_channel_policy(
+        &self,
+        channel_id: &str,
+        update: &PolicyUpdate,
+        policy: &ChannelPolicy,
+    ) -> Result<()> {
+        reject_options(
+            self.kind(),
+            &[
+                (update.time_lock_delta.is_some(), "Le réglage du delta CLTV par canal"),
+                (
+                    update.inbound_base_fee_msat.is_some() || update.inbound_fee_rate_ppm.is_some(),
+                    "Le réglage des frais entrants",
+                ),
+            ],
+        )?;
+        let _: Value = self
+            .call(
+                "setchannel",
+                json!({
+                    "id": channel_id,
+                    "feebase": policy.base_fee_msat,
+                    "feeppm": policy.fee_rate_ppm,
+                    "htlcmin": policy.min_htlc_msat,
+                    "htlcmax": policy.max_htlc_msat,
+                }),
+            )
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,262 @@
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::UnixStream;
+use tracing::{debug, info};
+
+use super::cln::{ClnBackend, ClnTransport};
+use super::BackendKind;
+
+/// `lightningd` termine chaque réponse par une ligne vide
+const RESPONSE_TERMINATOR: &[u8] = b"\n\n";
+
+/// Backend Core Lightning via la socket JSON-RPC `lightning-rpc`
+pub type ClnRpcBackend = ClnBackend<ClnSocket>;
+
+/// Transport JSON-RPC 2.0 sur la socket unix de `lightningd`
+pub struct ClnSocket {
+    socket_path: PathBuf,
+    next_id: AtomicU64,
+}
+
+impl ClnRpcBackend {
+    pub fn new(socket_path: PathBuf) -> Result<Self> {
+        if !socket_path.exists() {
+            bail!("La socket Core Lightning {} n'existe pas", socket_path.display());
+        }
+        info!(socket = %socket_path.display(), "Création du backend Core Lightning RPC");
+        Ok(ClnBackend::with_transport(ClnSocket {
+            socket_path,
+            next_id: AtomicU64::new(1),
+        }))
+    }
+}
+
+#[async_trait]
+impl ClnTransport for ClnSocket {
+    fn kind(&self) -> BackendKind {
+        BackendKind::ClnRpc
+    }
+
+    async fn call_raw(&self, method: &str, params: Value) -> Result<Value> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": params,
+        });
+        debug!(method = %method, id = id, "Appel JSON-RPC Core Lightning");
+
+        // Une connexion par appel : lightningd traite les requêtes d'une même
+        // connexion dans l'ordre, ce qui bloquerait les appels concurrents.
+        let mut stream = UnixStream::connect(&self.socket_path)
+            .await
+            .with_context(|| format!("Connexion impossible à {}", self.socket_path.display()))?;
+        stream.write_all(&serde_json::to_vec(&request)?).await?;
+
+        let response = read_response(&mut stream).await?;
+
+        if let Some(error) = response.get("error") {
+            return Err(anyhow!(
+                "Erreur Core Lightning sur {}: {}",
+                method,
+                error
+                    .get("message")
+                    .and_then(Value::as_str)
+                    .unwrap_or("erreur inconnue")
+            ));
+        }
+        response
+            .get("result")
+            .cloned()
+            .ok_or_else(|| anyhow!("Réponse JSON-RPC sans résultat pour {}", method))
+    }
+}
+
+/// Lit une réponse JSON-RPC entière puis la décode une seule fois.
+///
+/// La connexion reste ouverte après la réponse : sa fin est repérée par la ligne
+/// vide finale, recherchée dans les seuls octets reçus depuis la lecture précédente.
+async fn read_response(stream: &mut UnixStream) -> Result<Value> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 8192];
+    let end = loop {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            if buffer.iter().all(u8::is_ascii_whitespace) {
+                bail!("Connexion fermée par lightningd avant la fin de la réponse");
+            }
+            break buffer.len();
+        }
+        let start = buffer.len().saturating_sub(RESPONSE_TERMINATOR.len() - 1);
+        buffer.extend_from_slice(&chunk[..read]);
+        if let Some(position) = buffer[start..]
+            .windows(RESPONSE_TERMINATOR.len())
+            .position(|window| window == RESPONSE_TERMINATOR)
+        {
+            break start + position;
+        }
+    };
+    serde_json::from_slice(&buffer[..end]).context("Réponse JSON-RPC de lightningd illisible")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::backends::NodeBackend;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::net::UnixListener;
+
+    /// Fausse socket `lightning-rpc` qui rejoue des réponses enregistrées, par méthode,
+    /// en morceaux de `chunk` octets, sans jamais fermer la connexion la première
+    struct FakeLightningd {
+        path: PathBuf,
+    }
+
+    impl FakeLightningd {
+        fn start(name: &str, replies: Vec<(&str, Value)>, chunk: usize) -> Self {
+            let path = std::env::temp_dir()
+                .join(format!("lightdash-{}-{}.sock", name, std::process::id()));
+            let _ = std::fs::remove_file(&path);
+            let listener = UnixListener::bind(&path).expect("socket de test");
+            let replies: Arc<HashMap<String, Value>> = Arc::new(
+                replies
+                    .into_iter()
+                    .map(|(method, reply)| (method.to_string(), reply))
+                    .collect(),
+            );
+            tokio::spawn(async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    tokio::spawn(Self::serve(stream, replies.clone(), chunk));
+                }
+            });
+            Self { path }
+        }
+
+        async fn serve(
+            mut stream: UnixStream,
+            replies: Arc<HashMap<String, Value>>,
+            chunk: usize,
+        ) {
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 1024];
+            let request: Value = loop {
+                let read = stream.read(&mut buffer).await.unwrap();
+                request.extend_from_slice(&buffer[..read]);
+                if let Ok(request) = serde_json::from_slice(&request) {
+                    break request;
+                }
+            };
+            let method = request["method"].as_str().unwrap();
+            let mut reply = replies[method].clone();
+            reply["jsonrpc"] = json!("2.0");
+            reply["id"] = request["id"].clone();
+            let mut bytes = serde_json::to_vec(&reply).unwrap();
+            bytes.extend_from_slice(RESPONSE_TERMINATOR);
+            for part in bytes.chunks(chunk) {
+                stream.write_all(part).await.unwrap();
+                stream.flush().await.unwrap();
+                tokio::time::sleep(Duration::from_millis(1)).await;
+            }
+            // Comme lightningd, la connexion reste ouverte jusqu'au client
+            let _ = stream.read(&mut buffer).await;
+        }
+
+        fn backend(&self) -> ClnRpcBackend {
+            ClnRpcBackend::new(self.path.clone()).unwrap()
+        }
+    }
+
+    impl Drop for FakeLightningd {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+
+    fn getinfo() -> Value {
+        json!({ "result": {
+            "id": "02a0f8e6c4b7e9d3c0a1b2c3d4e5f60718293a4b5c6d7e8f9a0b1c2d3e4f5a6b7c",
+            "alias": "cln-test",
+            "version": "v24.08",
+            "network": "regtest",
+            "num_active_channels": 1,
+            "num_inactive_channels": 0,
+            "num_pending_channels": 1,
+            "blockheight": 120
+        }})
+    }
+
+    fn listpeerchannels() -> Value {
+        json!({ "result": { "channels": [{
+            "peer_id": "03b1c2d3e4f5a6b7c8d9e0f1a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6",
+            "peer_connected": true,
+            "state": "CHANNELD_NORMAL",
+            "short_channel_id": "103x1x0",
+            "channel_id": "aa".repeat(32),
+            "direction": 0,
+            "total_msat": 2_000_000_000u64,
+            "to_us_msat": 1_500_000_000u64,
+            "funding_txid": "bb".repeat(32)
+        }]}})
+    }
+
+    #[tokio::test]
+    async fn replays_recorded_responses() {
+        let lightningd = FakeLightningd::start(
+            "success",
+            vec![("getinfo", getinfo()), ("listpeerchannels", listpeerchannels())],
+            8192,
+        );
+        let info = lightningd.backend().get_node_info().await.unwrap();
+        assert_eq!(info.alias, "cln-test");
+        assert_eq!(info.channels, 2);
+        assert_eq!(info.capacity, 2_000_000);
+
+        let channels = lightningd.backend().list_channels().await.unwrap();
+        assert_eq!(channels.len(), 1);
+        assert_eq!(channels[0].id, "103x1x0");
+        assert_eq!(channels[0].local_balance, 1_500_000);
+        assert_eq!(channels[0].remote_balance, 500_000);
+    }
+
+    #[tokio::test]
+    async fn surfaces_json_rpc_errors() {
+        let lightningd = FakeLightningd::start(
+            "error",
+            vec![(
+                "getinfo",
+                json!({ "error": { "code": -32601, "message": "Unknown command 'getinfo'" } }),
+            )],
+            8192,
+        );
+        let error = lightningd.backend().get_node_info().await.unwrap_err();
+        assert!(error.to_string().contains("Unknown command 'getinfo'"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn reassembles_responses_split_across_reads() {
+        // Réponse bien plus grande que le tampon de lecture, livrée par petits morceaux
+        let forwards: Vec<Value> = (0..500)
+            .map(|i| json!({ "in_channel": "103x1x0", "out_channel": "104x1x0",
+                             "fee_msat": i, "status": "settled", "received_time": 1.5 }))
+            .collect();
+        let lightningd = FakeLightningd::start(
+            "split",
+            vec![("listforwards", json!({ "result": { "forwards": forwards } }))],
+            333,
+        );
+        let socket = ClnSocket {
+            socket_path: lightningd.path.clone(),
+            next_id: AtomicU64::new(1),
+        };
+        let result = socket.call_raw("listforwards", json!({})).await.unwrap();
+        assert_eq!(result["forwards"].as_array().unwrap().len(), 500);
+        assert_eq!(result["forwards"][499]["fee_msat"], 499);
+    }
+}
@@ -0,0 +1,908 @@
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tracing::{info, instrument, warn};
+
+use super::{
+    reject_options, value_as_u64, BackendKind, ForwardingPage, NodeBackend, Unsupported,
+    DEFAULT_INVOICE_EXPIRY,
+};
+use crate::models::lightning::{
+    Channel, ChannelCloseResult, ChannelOpenResult, ChannelPolicy, ChannelStatus, CloseOptions,
+    DecodedBolt12, ForwardingEvent,
+    Invoice, NodeCapabilities, PolicyUpdate, NodeInfo, PaymentOptions, PaymentResult, PaymentRoute, RouteHop,
+    PendingChannel, PendingChannels, PendingState,
+    RouteQuote, Transaction, TransactionStatus, TransactionType,
+};
+use crate::models::onchain::{AddressType, OnchainSendRequest, WalletBalance};
+use crate::services::{bolt11, bolt12};
+
+/// Intervalle et nombre de consultations de l'état d'un keysend, qu'Eclair envoie en tâche de fond
+const KEYSEND_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const KEYSEND_POLL_ATTEMPTS: u32 = 60;
+/// Delta CLTV du dernier saut quand la facture n'en précise pas (BOLT11)
+const FINAL_CLTV_EXPIRY_DELTA: u32 = 18;
+
+/// Backend pour l'API HTTP d'Eclair
+pub struct EclairBackend {
+    client: Client,
+    base_url: String,
+    password: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EclairGetInfo {
+    node_id: String,
+    #[serde(default)]
+    alias: String,
+    version: String,
+    #[serde(default)]
+    network: String,
+    #[serde(default)]
+    block_height: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EclairChannel {
+    node_id: String,
+    channel_id: String,
+    state: String,
+    #[serde(default)]
+    data: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct EclairAudit {
+    #[serde(default)]
+    sent: Vec<EclairSent>,
+    #[serde(default)]
+    received: Vec<EclairReceived>,
+    /// Relais de canal à canal ou trampoline, dont le format varie selon les versions
+    #[serde(default)]
+    relayed: Vec<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EclairSent {
+    payment_hash: String,
+    #[serde(default)]
+    recipient_amount: u64,
+    #[serde(default)]
+    parts: Vec<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EclairReceived {
+    payment_hash: String,
+    #[serde(default)]
+    parts: Vec<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EclairOnchainBalance {
+    #[serde(default)]
+    confirmed: u64,
+    #[serde(default)]
+    unconfirmed: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct EclairOnchainTransaction {
+    txid: String,
+    /// Négatif pour une dépense du portefeuille
+    #[serde(default)]
+    amount: i64,
+    #[serde(default)]
+    fees: u64,
+    #[serde(default)]
+    confirmations: u32,
+    #[serde(default)]
+    timestamp: Value,
+}
+
+/// Objectif de confirmation exigé par `sendonchain` quand la requête n'en précise pas
+const SEND_ONCHAIN_TARGET_CONF: u32 = 6;
+
+/// Nombre de transactions on-chain lues, Eclair paginant `onchaintransactions`
+const ONCHAIN_TRANSACTIONS_COUNT: u32 = 1000;
+
+/// Eclair renvoie ses horodatages soit en millisecondes, soit en objet `{iso, unix}`
+pub(crate) fn eclair_timestamp(value: &Value) -> i64 {
+    match value {
+        Value::Object(o) => o.get("unix").map(value_as_u64).unwrap_or(0) as i64,
+        other => (value_as_u64(other) / 1000) as i64,
+    }
+}
+
+/// Short channel id confirmé, selon la version d'Eclair
+fn short_channel_id(data: &Value) -> Option<String> {
+    ["/shortIds/real/realScid", "/shortChannelId"]
+        .iter()
+        .find_map(|path| data.pointer(path)?.as_str())
+        .map(str::to_string)
+}
+
+/// Montant et premier canal d'un côté d'un relais : champs `amountIn`/`fromChannelId`
+/// d'un relais de canal, ou liste `incoming` des parts d'un relais trampoline
+fn relayed_side(relayed: &Value, amount: &str, channel: &str, parts: &str) -> (u64, String) {
+    match relayed.get(parts).and_then(Value::as_array) {
+        Some(parts) => (
+            parts
+                .iter()
+                .map(|p| p.get("amount").map(value_as_u64).unwrap_or(0))
+                .sum(),
+            parts
+                .first()
+                .and_then(|p| p["channelId"].as_str())
+                .unwrap_or_default()
+                .to_string(),
+        ),
+        None => (
+            relayed.get(amount).map(value_as_u64).unwrap_or(0),
+            relayed[channel].as_str().unwrap_or_default().to_string(),
+        ),
+    }
+}
+
+/// Relais lu dans `/audit` ; `None` pour une entrée sans montant entrant
+fn relayed_event(relayed: &Value) -> Option<ForwardingEvent> {
+    let (amt_in_msat, chan_id_in) = relayed_side(relayed, "amountIn", "fromChannelId", "incoming");
+    let (amt_out_msat, chan_id_out) =
+        relayed_side(relayed, "amountOut", "toChannelId", "outgoing");
+    if amt_in_msat == 0 {
+        return None;
+    }
+    let timestamp = ["timestamp", "settledAt"]
+        .iter()
+        .find_map(|key| relayed.get(*key))
+        .map(eclair_timestamp)
+        .unwrap_or(0);
+    Some(ForwardingEvent {
+        timestamp,
+        chan_id_in,
+        chan_id_out,
+        amt_in_msat,
+        amt_out_msat,
+        fee_msat: amt_in_msat.saturating_sub(amt_out_msat),
+    })
+}
+
+/// Délai imposé à nos fonds par le pair (son `toSelfDelay`), selon la version d'Eclair
+fn csv_delay(data: &Value) -> Option<u32> {
+    [
+        "/commitments/params/remoteParams/toSelfDelay",
+        "/commitments/remoteParams/toSelfDelay",
+    ]
+    .iter()
+    .find_map(|path| data.pointer(path))
+    .map(|delay| value_as_u64(delay) as u32)
+}
+
+/// Politique locale d'un canal, lue dans le `channel_update` qu'Eclair a émis
+/// État d'attente d'un canal Eclair, `None` pour un canal ouvert ou fermé
+fn pending_state(state: &str, data: &Value) -> Option<PendingState> {
+    match state {
+        "WAIT_FOR_FUNDING_CONFIRMED"
+        | "WAIT_FOR_CHANNEL_READY"
+        | "WAIT_FOR_DUAL_FUNDING_CONFIRMED"
+        | "WAIT_FOR_DUAL_FUNDING_READY" => Some(PendingState::PendingOpen),
+        "SHUTDOWN" | "NEGOTIATING" => Some(PendingState::PendingClose),
+        // Une transaction d'engagement publiée signale une fermeture unilatérale
+        "CLOSING" if data.get("localCommitPublished").is_some()
+            || data.get("remoteCommitPublished").is_some() =>
+        {
+            Some(PendingState::ForceClosing)
+        }
+        "CLOSING" => Some(PendingState::WaitingClose),
+        _ => None,
+    }
+}
+
+/// Transaction de fermeture publiée, selon le type de fermeture
+fn closing_txid(data: &Value) -> Option<String> {
+    [
+        "/mutualClosePublished/0/txid",
+        "/mutualClosePublished/0/tx/txid",
+        "/localCommitPublished/commitTx/txid",
+        "/remoteCommitPublished/commitTx/txid",
+    ]
+    .iter()
+    .find_map(|path| data.pointer(path)?.as_str())
+    .map(str::to_string)
+}
+
+fn local_policy(data: &Value) -> Option<ChannelPolicy> {
+    let update = data.get("channelUpdate")?;
+    Some(ChannelPolicy {
+        base_fee_msat: value_as_u64(&update["feeBaseMsat"]),
+        fee_rate_ppm: value_as_u64(&update["feeProportionalMillionths"]) as u32,
+        time_lock_delta: value_as_u64(&update["cltvExpiryDelta"]) as u32,
+        min_htlc_msat: value_as_u64(&update["htlcMinimumMsat"]),
+        max_htlc_msat: value_as_u64(&update["htlcMaximumMsat"]),
+        inbound_base_fee_msat: 0,
+        inbound_fee_rate_ppm: 0,
+        disabled: update.pointer("/channelFlags/isEnabled") == Some(&Value::Bool(false)),
+    })
+}
+
+/// Reconstitue montants, frais et délais d'une route `findroutetonode` à partir des
+/// `channel_update` de chaque saut, en remontant depuis le destinataire.
+///
+/// Le premier canal est le nôtre : il ne coûte ni frais ni délai.
+fn route_quote(route: &Value) -> RouteQuote {
+    let channels = route["hops"].as_array().cloned().unwrap_or_default();
+    let mut amount_msat = value_as_u64(&route["amount"]);
+    let mut time_lock = FINAL_CLTV_EXPIRY_DELTA;
+    let mut next_fee_msat = 0;
+    let mut hops = Vec::with_capacity(channels.len());
+
+    for (i, channel) in channels.iter().enumerate().rev() {
+        let update = channel
+            .pointer("/source/channelUpdate")
+            .unwrap_or(&Value::Null);
+        hops.push(RouteHop {
+            pubkey: channel["nextNodeId"].as_str().unwrap_or_default().to_string(),
+            channel_id: update["shortChannelId"].as_str().unwrap_or_default().to_string(),
+            amount_msat,
+            fee_msat: next_fee_msat,
+        });
+        next_fee_msat = if i == 0 {
+            0
+        } else {
+            value_as_u64(&update["feeBaseMsat"])
+                + amount_msat * value_as_u64(&update["feeProportionalMillionths"]) / 1_000_000
+        };
+        if i > 0 {
+            time_lock += value_as_u64(&update["cltvExpiryDelta"]) as u32;
+        }
+        amount_msat += next_fee_msat;
+    }
+    hops.reverse();
+
+    let fee_msat = amount_msat - value_as_u64(&route["amount"]);
+    RouteQuote::new(hops, amount_msat, fee_msat, time_lock)
+}
+
+/// Traduit la réponse d'un paiement bloquant (`payinvoice`, `payoffer`).
+///
+/// Eclair ne détaille pas les sauts : chaque partie est rapportée avec ses seuls
+/// montant et frais, et les tentatives se limitent aux parties ou aux échecs connus.
+fn payment_result(response: &Value) -> PaymentResult {
+    let sent = response["type"] == "payment-sent";
+    let parts = response["parts"].as_array().cloned().unwrap_or_default();
+    PaymentResult {
+        payment_hash: response["paymentHash"].as_str().unwrap_or_default().to_string(),
+        preimage: response["paymentPreimage"].as_str().map(str::to_string),
+        status: if sent {
+            TransactionStatus::Completed
+        } else {
+            TransactionStatus::Failed
+        },
+        amount: value_as_u64(&response["recipientAmount"]) / 1000,
+        fee: parts
+            .iter()
+            .map(|p| value_as_u64(&p["feesPaid"]))
+            .sum::<u64>()
+            / 1000,
+        failure_reason: (!sent).then(|| {
+            response
+                .pointer("/failures/0/t")
+                .or_else(|| response.pointer("/failures/0/failureMessage"))
+                .and_then(Value::as_str)
+                .unwrap_or("paiement échoué")
+                .to_string()
+        }),
+        attempts: if sent {
+            parts.len()
+        } else {
+            response["failures"].as_array().map_or(0, Vec::len)
+        } as u32,
+        routes: parts
+            .iter()
+            .map(|p| {
+                let fee_msat = value_as_u64(&p["feesPaid"]);
+                PaymentRoute {
+                    hops: Vec::new(),
+                    amount_msat: value_as_u64(&p["amount"]) + fee_msat,
+                    fee_msat,
+                }
+            })
+            .collect(),
+    }
+}
+
+impl EclairBackend {
+    pub fn new(base_url: String, password: String) -> Self {
+        info!(base_url = %base_url, "Création du backend Eclair");
+        Self {
+            client: Client::new(),
+            base_url,
+            password,
+        }
+    }
+
+    async fn call<T: DeserializeOwned>(&self, method: &str, params: &[(&str, String)]) -> Result<T> {
+        let url = format!("{}/{}", self.base_url, method);
+        let response = self
+            .client
+            .post(&url)
+            .basic_auth("", Some(&self.password))
+            .form(params)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.json::<T>().await?)
+    }
+}
+
+#[async_trait]
+impl NodeBackend for EclairBackend {
+    fn kind(&self) -> BackendKind {
+        BackendKind::Eclair
+    }
+
+    fn capabilities(&self) -> NodeCapabilities {
+        NodeCapabilities {
+            bolt12_offers: false,
+            bolt12_payments: true,
+        }
+    }
+
+    #[instrument(skip(self), err)]
+    async fn get_node_info(&self) -> Result<NodeInfo> {
+        let info: EclairGetInfo = self.call("getinfo", &[]).await?;
+        let channels = self.list_channels().await?;
+        Ok(NodeInfo {
+            pubkey: info.node_id,
+            alias: info.alias,
+            capacity: channels.iter().map(|c| c.capacity).sum(),
+            channels: channels.len() as u32,
+            version: info.version,
+            network: info.network.parse().ok(),
+            capabilities: self.capabilities(),
+        })
+    }
+
+    #[instrument(skip(self), err)]
+    async fn list_channels(&self) -> Result<Vec<Channel>> {
+        let channels: Vec<EclairChannel> = self.call("channels", &[]).await?;
+        Ok(channels
+            .into_iter()
+            .map(|c| {
+                let commitment = c.data.pointer("/commitments/active/0");
+                let read = |path: &str| {
+                    commitment
+                        .and_then(|v| v.pointer(path))
+                        .map(value_as_u64)
+                        .unwrap_or(0)
+                };
+                Channel {
+                    short_channel_id: short_channel_id(&c.data),
+                    id: c.channel_id,
+                    capacity: read("/fundingTx/amountSatoshis"),
+                    local_balance: read("/localCommit/spec/toLocal") / 1000,
+                    remote_balance: read("/localCommit/spec/toRemote") / 1000,
+                    remote_pubkey: c.node_id,
+                    status: match c.state.as_str() {
+                        "NORMAL" => ChannelStatus::Active,
+                        "WAIT_FOR_FUNDING_CONFIRMED"
+                        | "WAIT_FOR_CHANNEL_READY"
+                        | "WAIT_FOR_DUAL_FUNDING_CONFIRMED"
+                        | "WAIT_FOR_DUAL_FUNDING_READY" => ChannelStatus::Pending,
+                        _ => ChannelStatus::Inactive,
+                    },
+                    local_policy: local_policy(&c.data),
+                    // Eclair n'expose pas la politique du pair avec le canal
+                    remote_policy: None,
+                    csv_delay: csv_delay(&c.data),
+                }
+            })
+            .collect())
+    }
+
+    #[instrument(skip(self), err)]
+    async fn list_pending_channels(&self) -> Result<PendingChannels> {
+        let info: EclairGetInfo = self.call("getinfo", &[]).await?;
+        let channels: Vec<EclairChannel> = self.call("channels", &[]).await?;
+        // Eclair n'expose ni les confirmations ni la maturité des sorties à délai
+        let channels = channels
+            .into_iter()
+            .filter_map(|c| {
+                let state = pending_state(&c.state, &c.data)?;
+                let commitment = c.data.pointer("/commitments/active/0");
+                let read = |path: &str| commitment.and_then(|v| v.pointer(path));
+                Some(PendingChannel {
+                    capacity: read("/fundingTx/amountSatoshis")
+                        .map(value_as_u64)
+                        .unwrap_or(0),
+                    limbo_balance: read("/localCommit/spec/toLocal")
+                        .map(value_as_u64)
+                        .unwrap_or(0)
+                        / 1000,
+                    funding_txid: read("/fundingTx/outPoint")
+                        .and_then(Value::as_str)
+                        .and_then(|outpoint| outpoint.split(':').next())
+                        .unwrap_or_default()
+                        .to_string(),
+                    closing_txid: closing_txid(&c.data),
+                    channel_id: c.channel_id,
+                    remote_pubkey: c.node_id,
+                    state,
+                    confirmations: None,
+                    blocks_til_maturity: None,
+                    maturity_height: None,
+                })
+            })
+            .collect();
+
+        Ok(PendingChannels {
+            block_height: info.block_height,
+            channels,
+        })
+    }
+
+    /// Relais de `/audit` depuis `start_time`, paginés par `skip` et `count`
+    #[instrument(skip(self), err)]
+    async fn forwarding_history(
+        &self,
+        start_time: i64,
+        offset: u64,
+        limit: u32,
+    ) -> Result<ForwardingPage> {
+        let audit: EclairAudit = self
+            .call(
+                "audit",
+                &[
+                    ("from", start_time.max(0).to_string()),
+                    ("skip", offset.to_string()),
+                    ("count", limit.to_string()),
+                ],
+            )
+            .await?;
+        Ok(ForwardingPage {
+            next_offset: offset + audit.relayed.len() as u64,
+            events: audit.relayed.iter().filter_map(relayed_event).collect(),
+        })
+    }
+
+    #[instrument(skip(self), err)]
+    async fn list_transactions(&self) -> Result<Vec<Transaction>> {
+        let audit: EclairAudit = self.call("audit", &[]).await?;
+
+        let mut transactions: Vec<Transaction> = audit
+            .sent
+            .into_iter()
+            .map(|s| Transaction {
+                id: s.payment_hash,
+                amount: s.recipient_amount / 1000,
+                fee: s
+                    .parts
+                    .iter()
+                    .map(|p| p.get("feesPaid").map(value_as_u64).unwrap_or(0))
+                    .sum::<u64>()
+                    / 1000,
+                timestamp: s
+                    .parts
+                    .first()
+                    .and_then(|p| p.get("timestamp"))
+                    .map(eclair_timestamp)
+                    .unwrap_or(0),
+                type_: TransactionType::Payment,
+                status: TransactionStatus::Completed,
+                description: None,
+                outgoing: true,
+                custom_records: BTreeMap::new(),
+            })
+            .collect();
+
+        transactions.extend(audit.received.into_iter().map(|r| Transaction {
+            id: r.payment_hash,
+            amount: r
+                .parts
+                .iter()
+                .map(|p| p.get("amount").map(value_as_u64).unwrap_or(0))
+                .sum::<u64>()
+                / 1000,
+            fee: 0,
+            timestamp: r
+                .parts
+                .first()
+                .and_then(|p| p.get("timestamp"))
+                .map(eclair_timestamp)
+                .unwrap_or(0),
+            type_: TransactionType::Invoice,
+            status: TransactionStatus::Completed,
+            description: None,
+            outgoing: false,
+            custom_records: BTreeMap::new(),
+        }));
+
+        transactions.sort_by_key(|t| Reverse(t.timestamp));
+        Ok(transactions)
+    }
+
+    async fn send_payment(
+        &self,
+        payment_request: &str,
+        options: &PaymentOptions,
+    ) -> Result<PaymentResult> {
+        reject_options(
+            self.kind(),
+            &[
+                (options.max_parts.is_some(), "La limite du nombre de parties d'un paiement"),
+                (options.timeout.is_some(), "Le délai maximal d'un paiement"),
+                (options.outgoing_channel.is_some(), "Le choix du canal de sortie d'un paiement"),
+                (options.last_hop.is_some(), "Le choix du dernier saut d'un paiement"),
+            ],
+        )?;
+
+        let mut params = vec![
+            ("invoice", payment_request.to_string()),
+            ("blocking", "true".to_string()),
+        ];
+        let amount_msat = bolt11::decode(payment_request)?.amount_msat.unwrap_or(0);
+        if let Some(max_fee) = options.fee_limit_msat(amount_msat) {
+            // Eclair retient le plus élevé des deux plafonds : le proportionnel est neutralisé
+            params.push(("maxFeeFlatSat", (max_fee / 1000).to_string()));
+            params.push(("maxFeePct", "0".to_string()));
+        }
+        let response: Value = self.call("payinvoice", &params).await?;
+        Ok(payment_result(&response))
+    }
+
+    /// `sendtonode` rend la main avant la fin du paiement : son état est ensuite
+    /// consulté jusqu'à ce que toutes ses parties aient abouti ou échoué.
+    async fn send_keysend(
+        &self,
+        pubkey: &str,
+        amount: u64,
+        custom_records: &BTreeMap<u64, Vec<u8>>,
+    ) -> Result<PaymentResult> {
+        if !custom_records.is_empty() {
+            return Err(Unsupported {
+                backend: self.kind(),
+                operation: "L'envoi d'enregistrements TLV personnalisés",
+            }
+            .into());
+        }
+        let id: String = self
+            .call(
+                "sendtonode",
+                &[
+                    ("nodeId", pubkey.to_string()),
+                    ("amountMsat", (amount * 1000).to_string()),
+                ],
+            )
+            .await?;
+
+        let mut payment_hash = String::new();
+        for _ in 0..KEYSEND_POLL_ATTEMPTS {
+            tokio::time::sleep(KEYSEND_POLL_INTERVAL).await;
+            let parts: Vec<Value> = self.call("getsentinfo", &[("id", id.clone())]).await?;
+            if let Some(hash) = parts.first().and_then(|p| p["paymentHash"].as_str()) {
+                payment_hash = hash.to_string();
+            }
+            let state_is =
+                |part: &Value, state: &str| part.pointer("/status/type") == Some(&Value::from(state));
+            if parts.is_empty() || parts.iter().any(|p| state_is(p, "pending")) {
+                continue;
+            }
+
+            let sent: Vec<&Value> = parts.iter().filter(|p| state_is(p, "sent")).collect();
+            return Ok(PaymentResult {
+                payment_hash,
+                preimage: sent
+                    .first()
+                    .and_then(|p| p.pointer("/status/paymentPreimage"))
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                status: if sent.is_empty() {
+                    TransactionStatus::Failed
+                } else {
+                    TransactionStatus::Completed
+                },
+                amount,
+                fee: sent
+                    .iter()
+                    .map(|p| p.pointer("/status/feesPaid").map(value_as_u64).unwrap_or(0))
+                    .sum::<u64>()
+                    / 1000,
+                failure_reason: sent.is_empty().then(|| {
+                    parts[0]
+                        .pointer("/status/failures/0/failureMessage")
+                        .and_then(Value::as_str)
+                        .unwrap_or("paiement échoué")
+                        .to_string()
+                }),
+                routes: sent
+                    .iter()
+                    .map(|p| {
+                        let fee_msat = p.pointer("/status/feesPaid").map(value_as_u64).unwrap_or(0);
+                        PaymentRoute {
+                            hops: Vec::new(),
+                            amount_msat: value_as_u64(&p["amount"]) + fee_msat,
+                            fee_msat,
+                        }
+                    })
+                    .collect(),
+                attempts: parts.len() as u32,
+            });
+        }
+        // Toujours en cours : le résultat final apparaîtra dans les transactions
+        Ok(PaymentResult {
+            payment_hash,
+            preimage: None,
+            status: TransactionStatus::Pending,
+            amount,
+            fee: 0,
+            failure_reason: None,
+            routes: Vec::new(),
+            attempts: 0,
+        })
+    }
+
+    /// Eclair exige le montant même quand l'offre le fixe : il est alors lu dans l'offre
+    async fn pay_offer(
+        &self,
+        offer: &str,
+        amount: Option<u64>,
+        payer_note: Option<&str>,
+    ) -> Result<PaymentResult> {
+        if payer_note.is_some() {
+            warn!("Eclair ne transmet pas de note au destinataire d'une offre, note ignorée");
+        }
+        let amount_msat = match amount {
+            Some(amount) => amount
+                .checked_mul(1000)
+                .with_context(|| format!("Montant hors limites: {} sats", amount))?,
+            None => match bolt12::decode(offer)? {
+                DecodedBolt12::Offer(decoded) if decoded.currency.is_none() => decoded
+                    .amount
+                    .context("Montant requis pour une offre à montant libre")?,
+                DecodedBolt12::Offer(_) => {
+                    bail!("Montant requis pour une offre libellée dans une autre devise")
+                }
+                DecodedBolt12::Invoice(_) => bail!("Une offre BOLT12 (lno1...) est attendue"),
+            },
+        };
+        let response: Value = self
+            .call(
+                "payoffer",
+                &[
+                    ("offer", offer.to_string()),
+                    ("amountMsat", amount_msat.to_string()),
+                    ("blocking", "true".to_string()),
+                ],
+            )
+            .await?;
+        Ok(payment_result(&response))
+    }
+
+    async fn query_routes(&self, destination: &str, amount: u64) -> Result<Vec<RouteQuote>> {
+        let response: Value = self
+            .call(
+                "findroutetonode",
+                &[
+                    ("nodeId", destination.to_string()),
+                    ("amountMsat", (amount * 1000).to_string()),
+                    ("format", "full".to_string()),
+                ],
+            )
+            .await?;
+        Ok(response["routes"]
+            .as_array()
+            .map(|routes| routes.iter().map(route_quote).collect())
+            .unwrap_or_default())
+    }
+
+    async fn create_invoice(
+        &self,
+        amount: u64,
+        description: &str,
+        expiry: Option<u64>,
+    ) -> Result<Invoice> {
+        let expiry = expiry.unwrap_or(DEFAULT_INVOICE_EXPIRY);
+        let response: Value = self
+            .call(
+                "createinvoice",
+                &[
+                    ("amountMsat", (amount * 1000).to_string()),
+                    ("description", description.to_string()),
+                    ("expireIn", expiry.to_string()),
+                ],
+            )
+            .await?;
+        Ok(Invoice {
+            payment_hash: response["paymentHash"].as_str().unwrap_or_default().to_string(),
+            payment_request: response["serialized"]
+                .as_str()
+                .context("Réponse Eclair sans facture sérialisée")?
+                .to_string(),
+            amount,
+            description: description.to_string(),
+            expiry,
+        })
+    }
+
+    /// Le portefeuille d'Eclair est celui de bitcoind : pas de réserve propre au nœud
+    async fn wallet_balance(&self) -> Result<WalletBalance> {
+        let balance: EclairOnchainBalance = self.call("onchainbalance", &[]).await?;
+        Ok(WalletBalance {
+            confirmed: balance.confirmed,
+            unconfirmed: balance.unconfirmed,
+            locked: 0,
+        })
+    }
+
+    async fn new_address(&self, address_type: AddressType) -> Result<String> {
+        reject_options(
+            self.kind(),
+            &[(address_type != AddressType::P2wkh, "Le choix du type d'adresse")],
+        )?;
+        self.call("getnewaddress", &[]).await
+    }
+
+    async fn send_onchain(&self, request: &OnchainSendRequest) -> Result<String> {
+        reject_options(
+            self.kind(),
+            &[
+                (request.send_all, "L'envoi de la totalité du portefeuille"),
+                (request.sat_per_vbyte.is_some(), "Le choix du taux de frais d'un envoi"),
+                (!request.utxos.is_empty(), "Le choix des UTXO d'un envoi"),
+            ],
+        )?;
+        let amount = request.amount.context("Montant de l'envoi requis")?;
+        let target_conf = request.target_conf.unwrap_or(SEND_ONCHAIN_TARGET_CONF);
+        self.call(
+            "sendonchain",
+            &[
+                ("address", request.address.clone()),
+                ("amountSatoshis", amount.to_string()),
+                ("confirmationTarget", target_conf.to_string()),
+            ],
+        )
+        .await
+    }
+
+    /// Eclair n'étiquette pas ses transactions : toutes sont de type `Onchain`
+    async fn list_onchain_transactions(&self) -> Result<Vec<Transaction>> {
+        let transactions: Vec<EclairOnchainTransaction> = self
+            .call(
+                "onchaintransactions",
+                &[("count", ONCHAIN_TRANSACTIONS_COUNT.to_string())],
+            )
+            .await?;
+        Ok(transactions
+            .into_iter()
+            .map(|t| Transaction {
+                id: t.txid,
+                amount: t.amount.unsigned_abs(),
+                fee: t.fees,
+                // Secondes, ou objet `{iso, unix}` selon la version
+                timestamp: match &t.timestamp {
+                    Value::Object(_) => eclair_timestamp(&t.timestamp),
+                    other => value_as_u64(other) as i64,
+                },
+                type_: TransactionType::Onchain,
+                status: if t.confirmations > 0 {
+                    TransactionStatus::Completed
+                } else {
+                    TransactionStatus::Pending
+                },
+                description: None,
+                outgoing: t.amount < 0,
+                custom_records: BTreeMap::new(),
+            })
+            .collect())
+    }
+
+    async fn open_channel(&self, pubkey: &str, amount: u64) -> Result<ChannelOpenResult> {
+        // Eclair répond par une phrase : "created channel <id> with fundingTxId=<txid> and fees=..."
+        let message: String = self
+            .call(
+                "open",
+                &[
+                    ("nodeId", pubkey.to_string()),
+                    ("fundingSatoshis", amount.to_string()),
+                ],
+            )
+            .await?;
+        let funding_txid = message
+            .split("fundingTxId=")
+            .nth(1)
+            .and_then(|rest| rest.split_whitespace().next())
+            .context("Transaction de financement absente de la réponse Eclair")?
+            .to_string();
+        Ok(ChannelOpenResult {
+            funding_txid,
+            output_index: None,
+        })
+    }
+
+    /// Eclair attend un script de sortie plutôt qu'une adresse, et un taux explicite
+    async fn close_channel(
+        &self,
+        channel_id: &str,
+        options: &CloseOptions,
+    ) -> Result<ChannelCloseResult> {
+        reject_options(
+            self.kind(),
+            &[
+                (options.target_conf.is_some(), "L'objectif de confirmation d'une fermeture"),
+                (options.delivery_address.is_some(), "L'adresse de réception d'une fermeture"),
+                (
+                    options.max_fee_per_vbyte.is_some() && options.sat_per_vbyte.is_none(),
+                    "Un plafond de frais de fermeture sans taux choisi",
+                ),
+            ],
+        )?;
+
+        let mut params = vec![("channelId", channel_id.to_string())];
+        if let Some(rate) = options.sat_per_vbyte {
+            params.push(("preferredFeerateSatByte", rate.to_string()));
+            params.push(("minFeerateSatByte", rate.to_string()));
+            params.push((
+                "maxFeerateSatByte",
+                options.max_fee_per_vbyte.unwrap_or(rate).to_string(),
+            ));
+        }
+        let method = if options.force { "forceclose" } else { "close" };
+        let response: Value = self.call(method, &params).await?;
+        match response.get(channel_id).and_then(Value::as_str) {
+            Some("ok") | None => Ok(ChannelCloseResult {
+                channel_id: channel_id.to_string(),
+                closing_txid: None,
+                force: options.force,
+                csv_delay: None,
+            }),
+            Some(error) => Err(anyhow!("Fermeture refusée par Eclair: {}", error)),
+        }
+    }
+
+    /// Eclair ne modifie par canal que les frais de relais
+    async fn set_channel_policy(
+        &self,
+        channel_id: &str,
+        update: &PolicyUpdate,
+        policy: &ChannelPolicy,
+    ) -> Result<()> {
+        reject_options(
+            self.kind(),
+            &[
+                (update.time_lock_delta.is_some(), "Le réglage du delta CLTV par canal"),
+                (
+                    update.min_htlc_msat.is_some() || update.max_htlc_msat.is_some(),
+                    "Le réglage des bornes de HTLC par canal",
+                ),
+                (
+                    update.inbound_base_fee_msat.is_some() || update.inbound_fee_rate_ppm.is_some(),
+                    "Le réglage des frais entrants",
+                ),
+            ],
+        )?;
+        let response: Value = self
+            .call(
+                "updaterelayfee",
+                &[
+                    ("channelId", channel_id.to_string()),
+                    ("feeBaseMsat", policy.base_fee_msat.to_string()),
+                    ("feeProportionalMillionths", policy.fee_rate_ppm.to_string()),
+                ],
+            )
+            .await?;
+        match response.get(channel_id).and_then(Value::as_str) {
+            Some("ok") | None => Ok(()),
+            Some(error) => Err(anyhow!("Politique refusée par Eclair: {}", error)),
+        }
+    }
+}
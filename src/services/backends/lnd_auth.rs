@@ -0,0 +1,93 @@
+use anyhow::{anyhow, bail, Context, Result};
+use reqwest::{Certificate, Client};
+use std::env;
+use std::fs;
+use std::path::Path;
+use tracing::info;
+
+/// Identifiants nécessaires pour parler à un nœud LND
+#[derive(Clone)]
+pub struct LndCredentials {
+    /// Macaroon encodé en hexadécimal, envoyé avec chaque requête
+    pub macaroon_hex: String,
+    /// Certificat TLS auto-signé de LND, au format PEM
+    pub tls_cert_pem: Vec<u8>,
+}
+
+impl std::fmt::Debug for LndCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Le macaroon est un secret : il ne doit jamais apparaître dans les logs
+        f.debug_struct("LndCredentials")
+            .field("macaroon_hex", &"***")
+            .field("tls_cert_pem", &format!("{} octets", self.tls_cert_pem.len()))
+            .finish()
+    }
+}
+
+impl LndCredentials {
+    /// Lit `LIGHTNING_MACAROON` et `LIGHTNING_CERT` depuis l'environnement.
+    ///
+    /// Chaque variable accepte soit la valeur elle-même (macaroon en hexadécimal,
+    /// certificat PEM), soit le chemin d'un fichier la contenant.
+    pub fn from_env() -> Result<Self> {
+        let macaroon = env::var("LIGHTNING_MACAROON")
+            .map_err(|_| anyhow!("LIGHTNING_MACAROON non définie"))?;
+        let cert = env::var("LIGHTNING_CERT")
+            .map_err(|_| anyhow!("LIGHTNING_CERT non définie"))?;
+
+        Self::from_values(&macaroon, &cert)
+    }
+
+    /// Même format que `from_env`, pour les nœuds déclarés dans un fichier de configuration
+    pub fn from_values(macaroon: &str, cert: &str) -> Result<Self> {
+        Ok(Self {
+            macaroon_hex: load_macaroon(macaroon)?,
+            tls_cert_pem: load_cert(cert)?,
+        })
+    }
+
+    /// Construit un client HTTP qui ne fait confiance qu'au certificat du nœud
+    pub fn http_client(&self) -> Result<Client> {
+        let cert = Certificate::from_pem(&self.tls_cert_pem)
+            .context("Certificat TLS LND invalide")?;
+        Client::builder()
+            .tls_built_in_root_certs(false)
+            .add_root_certificate(cert)
+            .build()
+            .context("Impossible de construire le client HTTP LND")
+    }
+}
+
+fn load_macaroon(value: &str) -> Result<String> {
+    let value = value.trim();
+    if !value.is_empty()
+        && value.len().is_multiple_of(2)
+        && value.chars().all(|c| c.is_ascii_hexdigit())
+    {
+        return Ok(value.to_lowercase());
+    }
+
+    let path = Path::new(value);
+    let bytes = fs::read(path)
+        .with_context(|| format!("Impossible de lire le macaroon {}", path.display()))?;
+    if bytes.is_empty() {
+        bail!("Le fichier macaroon {} est vide", path.display());
+    }
+    info!(path = %path.display(), "Macaroon chargé depuis un fichier");
+    Ok(hex::encode(bytes))
+}
+
+fn load_cert(value: &str) -> Result<Vec<u8>> {
+    if value.contains("-----BEGIN CERTIFICATE-----") {
+        return Ok(value.as_bytes().to_vec());
+    }
+
+    let path = Path::new(value.trim());
+    let pem = fs::read(path)
+        .with_context(|| format!("Impossible de lire le certificat TLS {}", path.display()))?;
+    if !String::from_utf8_lossy(&pem).contains("-----BEGIN CERTIFICATE-----") {
+        bail!("Le fichier {} n'est pas un certificat PEM", path.display());
+    }
+    info!(path = %path.display(), "Certificat TLS chargé depuis un fichier");
+    Ok(pem)
+}
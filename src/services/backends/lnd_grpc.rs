@@ -0,0 +1,2096 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use tonic::codegen::InterceptedService;
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::service::Interceptor;
+use tonic::transport::{Certificate, Channel as GrpcChannel, ClientTlsConfig, Endpoint};
+use tonic::{Request, Status, Streaming};
+use tonic_lnd::lnrpc::{
+    self, channel_event_update, channel_point::FundingTxid, close_status_update, funding_shim,
+    funding_transition_msg, htlc_attempt::HtlcStatus, invoice::InvoiceState,
+    lightning_client::LightningClient, open_status_update, payment::PaymentStatus,
+    pending_channels_response, policy_update_request,
+};
+use tonic_lnd::routerrpc::{self, htlc_event, router_client::RouterClient};
+use tonic_lnd::walletrpc::{self, wallet_kit_client::WalletKitClient};
+use tracing::{debug, info, instrument};
+
+use super::{
+    fee_limit_msat, format_scid, lnd_transaction_type, parse_scid, BackendKind, ForwardingPage,
+    LndCredentials, NodeBackend, DEFAULT_INVOICE_EXPIRY, DEFAULT_PAYMENT_TIMEOUT,
+};
+use crate::models::lightning::{
+    BatchOpenRequest, Channel, ChannelCloseResult, ChannelOpenResult, ChannelPolicy,
+    ChannelRequest, ChannelStatus, CloseOptions, ForwardingEvent, Invoice, NodeEvent, NodeInfo,
+    PaymentOptions, PaymentResult, PaymentRoute, PendingChannel, PendingChannels, PendingState,
+    PolicyUpdate, PsbtFunding, RouteHop, RouteQuote, Transaction, TransactionStatus,
+    TransactionType,
+};
+use crate::models::onchain::{AddressType, OnchainSendRequest, Utxo, WalletBalance};
+use crate::models::pnl::{ChannelCost, CostKind};
+use crate::services::bolt11;
+use crate::services::events::EventSink;
+use crate::services::keysend::{self, KEYSEND_PREIMAGE_RECORD};
+
+/// Ajoute le macaroon aux métadonnées de chaque appel gRPC
+#[derive(Clone)]
+pub struct MacaroonInterceptor {
+    macaroon: MetadataValue<Ascii>,
+}
+
+impl Interceptor for MacaroonInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        request
+            .metadata_mut()
+            .insert("macaroon", self.macaroon.clone());
+        Ok(request)
+    }
+}
+
+type AuthedChannel = InterceptedService<GrpcChannel, MacaroonInterceptor>;
+
+/// Backend gRPC natif pour LND (`lnrpc`, `routerrpc` et `walletrpc`)
+pub struct LndGrpcBackend {
+    lightning: LightningClient<AuthedChannel>,
+    router: RouterClient<AuthedChannel>,
+    wallet: WalletKitClient<AuthedChannel>,
+    /// Flux d'ouverture des financements PSBT en cours, par `pending_chan_id` :
+    /// LND y publie le canal en attente une fois la transaction finalisée
+    psbt_fundings: Mutex<HashMap<String, Streaming<lnrpc::OpenStatusUpdate>>>,
+}
+
+impl LndGrpcBackend {
+    /// Ouvre la connexion gRPC vers `address` (ex: `https://localhost:10009`)
+    pub async fn connect(address: String, credentials: &LndCredentials) -> Result<Self> {
+        info!(address = %address, "Connexion au backend LND gRPC");
+        let endpoint = Endpoint::from_shared(address.clone())
+            .with_context(|| format!("Adresse gRPC LND invalide: {}", address))?;
+        let domain = endpoint.uri().host().unwrap_or("localhost").to_string();
+
+        let tls = ClientTlsConfig::new()
+            .ca_certificate(Certificate::from_pem(&credentials.tls_cert_pem))
+            .domain_name(domain);
+        let channel = endpoint
+            .tls_config(tls)
+            .context("Configuration TLS gRPC invalide")?
+            .connect()
+            .await
+            .with_context(|| format!("Connexion gRPC impossible vers {}", address))?;
+        Self::with_channel(channel, &credentials.macaroon_hex)
+    }
+
+    /// Clients authentifiés par `macaroon_hex` sur un canal gRPC déjà établi
+    pub(crate) fn with_channel(channel: GrpcChannel, macaroon_hex: &str) -> Result<Self> {
+        let interceptor = MacaroonInterceptor {
+            macaroon: macaroon_hex
+                .parse()
+                .context("Macaroon invalide pour les métadonnées gRPC")?,
+        };
+
+        Ok(Self {
+            lightning: LightningClient::with_interceptor(channel.clone(), interceptor.clone()),
+            router: RouterClient::with_interceptor(channel.clone(), interceptor.clone()),
+            wallet: WalletKitClient::with_interceptor(channel, interceptor),
+            psbt_fundings: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Avance un financement PSBT d'une étape (vérification, finalisation ou annulation)
+    async fn funding_step(&self, trigger: funding_transition_msg::Trigger) -> Result<()> {
+        self.lightning
+            .clone()
+            .funding_state_step(lnrpc::FundingTransitionMsg {
+                trigger: Some(trigger),
+            })
+            .await?;
+        Ok(())
+    }
+
+    async fn list_channels_raw(&self) -> Result<Vec<lnrpc::Channel>> {
+        Ok(self
+            .lightning
+            .clone()
+            .list_channels(lnrpc::ListChannelsRequest::default())
+            .await?
+            .into_inner()
+            .channels)
+    }
+
+    /// Politiques locale et distante d'un canal, lues dans le graphe du nœud.
+    ///
+    /// Un canal absent du graphe (pas encore annoncé) n'a pas de politique connue.
+    async fn channel_policies(
+        &self,
+        chan_id: u64,
+        remote_pubkey: &str,
+    ) -> (Option<ChannelPolicy>, Option<ChannelPolicy>) {
+        let Ok(edge) = self
+            .lightning
+            .clone()
+            .get_chan_info(lnrpc::ChanInfoRequest {
+                chan_id,
+                ..Default::default()
+            })
+            .await
+        else {
+            return (None, None);
+        };
+        let edge = edge.into_inner();
+        let (local, remote) = if edge.node1_pub == remote_pubkey {
+            (edge.node2_policy, edge.node1_policy)
+        } else {
+            (edge.node1_policy, edge.node2_policy)
+        };
+        (local.map(channel_policy), remote.map(channel_policy))
+    }
+
+    /// Identifiant numérique d'un canal, désigné par son point de financement
+    /// ou son short channel id
+    async fn chan_id(&self, channel_id: &str) -> Result<u64> {
+        if let Some(scid) = parse_scid(channel_id) {
+            return Ok(scid);
+        }
+        self.list_channels_raw()
+            .await?
+            .into_iter()
+            .find(|c| c.channel_point == channel_id)
+            .map(|c| c.chan_id)
+            .with_context(|| format!("Canal {} introuvable parmi les canaux ouverts", channel_id))
+    }
+
+    /// Requête de paiement d'une facture, avec les contraintes de `options`
+    async fn payment_request(
+        &self,
+        payment_request: &str,
+        options: &PaymentOptions,
+    ) -> Result<routerrpc::SendPaymentRequest> {
+        let amount_msat = bolt11::decode(payment_request)?.amount_msat.unwrap_or(0);
+        let outgoing_chan_ids = match &options.outgoing_channel {
+            Some(channel_id) => vec![self.chan_id(channel_id).await?],
+            None => Vec::new(),
+        };
+        Ok(routerrpc::SendPaymentRequest {
+            payment_request: payment_request.to_string(),
+            fee_limit_msat: fee_limit_msat(options, amount_msat) as i64,
+            timeout_seconds: options.timeout.unwrap_or(DEFAULT_PAYMENT_TIMEOUT) as i32,
+            max_parts: options.max_parts.unwrap_or_default(),
+            outgoing_chan_ids,
+            last_hop_pubkey: options
+                .last_hop
+                .as_deref()
+                .map(hex::decode)
+                .transpose()?
+                .unwrap_or_default(),
+            ..Default::default()
+        })
+    }
+
+    /// Lance un paiement via le routeur et attend son issue finale
+    async fn track_payment(&self, request: routerrpc::SendPaymentRequest) -> Result<PaymentResult> {
+        let mut updates = self
+            .router
+            .clone()
+            .send_payment_v2(routerrpc::SendPaymentRequest {
+                no_inflight_updates: true,
+                ..request
+            })
+            .await?
+            .into_inner();
+
+        // Le routeur diffuse l'état du paiement jusqu'à son issue finale
+        while let Some(payment) = updates.message().await? {
+            let status = payment.status();
+            if matches!(status, PaymentStatus::Succeeded | PaymentStatus::Failed) {
+                let succeeded = status == PaymentStatus::Succeeded;
+                return Ok(PaymentResult {
+                    failure_reason: (!succeeded)
+                        .then(|| payment.failure_reason().as_str_name().to_string()),
+                    payment_hash: payment.payment_hash,
+                    preimage: succeeded.then_some(payment.payment_preimage),
+                    status: if succeeded {
+                        TransactionStatus::Completed
+                    } else {
+                        TransactionStatus::Failed
+                    },
+                    amount: payment.value_sat.max(0) as u64,
+                    fee: payment.fee_sat.max(0) as u64,
+                    attempts: payment.htlcs.len() as u32,
+                    routes: payment
+                        .htlcs
+                        .into_iter()
+                        .filter(|h| h.status() == HtlcStatus::Succeeded)
+                        .filter_map(|h| h.route)
+                        .map(|route| PaymentRoute {
+                            hops: route_hops(route.hops),
+                            amount_msat: route.total_amt_msat.max(0) as u64,
+                            fee_msat: route.total_fees_msat.max(0) as u64,
+                        })
+                        .collect(),
+                });
+            }
+        }
+        Err(anyhow!(
+            "Flux de paiement interrompu avant la fin du paiement"
+        ))
+    }
+}
+
+fn channel_policy(policy: lnrpc::RoutingPolicy) -> ChannelPolicy {
+    ChannelPolicy {
+        base_fee_msat: policy.fee_base_msat.max(0) as u64,
+        fee_rate_ppm: policy.fee_rate_milli_msat.max(0) as u32,
+        time_lock_delta: policy.time_lock_delta,
+        min_htlc_msat: policy.min_htlc.max(0) as u64,
+        max_htlc_msat: policy.max_htlc_msat,
+        inbound_base_fee_msat: policy.inbound_fee_base_msat,
+        inbound_fee_rate_ppm: policy.inbound_fee_rate_milli_msat,
+        disabled: policy.disabled,
+    }
+}
+
+fn route_hops(hops: Vec<lnrpc::Hop>) -> Vec<RouteHop> {
+    hops.into_iter()
+        .map(|hop| RouteHop {
+            pubkey: hop.pub_key,
+            channel_id: format_scid(hop.chan_id),
+            amount_msat: hop.amt_to_forward_msat.max(0) as u64,
+            fee_msat: hop.fee_msat.max(0) as u64,
+        })
+        .collect()
+}
+
+/// Requête d'ouverture de canal, sans financement particulier
+fn open_request(request: &ChannelRequest) -> Result<lnrpc::OpenChannelRequest> {
+    Ok(lnrpc::OpenChannelRequest {
+        node_pubkey: hex::decode(&request.pubkey).context("Clé publique invalide")?,
+        local_funding_amount: request.amount as i64,
+        push_sat: request.push_amount as i64,
+        private: request.private,
+        base_fee: request.base_fee_msat.unwrap_or(0),
+        use_base_fee: request.base_fee_msat.is_some(),
+        fee_rate: request.fee_rate_ppm.unwrap_or(0) as u64,
+        use_fee_rate: request.fee_rate_ppm.is_some(),
+        ..Default::default()
+    })
+}
+
+fn pending_channel(pending: &lnrpc::PendingUpdate) -> ChannelOpenResult {
+    ChannelOpenResult {
+        funding_txid: txid_to_hex(&pending.txid),
+        output_index: Some(pending.output_index),
+    }
+}
+
+/// Canal en attente, les confirmations de la transaction attendue étant lues
+/// dans le portefeuille (`confirmations`, par txid)
+fn to_pending_channel(
+    channel: pending_channels_response::PendingChannel,
+    state: PendingState,
+    closing_txid: String,
+    limbo_balance: i64,
+    confirmations: &HashMap<String, u32>,
+) -> PendingChannel {
+    let funding_txid = channel
+        .channel_point
+        .split(':')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    let closing_txid = (!closing_txid.is_empty()).then_some(closing_txid);
+    PendingChannel {
+        confirmations: confirmations
+            .get(closing_txid.as_ref().unwrap_or(&funding_txid))
+            .copied(),
+        channel_id: channel.channel_point,
+        remote_pubkey: channel.remote_node_pub,
+        capacity: channel.capacity.max(0) as u64,
+        state,
+        funding_txid,
+        closing_txid,
+        blocks_til_maturity: None,
+        maturity_height: None,
+        limbo_balance: limbo_balance.max(0) as u64,
+    }
+}
+
+fn decode_psbt(psbt: &str) -> Result<Vec<u8>> {
+    BASE64.decode(psbt).context("PSBT invalide: base64 attendu")
+}
+
+/// Les txid sont transmis en octets little-endian par LND
+fn txid_to_hex(bytes: &[u8]) -> String {
+    let mut reversed = bytes.to_vec();
+    reversed.reverse();
+    hex::encode(reversed)
+}
+
+/// Reconstruit l'identifiant `txid:index` utilisé pour les canaux LND
+fn format_channel_point(point: &lnrpc::ChannelPoint) -> String {
+    let txid = match &point.funding_txid {
+        Some(FundingTxid::FundingTxidBytes(bytes)) => txid_to_hex(bytes),
+        Some(FundingTxid::FundingTxidStr(txid)) => txid.clone(),
+        None => String::new(),
+    };
+    format!("{}:{}", txid, point.output_index)
+}
+
+fn payment_status(status: PaymentStatus) -> TransactionStatus {
+    match status {
+        PaymentStatus::Succeeded => TransactionStatus::Completed,
+        PaymentStatus::Failed => TransactionStatus::Failed,
+        _ => TransactionStatus::Pending,
+    }
+}
+
+fn channel_event(update: channel_event_update::Channel) -> Option<NodeEvent> {
+    use channel_event_update::Channel as Update;
+    match update {
+        Update::OpenChannel(c) => Some(NodeEvent::ChannelOpened {
+            channel_id: c.channel_point,
+            remote_pubkey: c.remote_pubkey,
+            capacity: c.capacity.max(0) as u64,
+        }),
+        Update::ClosedChannel(c) => Some(NodeEvent::ChannelClosed {
+            channel_id: c.channel_point,
+        }),
+        Update::ActiveChannel(point) => Some(NodeEvent::ChannelActive {
+            channel_id: format_channel_point(&point),
+        }),
+        Update::InactiveChannel(point) => Some(NodeEvent::ChannelInactive {
+            channel_id: format_channel_point(&point),
+        }),
+        _ => None,
+    }
+}
+
+fn parse_channel_point(channel_id: &str) -> Result<lnrpc::ChannelPoint> {
+    let (txid, index) = channel_id
+        .split_once(':')
+        .context("Identifiant de canal LND attendu au format txid:index")?;
+    Ok(lnrpc::ChannelPoint {
+        funding_txid: Some(FundingTxid::FundingTxidStr(txid.to_string())),
+        output_index: index.parse().context("Index de sortie invalide")?,
+    })
+}
+
+#[async_trait]
+impl NodeBackend for LndGrpcBackend {
+    fn kind(&self) -> BackendKind {
+        BackendKind::LndGrpc
+    }
+
+    #[instrument(skip(self), err)]
+    async fn get_node_info(&self) -> Result<NodeInfo> {
+        let info = self
+            .lightning
+            .clone()
+            .get_info(lnrpc::GetInfoRequest {})
+            .await?
+            .into_inner();
+        let capacity = self
+            .list_channels_raw()
+            .await?
+            .iter()
+            .map(|c| c.capacity.max(0) as u64)
+            .sum();
+        Ok(NodeInfo {
+            pubkey: info.identity_pubkey,
+            alias: info.alias,
+            capacity,
+            channels: info.num_active_channels
+                + info.num_inactive_channels
+                + info.num_pending_channels,
+            network: info.chains.first().and_then(|c| c.network.parse().ok()),
+            capabilities: self.capabilities(),
+            version: info.version,
+        })
+    }
+
+    #[instrument(skip(self), err)]
+    async fn list_channels(&self) -> Result<Vec<Channel>> {
+        let pending = self
+            .lightning
+            .clone()
+            .pending_channels(lnrpc::PendingChannelsRequest::default())
+            .await?
+            .into_inner();
+
+        let open = self.list_channels_raw().await?;
+        let mut channels = Vec::with_capacity(open.len());
+        for c in open {
+            let (local_policy, remote_policy) =
+                self.channel_policies(c.chan_id, &c.remote_pubkey).await;
+            channels.push(Channel {
+                id: c.channel_point,
+                short_channel_id: Some(format_scid(c.chan_id)),
+                capacity: c.capacity.max(0) as u64,
+                local_balance: c.local_balance.max(0) as u64,
+                remote_balance: c.remote_balance.max(0) as u64,
+                remote_pubkey: c.remote_pubkey,
+                status: if c.active {
+                    ChannelStatus::Active
+                } else {
+                    ChannelStatus::Inactive
+                },
+                local_policy,
+                remote_policy,
+                csv_delay: c.local_constraints.as_ref().map(|l| l.csv_delay),
+            });
+        }
+
+        channels.extend(
+            pending
+                .pending_open_channels
+                .into_iter()
+                .filter_map(|p| p.channel)
+                .map(|c| Channel {
+                    id: c.channel_point,
+                    short_channel_id: None,
+                    capacity: c.capacity.max(0) as u64,
+                    local_balance: c.local_balance.max(0) as u64,
+                    remote_balance: c.remote_balance.max(0) as u64,
+                    remote_pubkey: c.remote_node_pub,
+                    status: ChannelStatus::Pending,
+                    local_policy: None,
+                    remote_policy: None,
+                    csv_delay: None,
+                }),
+        );
+
+        Ok(channels)
+    }
+
+    #[instrument(skip(self), err)]
+    async fn list_pending_channels(&self) -> Result<PendingChannels> {
+        let info = self
+            .lightning
+            .clone()
+            .get_info(lnrpc::GetInfoRequest {})
+            .await?
+            .into_inner();
+        let pending = self
+            .lightning
+            .clone()
+            .pending_channels(lnrpc::PendingChannelsRequest::default())
+            .await?
+            .into_inner();
+        // Les confirmations ne figurent pas dans PendingChannels
+        let confirmations: HashMap<String, u32> = self
+            .lightning
+            .clone()
+            .get_transactions(lnrpc::GetTransactionsRequest::default())
+            .await?
+            .into_inner()
+            .transactions
+            .into_iter()
+            .map(|t| (t.tx_hash, t.num_confirmations.max(0) as u32))
+            .collect();
+
+        let mut channels = Vec::new();
+        for p in pending.pending_open_channels {
+            if let Some(channel) = p.channel {
+                let limbo_balance = channel.local_balance;
+                channels.push(to_pending_channel(
+                    channel,
+                    PendingState::PendingOpen,
+                    String::new(),
+                    limbo_balance,
+                    &confirmations,
+                ));
+            }
+        }
+        // `pending_closing_channels` est déprécié : LND tient une fermeture coopérative
+        // pour acquise dès que sa transaction est vue on-chain, et la range d'ici là
+        // parmi les canaux en attente de fermeture
+        for p in pending.waiting_close_channels {
+            if let Some(channel) = p.channel {
+                channels.push(to_pending_channel(
+                    channel,
+                    PendingState::WaitingClose,
+                    p.closing_txid,
+                    p.limbo_balance,
+                    &confirmations,
+                ));
+            }
+        }
+        for p in pending.pending_force_closing_channels {
+            let Some(channel) = p.channel else { continue };
+            let mut channel = to_pending_channel(
+                channel,
+                PendingState::ForceClosing,
+                p.closing_txid,
+                p.limbo_balance,
+                &confirmations,
+            );
+            // La maturité reste inconnue tant que des HTLC ne sont pas résolus
+            if p.maturity_height > 0 {
+                channel.maturity_height = Some(p.maturity_height);
+                channel.blocks_til_maturity = Some(p.blocks_til_maturity.max(0) as u32);
+            }
+            channels.push(channel);
+        }
+
+        Ok(PendingChannels {
+            block_height: info.block_height,
+            channels,
+        })
+    }
+
+    #[instrument(skip(self), err)]
+    async fn list_transactions(&self) -> Result<Vec<Transaction>> {
+        let payments = self
+            .lightning
+            .clone()
+            .list_payments(lnrpc::ListPaymentsRequest {
+                include_incomplete: true,
+                ..Default::default()
+            })
+            .await?
+            .into_inner()
+            .payments;
+        let invoices = self
+            .lightning
+            .clone()
+            .list_invoices(lnrpc::ListInvoiceRequest::default())
+            .await?
+            .into_inner()
+            .invoices;
+
+        let mut transactions: Vec<Transaction> = payments
+            .into_iter()
+            .map(|p| {
+                // Les enregistrements envoyés figurent sur le dernier saut de la route réussie
+                let custom_records = p
+                    .htlcs
+                    .iter()
+                    .find(|h| h.status() == HtlcStatus::Succeeded)
+                    .and_then(|h| h.route.as_ref())
+                    .and_then(|r| r.hops.last())
+                    .map(|hop| keysend::custom_records(hop.custom_records.clone()))
+                    .unwrap_or_default();
+                Transaction {
+                    status: payment_status(p.status()),
+                    id: p.payment_hash,
+                    amount: p.value_sat.max(0) as u64,
+                    fee: p.fee_sat.max(0) as u64,
+                    timestamp: p.creation_time_ns / 1_000_000_000,
+                    type_: TransactionType::Payment,
+                    description: keysend::decode_message(&custom_records),
+                    outgoing: true,
+                    custom_records,
+                }
+            })
+            .collect();
+
+        transactions.extend(invoices.into_iter().map(|i| {
+            let custom_records =
+                keysend::custom_records(i.htlcs.iter().flat_map(|h| h.custom_records.clone()));
+            Transaction {
+                status: match i.state() {
+                    InvoiceState::Settled => TransactionStatus::Completed,
+                    InvoiceState::Canceled => TransactionStatus::Failed,
+                    _ => TransactionStatus::Pending,
+                },
+                id: hex::encode(&i.r_hash),
+                amount: if i.amt_paid_sat > 0 {
+                    i.amt_paid_sat
+                } else {
+                    i.value
+                }
+                .max(0) as u64,
+                fee: 0,
+                timestamp: i.creation_date,
+                type_: if i.is_keysend {
+                    TransactionType::Keysend
+                } else {
+                    TransactionType::Invoice
+                },
+                description: (!i.memo.is_empty())
+                    .then_some(i.memo)
+                    .or_else(|| keysend::decode_message(&custom_records)),
+                outgoing: false,
+                custom_records,
+            }
+        }));
+
+        transactions.sort_by_key(|t| Reverse(t.timestamp));
+        Ok(transactions)
+    }
+
+    #[instrument(skip(self), err)]
+    async fn forwarding_history(
+        &self,
+        start_time: i64,
+        offset: u64,
+        limit: u32,
+    ) -> Result<ForwardingPage> {
+        let response = self
+            .lightning
+            .clone()
+            .forwarding_history(lnrpc::ForwardingHistoryRequest {
+                start_time: start_time.max(0) as u64,
+                index_offset: offset as u32,
+                num_max_events: limit,
+                ..Default::default()
+            })
+            .await?
+            .into_inner();
+
+        Ok(ForwardingPage {
+            events: response
+                .forwarding_events
+                .into_iter()
+                .map(|e| ForwardingEvent {
+                    timestamp: (e.timestamp_ns / 1_000_000_000) as i64,
+                    chan_id_in: format_scid(e.chan_id_in),
+                    chan_id_out: format_scid(e.chan_id_out),
+                    amt_in_msat: e.amt_in_msat,
+                    amt_out_msat: e.amt_out_msat,
+                    fee_msat: e.fee_msat,
+                })
+                .collect(),
+            next_offset: response.last_offset_index as u64,
+        })
+    }
+
+    async fn send_payment(
+        &self,
+        payment_request: &str,
+        options: &PaymentOptions,
+    ) -> Result<PaymentResult> {
+        let request = self.payment_request(payment_request, options).await?;
+        self.track_payment(request).await
+    }
+
+    async fn send_circular_payment(
+        &self,
+        payment_request: &str,
+        options: &PaymentOptions,
+    ) -> Result<PaymentResult> {
+        let request = self.payment_request(payment_request, options).await?;
+        self.track_payment(routerrpc::SendPaymentRequest {
+            allow_self_payment: true,
+            ..request
+        })
+        .await
+    }
+
+    async fn send_keysend(
+        &self,
+        pubkey: &str,
+        amount: u64,
+        custom_records: &BTreeMap<u64, Vec<u8>>,
+    ) -> Result<PaymentResult> {
+        let (preimage, payment_hash) = keysend::new_preimage();
+        let mut records: HashMap<u64, Vec<u8>> = custom_records.clone().into_iter().collect();
+        records.insert(KEYSEND_PREIMAGE_RECORD, preimage.to_vec());
+
+        self.track_payment(routerrpc::SendPaymentRequest {
+            dest: hex::decode(pubkey)?,
+            amt: amount as i64,
+            payment_hash: payment_hash.to_vec(),
+            dest_custom_records: records,
+            fee_limit_msat: fee_limit_msat(&PaymentOptions::default(), amount * 1000) as i64,
+            timeout_seconds: DEFAULT_PAYMENT_TIMEOUT as i32,
+            ..Default::default()
+        })
+        .await
+    }
+
+    /// LND donne un verrou temporel absolu : il est ramené à la hauteur actuelle
+    async fn query_routes(&self, destination: &str, amount: u64) -> Result<Vec<RouteQuote>> {
+        let mut lightning = self.lightning.clone();
+        let block_height = lightning
+            .get_info(lnrpc::GetInfoRequest {})
+            .await?
+            .into_inner()
+            .block_height;
+        let response = lightning
+            .query_routes(lnrpc::QueryRoutesRequest {
+                pub_key: destination.to_string(),
+                amt: amount as i64,
+                ..Default::default()
+            })
+            .await?
+            .into_inner();
+        Ok(response
+            .routes
+            .into_iter()
+            .map(|route| {
+                RouteQuote::new(
+                    route_hops(route.hops),
+                    route.total_amt_msat.max(0) as u64,
+                    route.total_fees_msat.max(0) as u64,
+                    route.total_time_lock.saturating_sub(block_height),
+                )
+            })
+            .collect())
+    }
+
+    async fn create_invoice(
+        &self,
+        amount: u64,
+        description: &str,
+        expiry: Option<u64>,
+    ) -> Result<Invoice> {
+        let expiry = expiry.unwrap_or(DEFAULT_INVOICE_EXPIRY);
+        let response = self
+            .lightning
+            .clone()
+            .add_invoice(lnrpc::Invoice {
+                value: amount as i64,
+                memo: description.to_string(),
+                expiry: expiry as i64,
+                ..Default::default()
+            })
+            .await?
+            .into_inner();
+        Ok(Invoice {
+            payment_hash: hex::encode(response.r_hash),
+            payment_request: response.payment_request,
+            amount,
+            description: description.to_string(),
+            expiry,
+        })
+    }
+
+    async fn wallet_balance(&self) -> Result<WalletBalance> {
+        let balance = self
+            .lightning
+            .clone()
+            .wallet_balance(lnrpc::WalletBalanceRequest::default())
+            .await?
+            .into_inner();
+        Ok(WalletBalance {
+            confirmed: balance.confirmed_balance.max(0) as u64,
+            unconfirmed: balance.unconfirmed_balance.max(0) as u64,
+            locked: (balance.locked_balance + balance.reserved_balance_anchor_chan).max(0) as u64,
+        })
+    }
+
+    async fn list_utxos(&self) -> Result<Vec<Utxo>> {
+        let response = self
+            .lightning
+            .clone()
+            .list_unspent(lnrpc::ListUnspentRequest {
+                min_confs: 0,
+                max_confs: i32::MAX,
+                ..Default::default()
+            })
+            .await?
+            .into_inner();
+        Ok(response
+            .utxos
+            .into_iter()
+            .map(|u| {
+                let outpoint = u.outpoint.unwrap_or_default();
+                Utxo {
+                    txid: outpoint.txid_str,
+                    output_index: outpoint.output_index,
+                    address: u.address,
+                    amount: u.amount_sat.max(0) as u64,
+                    confirmations: u.confirmations.max(0) as u32,
+                }
+            })
+            .collect())
+    }
+
+    async fn new_address(&self, address_type: AddressType) -> Result<String> {
+        let lnd_type = match address_type {
+            AddressType::P2wkh => lnrpc::AddressType::WitnessPubkeyHash,
+            AddressType::P2tr => lnrpc::AddressType::TaprootPubkey,
+        };
+        let response = self
+            .lightning
+            .clone()
+            .new_address(lnrpc::NewAddressRequest {
+                r#type: lnd_type as i32,
+                ..Default::default()
+            })
+            .await?
+            .into_inner();
+        Ok(response.address)
+    }
+
+    async fn send_onchain(&self, request: &OnchainSendRequest) -> Result<String> {
+        let outpoints = request
+            .utxos
+            .iter()
+            .map(|outpoint| {
+                let (txid, index) = outpoint
+                    .split_once(':')
+                    .context("UTXO attendu au format txid:index")?;
+                Ok(lnrpc::OutPoint {
+                    txid_str: txid.to_string(),
+                    output_index: index.parse()?,
+                    ..Default::default()
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let response = self
+            .lightning
+            .clone()
+            .send_coins(lnrpc::SendCoinsRequest {
+                addr: request.address.clone(),
+                amount: request.amount.unwrap_or(0) as i64,
+                send_all: request.send_all,
+                target_conf: request.target_conf.unwrap_or(0) as i32,
+                sat_per_vbyte: request.sat_per_vbyte.unwrap_or(0),
+                outpoints,
+                ..Default::default()
+            })
+            .await?
+            .into_inner();
+        Ok(response.txid)
+    }
+
+    async fn list_onchain_transactions(&self) -> Result<Vec<Transaction>> {
+        let response = self
+            .lightning
+            .clone()
+            .get_transactions(lnrpc::GetTransactionsRequest::default())
+            .await?
+            .into_inner();
+        Ok(response
+            .transactions
+            .into_iter()
+            .map(|t| Transaction {
+                type_: lnd_transaction_type(&t.label),
+                id: t.tx_hash,
+                amount: t.amount.unsigned_abs(),
+                fee: t.total_fees.max(0) as u64,
+                timestamp: t.time_stamp,
+                status: if t.num_confirmations > 0 {
+                    TransactionStatus::Completed
+                } else {
+                    TransactionStatus::Pending
+                },
+                description: (!t.label.is_empty()).then_some(t.label),
+                outgoing: t.amount < 0,
+                custom_records: BTreeMap::new(),
+            })
+            .collect())
+    }
+
+    #[instrument(skip(self), err)]
+    async fn channel_costs(&self, since: i64) -> Result<Vec<ChannelCost>> {
+        let mut lightning = self.lightning.clone();
+        let info = lightning
+            .get_info(lnrpc::GetInfoRequest {})
+            .await?
+            .into_inner();
+        let open = self.list_channels_raw().await?;
+        let closed = lightning
+            .closed_channels(lnrpc::ClosedChannelsRequest::default())
+            .await?
+            .into_inner()
+            .channels;
+        let wallet = lightning
+            .get_transactions(lnrpc::GetTransactionsRequest::default())
+            .await?
+            .into_inner()
+            .transactions;
+        let payments = lightning
+            .list_payments(lnrpc::ListPaymentsRequest {
+                max_payments: 10_000,
+                reversed: true,
+                ..Default::default()
+            })
+            .await?
+            .into_inner()
+            .payments;
+
+        // Frais payés par le portefeuille, par transaction
+        let fees: HashMap<String, (u64, i64)> = wallet
+            .into_iter()
+            .filter(|t| t.total_fees > 0)
+            .map(|t| (t.tx_hash, (t.total_fees as u64, t.time_stamp)))
+            .collect();
+        let mut costs = Vec::new();
+        let mut onchain_cost = |txid: &str, channel_id: u64, remote: &str, kind| {
+            if let Some(&(fee, timestamp)) = fees.get(txid) {
+                if timestamp >= since {
+                    costs.push(ChannelCost {
+                        timestamp,
+                        channel_id: format_scid(channel_id),
+                        remote_pubkey: Some(remote.to_string()),
+                        kind,
+                        amount_msat: fee * 1000,
+                        reference: txid.to_string(),
+                    });
+                }
+            }
+        };
+
+        for c in open.iter().filter(|c| c.initiator) {
+            let funding_txid = c.channel_point.split(':').next().unwrap_or_default();
+            onchain_cost(
+                funding_txid,
+                c.chan_id,
+                &c.remote_pubkey,
+                CostKind::OpeningFee,
+            );
+        }
+        for c in &closed {
+            if c.open_initiator() == lnrpc::Initiator::Local {
+                let funding_txid = c.channel_point.split(':').next().unwrap_or_default();
+                onchain_cost(
+                    funding_txid,
+                    c.chan_id,
+                    &c.remote_pubkey,
+                    CostKind::OpeningFee,
+                );
+            }
+            onchain_cost(
+                &c.closing_tx_hash,
+                c.chan_id,
+                &c.remote_pubkey,
+                CostKind::ClosingFee,
+            );
+        }
+
+        // Un paiement vers notre propre nœud recharge le canal de son dernier saut
+        for p in payments {
+            let timestamp = p.creation_time_ns / 1_000_000_000;
+            if p.status() != PaymentStatus::Succeeded || timestamp < since {
+                continue;
+            }
+            let Some(route) = p
+                .htlcs
+                .iter()
+                .find(|h| h.status() == HtlcStatus::Succeeded)
+                .and_then(|h| h.route.as_ref())
+            else {
+                continue;
+            };
+            let hops = &route.hops;
+            match hops.last() {
+                Some(last) if last.pub_key == info.identity_pubkey && hops.len() > 1 => {
+                    costs.push(ChannelCost {
+                        timestamp,
+                        channel_id: format_scid(last.chan_id),
+                        remote_pubkey: Some(hops[hops.len() - 2].pub_key.clone()),
+                        kind: CostKind::Rebalance,
+                        amount_msat: p.fee_msat.max(0) as u64,
+                        reference: p.payment_hash.clone(),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        Ok(costs)
+    }
+
+    async fn open_channel(&self, pubkey: &str, amount: u64) -> Result<ChannelOpenResult> {
+        let point = self
+            .lightning
+            .clone()
+            .open_channel_sync(lnrpc::OpenChannelRequest {
+                node_pubkey: hex::decode(pubkey).context("Clé publique invalide")?,
+                local_funding_amount: amount as i64,
+                ..Default::default()
+            })
+            .await?
+            .into_inner();
+        let funding_txid = match point.funding_txid {
+            Some(FundingTxid::FundingTxidBytes(bytes)) => txid_to_hex(&bytes),
+            Some(FundingTxid::FundingTxidStr(txid)) => txid,
+            None => String::new(),
+        };
+        Ok(ChannelOpenResult {
+            funding_txid,
+            output_index: Some(point.output_index),
+        })
+    }
+
+    async fn open_channels(&self, request: &BatchOpenRequest) -> Result<Vec<ChannelOpenResult>> {
+        let channels = request
+            .channels
+            .iter()
+            .map(|channel| {
+                let open = open_request(channel)?;
+                Ok(lnrpc::BatchOpenChannel {
+                    node_pubkey: open.node_pubkey,
+                    local_funding_amount: open.local_funding_amount,
+                    push_sat: open.push_sat,
+                    private: open.private,
+                    base_fee: open.base_fee,
+                    use_base_fee: open.use_base_fee,
+                    fee_rate: open.fee_rate,
+                    use_fee_rate: open.use_fee_rate,
+                    ..Default::default()
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let response = self
+            .lightning
+            .clone()
+            .batch_open_channel(lnrpc::BatchOpenChannelRequest {
+                channels,
+                target_conf: request.target_conf.unwrap_or(0) as i32,
+                sat_per_vbyte: request.sat_per_vbyte.unwrap_or(0) as i64,
+                ..Default::default()
+            })
+            .await?
+            .into_inner();
+        Ok(response
+            .pending_channels
+            .iter()
+            .map(pending_channel)
+            .collect())
+    }
+
+    async fn start_psbt_funding(&self, request: &ChannelRequest) -> Result<PsbtFunding> {
+        let pending_chan_id: [u8; 32] = rand::random();
+        let mut open = open_request(request)?;
+        open.funding_shim = Some(lnrpc::FundingShim {
+            shim: Some(funding_shim::Shim::PsbtShim(lnrpc::PsbtShim {
+                pending_chan_id: pending_chan_id.to_vec(),
+                ..Default::default()
+            })),
+        });
+        let mut updates = self
+            .lightning
+            .clone()
+            .open_channel(open)
+            .await?
+            .into_inner();
+
+        // Le flux reste ouvert jusqu'à la finalisation : LND y annonce le canal en attente
+        let fund = match updates.message().await?.and_then(|u| u.update) {
+            Some(open_status_update::Update::PsbtFund(fund)) => fund,
+            _ => return Err(anyhow!("LND n'a pas demandé de financement PSBT")),
+        };
+        let funding_id = hex::encode(pending_chan_id);
+        self.psbt_fundings
+            .lock()
+            .unwrap()
+            .insert(funding_id.clone(), updates);
+        Ok(PsbtFunding {
+            funding_id,
+            funding_address: fund.funding_address,
+            amount: fund.funding_amount.max(0) as u64,
+            psbt: (!fund.psbt.is_empty()).then(|| BASE64.encode(&fund.psbt)),
+        })
+    }
+
+    async fn verify_psbt_funding(&self, funding_id: &str, psbt: &str) -> Result<()> {
+        self.funding_step(funding_transition_msg::Trigger::PsbtVerify(
+            lnrpc::FundingPsbtVerify {
+                funded_psbt: decode_psbt(psbt)?,
+                pending_chan_id: hex::decode(funding_id)?,
+                ..Default::default()
+            },
+        ))
+        .await
+    }
+
+    async fn finalize_psbt_funding(
+        &self,
+        funding_id: &str,
+        signed_psbt: &str,
+    ) -> Result<ChannelOpenResult> {
+        let mut updates = self
+            .psbt_fundings
+            .lock()
+            .unwrap()
+            .remove(funding_id)
+            .with_context(|| format!("Financement PSBT inconnu: {}", funding_id))?;
+        self.funding_step(funding_transition_msg::Trigger::PsbtFinalize(
+            lnrpc::FundingPsbtFinalize {
+                signed_psbt: decode_psbt(signed_psbt)?,
+                pending_chan_id: hex::decode(funding_id)?,
+                ..Default::default()
+            },
+        ))
+        .await?;
+        match updates.message().await?.and_then(|u| u.update) {
+            Some(open_status_update::Update::ChanPending(pending)) => Ok(pending_channel(&pending)),
+            _ => Err(anyhow!("LND n'a pas confirmé l'ouverture du canal")),
+        }
+    }
+
+    async fn cancel_psbt_funding(&self, funding_id: &str) -> Result<()> {
+        // Le flux d'ouverture est abandonné même si l'annulation échoue : une nouvelle
+        // tentative ne doit pas retomber sur un financement périmé
+        self.psbt_fundings.lock().unwrap().remove(funding_id);
+        self.funding_step(funding_transition_msg::Trigger::ShimCancel(
+            lnrpc::FundingShimCancel {
+                pending_chan_id: hex::decode(funding_id)?,
+            },
+        ))
+        .await
+    }
+
+    async fn close_channel(
+        &self,
+        channel_id: &str,
+        options: &CloseOptions,
+    ) -> Result<ChannelCloseResult> {
+        let mut updates = self
+            .lightning
+            .clone()
+            .close_channel(lnrpc::CloseChannelRequest {
+                channel_point: Some(parse_channel_point(channel_id)?),
+                force: options.force,
+                target_conf: options.target_conf.unwrap_or(0) as i32,
+                sat_per_vbyte: options.sat_per_vbyte.unwrap_or(0),
+                delivery_address: options.delivery_address.clone().unwrap_or_default(),
+                max_fee_per_vbyte: options.max_fee_per_vbyte.unwrap_or(0),
+                ..Default::default()
+            })
+            .await?
+            .into_inner();
+
+        // Comme pour l'API REST, on s'arrête à la diffusion de la transaction
+        let closing_txid = match updates.message().await?.and_then(|u| u.update) {
+            Some(close_status_update::Update::ClosePending(pending)) => txid_to_hex(&pending.txid),
+            Some(close_status_update::Update::ChanClose(closed)) => {
+                txid_to_hex(&closed.closing_txid)
+            }
+            _ => {
+                return Err(anyhow!(
+                    "Aucune mise à jour reçue pour la fermeture du canal"
+                ))
+            }
+        };
+        Ok(ChannelCloseResult {
+            channel_id: channel_id.to_string(),
+            closing_txid: Some(closing_txid),
+            force: options.force,
+            csv_delay: None,
+        })
+    }
+
+    async fn estimate_fee_rate(&self, target_conf: u32) -> Result<u64> {
+        let estimate = self
+            .wallet
+            .clone()
+            .estimate_fee(walletrpc::EstimateFeeRequest {
+                conf_target: target_conf as i32,
+            })
+            .await?
+            .into_inner();
+        // 1 vbyte = 4 unités de poids
+        Ok((estimate.sat_per_kw.max(0) as u64 * 4).div_ceil(1000))
+    }
+
+    async fn set_channel_policy(
+        &self,
+        channel_id: &str,
+        _update: &PolicyUpdate,
+        policy: &ChannelPolicy,
+    ) -> Result<()> {
+        // LND remet à zéro les champs absents : la politique est envoyée en entier
+        let response = self
+            .lightning
+            .clone()
+            .update_channel_policy(lnrpc::PolicyUpdateRequest {
+                scope: Some(policy_update_request::Scope::ChanPoint(
+                    parse_channel_point(channel_id)?,
+                )),
+                base_fee_msat: policy.base_fee_msat as i64,
+                fee_rate_ppm: policy.fee_rate_ppm,
+                time_lock_delta: policy.time_lock_delta,
+                min_htlc_msat: policy.min_htlc_msat,
+                min_htlc_msat_specified: true,
+                max_htlc_msat: policy.max_htlc_msat,
+                inbound_fee: Some(lnrpc::InboundFee {
+                    base_fee_msat: policy.inbound_base_fee_msat,
+                    fee_rate_ppm: policy.inbound_fee_rate_ppm,
+                }),
+                ..Default::default()
+            })
+            .await?
+            .into_inner();
+        if let Some(failure) = response.failed_updates.first() {
+            return Err(anyhow!(
+                "Politique refusée par LND: {}",
+                failure.update_error
+            ));
+        }
+        Ok(())
+    }
+
+    /// Relaie les flux natifs de LND : factures, canaux, HTLC et paiements
+    async fn subscribe_events(&self, sink: &EventSink) -> Result<()> {
+        let mut invoices = self
+            .lightning
+            .clone()
+            .subscribe_invoices(lnrpc::InvoiceSubscription::default())
+            .await?
+            .into_inner();
+        let mut channels = self
+            .lightning
+            .clone()
+            .subscribe_channel_events(lnrpc::ChannelEventSubscription {})
+            .await?
+            .into_inner();
+        let mut htlcs = self
+            .router
+            .clone()
+            .subscribe_htlc_events(routerrpc::SubscribeHtlcEventsRequest {})
+            .await?
+            .into_inner();
+        let mut payments = self
+            .router
+            .clone()
+            .track_payments(routerrpc::TrackPaymentsRequest {
+                no_inflight_updates: false,
+            })
+            .await?
+            .into_inner();
+        info!("Abonné aux flux d'événements LND");
+
+        // Les montants d'un routage sont annoncés à la transmission du HTLC,
+        // mais le routage n'est acquis qu'à son règlement.
+        let mut forwards: HashMap<(u64, u64), ForwardingEvent> = HashMap::new();
+
+        loop {
+            tokio::select! {
+                invoice = invoices.message() => {
+                    let invoice = invoice?.context("Flux des factures LND terminé")?;
+                    if invoice.state() == InvoiceState::Settled {
+                        sink.emit(NodeEvent::InvoiceSettled {
+                            payment_hash: hex::encode(&invoice.r_hash),
+                            amount: invoice.amt_paid_sat.max(0) as u64,
+                        });
+                    }
+                }
+                update = channels.message() => {
+                    let update = update?.context("Flux des canaux LND terminé")?;
+                    if let Some(event) = update.channel.and_then(channel_event) {
+                        sink.emit(event);
+                    }
+                }
+                htlc = htlcs.message() => {
+                    let htlc = htlc?.context("Flux des HTLC LND terminé")?;
+                    if htlc.event_type() != htlc_event::EventType::Forward {
+                        continue;
+                    }
+                    let key = (htlc.incoming_channel_id, htlc.incoming_htlc_id);
+                    match htlc.event {
+                        Some(htlc_event::Event::ForwardEvent(forward)) => {
+                            let info = forward.info.unwrap_or_default();
+                            let fee_msat =
+                                info.incoming_amt_msat.saturating_sub(info.outgoing_amt_msat);
+                            forwards.insert(key, ForwardingEvent {
+                                timestamp: (htlc.timestamp_ns / 1_000_000_000) as i64,
+                                chan_id_in: format_scid(htlc.incoming_channel_id),
+                                chan_id_out: format_scid(htlc.outgoing_channel_id),
+                                amt_in_msat: info.incoming_amt_msat,
+                                amt_out_msat: info.outgoing_amt_msat,
+                                fee_msat,
+                            });
+                        }
+                        Some(htlc_event::Event::SettleEvent(_)) => {
+                            if let Some(forward) = forwards.remove(&key) {
+                                sink.emit(NodeEvent::HtlcForwarded(forward));
+                            }
+                        }
+                        Some(htlc_event::Event::ForwardFailEvent(_))
+                        | Some(htlc_event::Event::LinkFailEvent(_)) => {
+                            forwards.remove(&key);
+                        }
+                        other => debug!(event = ?other, "Événement HTLC ignoré"),
+                    }
+                }
+                payment = payments.message() => {
+                    let payment = payment?.context("Flux des paiements LND terminé")?;
+                    sink.emit(NodeEvent::PaymentUpdated {
+                        status: payment_status(payment.status()),
+                        payment_hash: payment.payment_hash,
+                        amount: payment.value_sat.max(0) as u64,
+                        fee: payment.fee_sat.max(0) as u64,
+                    });
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::bolt11::tests::{description_hash_field, encode, payment_hash_field};
+    use crate::services::events::EventBus;
+    use std::convert::Infallible;
+    use std::marker::PhantomData;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::net::TcpListener;
+    use tokio_stream::StreamExt;
+    use tonic::body::Body;
+    use tonic::codec::{Codec, ProstCodec};
+    use tonic::codegen::{http, BoxFuture, BoxStream, Context, Poll, Service};
+    use tonic::server::{Grpc, NamedService, ServerStreamingService, UnaryService};
+    use tonic::transport::server::TcpIncoming;
+    use tonic::transport::Server;
+
+    const MACAROON: &str = "0201036c6e64";
+    const PEER: &str = "03b1c2d3e4f5a6b7c8d9e0f1a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6";
+    const FUNDING_TXID: &str = "a1b2c3d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8f90";
+    const PAYMENT_HASH: &str = "0001020304050607080900010203040506070809000102030405060708090102";
+    /// Short channel id `103x1x0`
+    const CHAN_ID: u64 = (103 << 40) | (1 << 16);
+
+    /// Faux `lnd` servant des réponses figées sur les services `lnrpc.Lightning`
+    /// et `routerrpc.Router`.
+    ///
+    /// Chaque appel reçu est consigné par son chemin gRPC ; un appel sans le
+    /// macaroon attendu est refusé comme le ferait LND.
+    #[derive(Clone, Default)]
+    struct StubLnd {
+        calls: Arc<Mutex<Vec<String>>>,
+        payments: Arc<Mutex<Vec<routerrpc::SendPaymentRequest>>>,
+        /// Refus opposé à `CloseChannel`, comme un canal bloqué par un HTLC en cours
+        close_error: Option<&'static str>,
+    }
+
+    /// Réponse unaire calculée à partir de la requête décodée
+    struct Unary<F>(Option<F>);
+
+    impl<Req, Res, F> UnaryService<Req> for Unary<F>
+    where
+        F: FnOnce(Req) -> Res,
+        Res: Send + 'static,
+    {
+        type Response = Res;
+        type Future = std::future::Ready<Result<tonic::Response<Res>, Status>>;
+
+        fn call(&mut self, request: Request<Req>) -> Self::Future {
+            let reply = self.0.take().expect("appel unaire servi une seule fois");
+            std::future::ready(Ok(tonic::Response::new(reply(request.into_inner()))))
+        }
+    }
+
+    /// Flux de réponses laissé ouvert après le dernier message, comme les
+    /// abonnements de LND
+    struct Stream<F>(Option<F>);
+
+    impl<Req, Res, F> ServerStreamingService<Req> for Stream<F>
+    where
+        F: FnOnce(Req) -> Vec<Res>,
+        Res: Send + 'static,
+    {
+        type Response = Res;
+        type ResponseStream = BoxStream<Res>;
+        type Future = std::future::Ready<Result<tonic::Response<BoxStream<Res>>, Status>>;
+
+        fn call(&mut self, request: Request<Req>) -> Self::Future {
+            let reply = self.0.take().expect("flux servi une seule fois");
+            let messages = tokio_stream::iter(reply(request.into_inner()).into_iter().map(Ok))
+                .chain(tokio_stream::pending());
+            std::future::ready(Ok(tonic::Response::new(
+                Box::pin(messages) as BoxStream<Res>
+            )))
+        }
+    }
+
+    async fn unary<Req, Res>(
+        request: http::Request<Body>,
+        reply: impl FnOnce(Req) -> Res,
+    ) -> http::Response<Body>
+    where
+        ProstCodec<Res, Req>: Codec<Encode = Res, Decode = Req> + Default,
+        Res: Send + 'static,
+    {
+        Grpc::new(ProstCodec::default())
+            .unary(Unary(Some(reply)), request)
+            .await
+    }
+
+    async fn stream<Req, Res>(
+        request: http::Request<Body>,
+        reply: impl FnOnce(Req) -> Vec<Res>,
+    ) -> http::Response<Body>
+    where
+        ProstCodec<Res, Req>: Codec<Encode = Res, Decode = Req> + Default,
+        Res: Send + 'static,
+    {
+        Grpc::new(ProstCodec::default())
+            .server_streaming(Stream(Some(reply)), request)
+            .await
+    }
+
+    fn channel_point() -> String {
+        format!("{}:0", FUNDING_TXID)
+    }
+
+    fn policy(fee_rate_milli_msat: i64) -> lnrpc::RoutingPolicy {
+        lnrpc::RoutingPolicy {
+            time_lock_delta: 80,
+            min_htlc: 1_000,
+            fee_base_msat: 1_000,
+            fee_rate_milli_msat,
+            max_htlc_msat: 990_000_000,
+            ..Default::default()
+        }
+    }
+
+    fn succeeded_payment(request: &routerrpc::SendPaymentRequest) -> lnrpc::Payment {
+        lnrpc::Payment {
+            payment_hash: PAYMENT_HASH.to_string(),
+            payment_preimage: "ff".repeat(32),
+            value_sat: 250_000,
+            fee_sat: 12,
+            payment_request: request.payment_request.clone(),
+            status: PaymentStatus::Succeeded as i32,
+            htlcs: vec![lnrpc::HtlcAttempt {
+                status: HtlcStatus::Succeeded as i32,
+                route: Some(lnrpc::Route {
+                    total_amt_msat: 250_012_000,
+                    total_fees_msat: 12_000,
+                    hops: vec![lnrpc::Hop {
+                        chan_id: CHAN_ID,
+                        pub_key: PEER.to_string(),
+                        amt_to_forward_msat: 250_000_000,
+                        fee_msat: 12_000,
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    /// Historique de routage du faux nœud, lu par pages comme `ForwardingHistory`
+    fn forwards() -> Vec<lnrpc::ForwardingEvent> {
+        (1..=3)
+            .map(|i| lnrpc::ForwardingEvent {
+                timestamp_ns: (1_700_000_000 + i) * 1_000_000_000,
+                chan_id_in: CHAN_ID,
+                chan_id_out: CHAN_ID + 1,
+                amt_in_msat: 100_000_000 + i * 1_000,
+                amt_out_msat: 100_000_000,
+                fee_msat: i * 1_000,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    impl StubLnd {
+        async fn dispatch(self, request: http::Request<Body>) -> http::Response<Body> {
+            let path = request.uri().path().to_string();
+            self.calls.lock().unwrap().push(path.clone());
+            let authorized = request
+                .headers()
+                .get("macaroon")
+                .is_some_and(|macaroon| macaroon == MACAROON);
+            if !authorized {
+                return Status::unauthenticated("macaroon absent").into_http();
+            }
+
+            match path.as_str() {
+                "/lnrpc.Lightning/GetInfo" => {
+                    unary(request, |_: lnrpc::GetInfoRequest| lnrpc::GetInfoResponse {
+                        identity_pubkey: PEER.replace("03b1", "02a0"),
+                        alias: "lnd-test".to_string(),
+                        version: "0.18.3-beta".to_string(),
+                        num_active_channels: 1,
+                        num_pending_channels: 1,
+                        chains: vec![lnrpc::Chain {
+                            network: "regtest".to_string(),
+                            ..Default::default()
+                        }],
+                        ..Default::default()
+                    })
+                    .await
+                }
+                "/lnrpc.Lightning/ListChannels" => {
+                    unary(request, |_: lnrpc::ListChannelsRequest| {
+                        lnrpc::ListChannelsResponse {
+                            channels: vec![lnrpc::Channel {
+                                active: true,
+                                remote_pubkey: PEER.to_string(),
+                                channel_point: channel_point(),
+                                chan_id: CHAN_ID,
+                                capacity: 1_000_000,
+                                local_balance: 600_000,
+                                remote_balance: 396_530,
+                                local_constraints: Some(lnrpc::ChannelConstraints {
+                                    csv_delay: 144,
+                                    ..Default::default()
+                                }),
+                                ..Default::default()
+                            }],
+                        }
+                    })
+                    .await
+                }
+                "/lnrpc.Lightning/PendingChannels" => {
+                    unary(request, |_: lnrpc::PendingChannelsRequest| {
+                        lnrpc::PendingChannelsResponse {
+                            pending_open_channels: vec![
+                                pending_channels_response::PendingOpenChannel {
+                                    channel: Some(pending_channels_response::PendingChannel {
+                                        remote_node_pub: PEER.to_string(),
+                                        channel_point: format!("{}:1", FUNDING_TXID),
+                                        capacity: 500_000,
+                                        local_balance: 496_530,
+                                        ..Default::default()
+                                    }),
+                                    ..Default::default()
+                                },
+                            ],
+                            ..Default::default()
+                        }
+                    })
+                    .await
+                }
+                "/lnrpc.Lightning/GetChanInfo" => {
+                    unary(request, |request: lnrpc::ChanInfoRequest| {
+                        assert_eq!(request.chan_id, CHAN_ID);
+                        lnrpc::ChannelEdge {
+                            channel_id: CHAN_ID,
+                            node1_pub: PEER.to_string(),
+                            node2_pub: PEER.replace("03b1", "02a0"),
+                            node1_policy: Some(policy(500)),
+                            node2_policy: Some(policy(100)),
+                            ..Default::default()
+                        }
+                    })
+                    .await
+                }
+                "/routerrpc.Router/SendPaymentV2" => {
+                    let payments = self.payments.clone();
+                    stream(request, move |request: routerrpc::SendPaymentRequest| {
+                        payments.lock().unwrap().push(request.clone());
+                        vec![succeeded_payment(&request)]
+                    })
+                    .await
+                }
+                "/lnrpc.Lightning/SubscribeInvoices" => {
+                    stream(request, |_: lnrpc::InvoiceSubscription| {
+                        vec![
+                            lnrpc::Invoice {
+                                r_hash: vec![0xaa; 32],
+                                state: InvoiceState::Open as i32,
+                                ..Default::default()
+                            },
+                            lnrpc::Invoice {
+                                r_hash: hex::decode(PAYMENT_HASH).unwrap(),
+                                amt_paid_sat: 21_000,
+                                state: InvoiceState::Settled as i32,
+                                ..Default::default()
+                            },
+                        ]
+                    })
+                    .await
+                }
+                "/lnrpc.Lightning/SubscribeChannelEvents" => {
+                    stream(request, |_: lnrpc::ChannelEventSubscription| {
+                        vec![lnrpc::ChannelEventUpdate {
+                            channel: Some(channel_event_update::Channel::ClosedChannel(
+                                lnrpc::ChannelCloseSummary {
+                                    channel_point: channel_point(),
+                                    ..Default::default()
+                                },
+                            )),
+                            ..Default::default()
+                        }]
+                    })
+                    .await
+                }
+                "/routerrpc.Router/SubscribeHtlcEvents" => {
+                    stream(request, |_: routerrpc::SubscribeHtlcEventsRequest| {
+                        let htlc = |id: u64, event| routerrpc::HtlcEvent {
+                            incoming_channel_id: CHAN_ID,
+                            outgoing_channel_id: CHAN_ID + 1,
+                            incoming_htlc_id: id,
+                            timestamp_ns: 1_700_000_000_000_000_000,
+                            event_type: htlc_event::EventType::Forward as i32,
+                            event: Some(event),
+                            ..Default::default()
+                        };
+                        let forward = || {
+                            htlc_event::Event::ForwardEvent(routerrpc::ForwardEvent {
+                                info: Some(routerrpc::HtlcInfo {
+                                    incoming_amt_msat: 100_050_000,
+                                    outgoing_amt_msat: 100_000_000,
+                                    ..Default::default()
+                                }),
+                            })
+                        };
+                        // Le HTLC 1 échoue, seul le HTLC 2 est réglé
+                        vec![
+                            htlc(1, forward()),
+                            htlc(2, forward()),
+                            htlc(
+                                1,
+                                htlc_event::Event::ForwardFailEvent(routerrpc::ForwardFailEvent {}),
+                            ),
+                            htlc(
+                                2,
+                                htlc_event::Event::SettleEvent(routerrpc::SettleEvent::default()),
+                            ),
+                        ]
+                    })
+                    .await
+                }
+                "/routerrpc.Router/TrackPayments" => {
+                    stream(request, |request: routerrpc::TrackPaymentsRequest| {
+                        assert!(!request.no_inflight_updates);
+                        vec![lnrpc::Payment {
+                            payment_hash: PAYMENT_HASH.to_string(),
+                            value_sat: 5_000,
+                            status: PaymentStatus::InFlight as i32,
+                            ..Default::default()
+                        }]
+                    })
+                    .await
+                }
+                "/lnrpc.Lightning/ListPayments" => {
+                    unary(request, |request: lnrpc::ListPaymentsRequest| {
+                        assert!(request.include_incomplete);
+                        lnrpc::ListPaymentsResponse {
+                            payments: vec![lnrpc::Payment {
+                                payment_hash: PAYMENT_HASH.to_string(),
+                                value_sat: 250_000,
+                                fee_sat: 12,
+                                creation_time_ns: 1_700_000_100 * 1_000_000_000,
+                                status: PaymentStatus::Succeeded as i32,
+                                ..Default::default()
+                            }],
+                            ..Default::default()
+                        }
+                    })
+                    .await
+                }
+                "/lnrpc.Lightning/ListInvoices" => {
+                    unary(request, |_: lnrpc::ListInvoiceRequest| {
+                        lnrpc::ListInvoiceResponse {
+                            invoices: vec![lnrpc::Invoice {
+                                memo: "Un café".to_string(),
+                                r_hash: vec![0xaa; 32],
+                                value: 21_000,
+                                amt_paid_sat: 21_000,
+                                creation_date: 1_700_000_200,
+                                state: InvoiceState::Settled as i32,
+                                ..Default::default()
+                            }],
+                            ..Default::default()
+                        }
+                    })
+                    .await
+                }
+                "/lnrpc.Lightning/ForwardingHistory" => {
+                    unary(request, |request: lnrpc::ForwardingHistoryRequest| {
+                        assert_eq!(request.start_time, 1_700_000_000);
+                        let events: Vec<_> = forwards()
+                            .into_iter()
+                            .skip(request.index_offset as usize)
+                            .take(request.num_max_events as usize)
+                            .collect();
+                        lnrpc::ForwardingHistoryResponse {
+                            last_offset_index: request.index_offset + events.len() as u32,
+                            forwarding_events: events,
+                        }
+                    })
+                    .await
+                }
+                "/lnrpc.Lightning/AddInvoice" => {
+                    unary(request, |request: lnrpc::Invoice| {
+                        assert_eq!(request.value, 21_000);
+                        assert_eq!(request.memo, "Un café");
+                        assert_eq!(request.expiry, 600);
+                        lnrpc::AddInvoiceResponse {
+                            r_hash: hex::decode(PAYMENT_HASH).unwrap(),
+                            payment_request: "lnbcrt210u1stub".to_string(),
+                            add_index: 7,
+                            ..Default::default()
+                        }
+                    })
+                    .await
+                }
+                "/lnrpc.Lightning/OpenChannelSync" => {
+                    unary(request, |request: lnrpc::OpenChannelRequest| {
+                        assert_eq!(hex::encode(&request.node_pubkey), PEER);
+                        assert_eq!(request.local_funding_amount, 1_000_000);
+                        lnrpc::ChannelPoint {
+                            funding_txid: Some(FundingTxid::FundingTxidBytes(
+                                hex::decode(FUNDING_TXID)
+                                    .unwrap()
+                                    .into_iter()
+                                    .rev()
+                                    .collect(),
+                            )),
+                            output_index: 0,
+                        }
+                    })
+                    .await
+                }
+                "/lnrpc.Lightning/CloseChannel" => {
+                    if let Some(error) = self.close_error {
+                        return Status::failed_precondition(error).into_http();
+                    }
+                    stream(request, |request: lnrpc::CloseChannelRequest| {
+                        let point = request.channel_point.unwrap();
+                        assert_eq!(format_channel_point(&point), channel_point());
+                        assert!(request.force);
+                        vec![lnrpc::CloseStatusUpdate {
+                            update: Some(close_status_update::Update::ClosePending(
+                                lnrpc::PendingUpdate {
+                                    txid: (0u8..32).collect(),
+                                    output_index: 0,
+                                    ..Default::default()
+                                },
+                            )),
+                        }]
+                    })
+                    .await
+                }
+                _ => Status::unimplemented(path).into_http(),
+            }
+        }
+
+        /// Sert le faux `lnd` en clair sur un port local et s'y connecte
+        async fn start(&self, macaroon_hex: &str) -> LndGrpcBackend {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let address = format!("http://{}", listener.local_addr().unwrap());
+            let server = Server::builder()
+                .add_service(Named::<Lightning>::new(self.clone()))
+                .add_service(Named::<Router>::new(self.clone()))
+                .serve_with_incoming(TcpIncoming::from(listener));
+            tokio::spawn(server);
+
+            let channel = Endpoint::from_shared(address)
+                .unwrap()
+                .connect()
+                .await
+                .unwrap();
+            LndGrpcBackend::with_channel(channel, macaroon_hex).unwrap()
+        }
+
+        fn calls(&self) -> Vec<String> {
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    struct Lightning;
+    struct Router;
+
+    /// Le serveur tonic route chaque appel vers le service nommé par son chemin
+    struct Named<S> {
+        lnd: StubLnd,
+        service: PhantomData<S>,
+    }
+
+    impl<S> Named<S> {
+        fn new(lnd: StubLnd) -> Self {
+            Self {
+                lnd,
+                service: PhantomData,
+            }
+        }
+    }
+
+    impl<S> Clone for Named<S> {
+        fn clone(&self) -> Self {
+            Self::new(self.lnd.clone())
+        }
+    }
+
+    impl NamedService for Named<Lightning> {
+        const NAME: &'static str = "lnrpc.Lightning";
+    }
+
+    impl NamedService for Named<Router> {
+        const NAME: &'static str = "routerrpc.Router";
+    }
+
+    impl<S> Service<http::Request<Body>> for Named<S> {
+        type Response = http::Response<Body>;
+        type Error = Infallible;
+        type Future = BoxFuture<Self::Response, Infallible>;
+
+        fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, request: http::Request<Body>) -> Self::Future {
+            let lnd = self.lnd.clone();
+            Box::pin(async move { Ok(lnd.dispatch(request).await) })
+        }
+    }
+
+    #[tokio::test]
+    async fn reads_node_info_with_capacity_of_open_channels() {
+        let lnd = StubLnd::default();
+        let info = lnd.start(MACAROON).await.get_node_info().await.unwrap();
+
+        assert_eq!(info.alias, "lnd-test");
+        assert_eq!(info.version, "0.18.3-beta");
+        assert_eq!(info.channels, 2);
+        assert_eq!(info.capacity, 1_000_000);
+        assert!(info.network.is_some());
+        assert_eq!(
+            lnd.calls(),
+            ["/lnrpc.Lightning/GetInfo", "/lnrpc.Lightning/ListChannels"]
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_calls_without_the_expected_macaroon() {
+        let lnd = StubLnd::default();
+        let error = lnd.start("0201").await.get_node_info().await.unwrap_err();
+
+        assert!(
+            format!("{:#}", error).contains("macaroon absent"),
+            "{:#}",
+            error
+        );
+    }
+
+    #[tokio::test]
+    async fn lists_open_channels_with_policies_and_pending_opens() {
+        let lnd = StubLnd::default();
+        let channels = lnd.start(MACAROON).await.list_channels().await.unwrap();
+
+        assert_eq!(channels.len(), 2);
+        let open = &channels[0];
+        assert_eq!(open.id, channel_point());
+        assert_eq!(open.short_channel_id.as_deref(), Some("103x1x0"));
+        assert_eq!(open.status, ChannelStatus::Active);
+        assert_eq!(open.local_balance, 600_000);
+        assert_eq!(open.csv_delay, Some(144));
+        // Le pair est node1 : notre politique est celle de node2
+        assert_eq!(open.local_policy.as_ref().unwrap().fee_rate_ppm, 100);
+        assert_eq!(open.remote_policy.as_ref().unwrap().fee_rate_ppm, 500);
+
+        let pending = &channels[1];
+        assert_eq!(pending.id, format!("{}:1", FUNDING_TXID));
+        assert_eq!(pending.status, ChannelStatus::Pending);
+        assert_eq!(pending.short_channel_id, None);
+        assert_eq!(pending.capacity, 500_000);
+    }
+
+    #[tokio::test]
+    async fn sends_payment_through_the_router_stream() {
+        let lnd = StubLnd::default();
+        let backend = lnd.start(MACAROON).await;
+        let invoice = encode(
+            "lnbc2500u",
+            &[
+                payment_hash_field(PAYMENT_HASH),
+                description_hash_field("Un café"),
+            ],
+        );
+        let options = PaymentOptions {
+            max_fee: Some(100),
+            max_parts: Some(4),
+            timeout: Some(30),
+            outgoing_channel: Some("103x1x0".to_string()),
+            ..Default::default()
+        };
+
+        let result = backend.send_payment(&invoice, &options).await.unwrap();
+
+        assert_eq!(result.status, TransactionStatus::Completed);
+        assert_eq!(result.payment_hash, PAYMENT_HASH);
+        assert_eq!(result.preimage, Some("ff".repeat(32)));
+        assert_eq!(
+            (result.amount, result.fee, result.attempts),
+            (250_000, 12, 1)
+        );
+        assert_eq!(result.routes.len(), 1);
+        assert_eq!(result.routes[0].hops[0].channel_id, "103x1x0");
+
+        let payments = lnd.payments.lock().unwrap();
+        let request = &payments[0];
+        assert_eq!(request.payment_request, invoice);
+        assert_eq!(request.fee_limit_msat, 100_000);
+        assert_eq!(request.max_parts, 4);
+        assert_eq!(request.timeout_seconds, 30);
+        assert_eq!(request.outgoing_chan_ids, [CHAN_ID]);
+        assert!(request.no_inflight_updates);
+    }
+
+    #[tokio::test]
+    async fn relays_subscribed_events_to_the_sink() {
+        let lnd = StubLnd::default();
+        let backend = lnd.start(MACAROON).await;
+        let bus = EventBus::new();
+        let mut events = bus.subscribe();
+        let sink = bus.sink("lnd");
+        let subscription = tokio::spawn(async move { backend.subscribe_events(&sink).await });
+
+        let mut received = Vec::new();
+        while received.len() < 4 {
+            let message = tokio::time::timeout(Duration::from_secs(5), events.recv())
+                .await
+                .expect("événement attendu")
+                .unwrap();
+            assert_eq!(message.node, "lnd");
+            received.push(message.event);
+        }
+        // Les flux restent ouverts : l'abonnement ne s'arrête pas de lui-même
+        assert!(!subscription.is_finished());
+        subscription.abort();
+
+        let settled = received.iter().find_map(|event| match event {
+            NodeEvent::InvoiceSettled {
+                payment_hash,
+                amount,
+            } => Some((payment_hash.as_str(), *amount)),
+            _ => None,
+        });
+        assert_eq!(settled, Some((PAYMENT_HASH, 21_000)));
+        assert!(received.iter().any(|event| matches!(
+            event,
+            NodeEvent::ChannelClosed { channel_id } if *channel_id == channel_point()
+        )));
+        let forwards: Vec<_> = received
+            .iter()
+            .filter_map(|event| match event {
+                NodeEvent::HtlcForwarded(forward) => Some(forward),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(forwards.len(), 1);
+        assert_eq!(forwards[0].chan_id_in, "103x1x0");
+        assert_eq!(forwards[0].chan_id_out, "103x1x1");
+        assert_eq!(forwards[0].fee_msat, 50_000);
+        assert_eq!(forwards[0].timestamp, 1_700_000_000);
+        assert!(received.iter().any(|event| matches!(
+            event,
+            NodeEvent::PaymentUpdated {
+                status: TransactionStatus::Pending,
+                amount: 5_000,
+                ..
+            }
+        )));
+    }
+
+    #[tokio::test]
+    async fn lists_payments_and_invoices_newest_first() {
+        let lnd = StubLnd::default();
+        let transactions = lnd.start(MACAROON).await.list_transactions().await.unwrap();
+
+        assert_eq!(transactions.len(), 2);
+        let invoice = &transactions[0];
+        assert!(matches!(invoice.type_, TransactionType::Invoice));
+        assert_eq!(invoice.id, "aa".repeat(32));
+        assert_eq!(invoice.amount, 21_000);
+        assert_eq!(invoice.description.as_deref(), Some("Un café"));
+        assert!(!invoice.outgoing);
+
+        let payment = &transactions[1];
+        assert!(matches!(payment.type_, TransactionType::Payment));
+        assert_eq!(payment.id, PAYMENT_HASH);
+        assert_eq!(payment.status, TransactionStatus::Completed);
+        assert_eq!((payment.amount, payment.fee), (250_000, 12));
+        assert_eq!(payment.timestamp, 1_700_000_100);
+        assert!(payment.outgoing);
+    }
+
+    #[tokio::test]
+    async fn pages_forwarding_history_by_index_offset() {
+        let lnd = StubLnd::default();
+        let backend = lnd.start(MACAROON).await;
+
+        let first = backend
+            .forwarding_history(1_700_000_000, 0, 2)
+            .await
+            .unwrap();
+        assert_eq!(first.events.len(), 2);
+        assert_eq!(first.next_offset, 2);
+        assert_eq!(first.events[0].chan_id_in, "103x1x0");
+        assert_eq!(first.events[0].chan_id_out, "103x1x1");
+        assert_eq!(first.events[0].timestamp, 1_700_000_001);
+        assert_eq!(first.events[1].fee_msat, 2_000);
+
+        let second = backend
+            .forwarding_history(1_700_000_000, first.next_offset, 2)
+            .await
+            .unwrap();
+        assert_eq!(second.events.len(), 1);
+        assert_eq!(second.events[0].amt_in_msat, 100_003_000);
+        assert_eq!(second.next_offset, 3);
+
+        let last = backend
+            .forwarding_history(1_700_000_000, second.next_offset, 2)
+            .await
+            .unwrap();
+        assert!(last.events.is_empty());
+        assert_eq!(last.next_offset, 3);
+    }
+
+    #[tokio::test]
+    async fn creates_invoice() {
+        let lnd = StubLnd::default();
+        let invoice = lnd
+            .start(MACAROON)
+            .await
+            .create_invoice(21_000, "Un café", Some(600))
+            .await
+            .unwrap();
+
+        assert_eq!(invoice.payment_hash, PAYMENT_HASH);
+        assert_eq!(invoice.payment_request, "lnbcrt210u1stub");
+        assert_eq!(invoice.amount, 21_000);
+        assert_eq!(invoice.expiry, 600);
+    }
+
+    #[tokio::test]
+    async fn opens_channel_and_reports_the_funding_txid() {
+        let lnd = StubLnd::default();
+        let result = lnd
+            .start(MACAROON)
+            .await
+            .open_channel(PEER, 1_000_000)
+            .await
+            .unwrap();
+
+        assert_eq!(result.funding_txid, FUNDING_TXID);
+        assert_eq!(result.output_index, Some(0));
+    }
+
+    #[tokio::test]
+    async fn closes_channel_with_the_pending_closing_txid() {
+        let lnd = StubLnd::default();
+        let options = CloseOptions {
+            force: true,
+            ..Default::default()
+        };
+        let result = lnd
+            .start(MACAROON)
+            .await
+            .close_channel(&channel_point(), &options)
+            .await
+            .unwrap();
+
+        let expected: Vec<u8> = (0u8..32).rev().collect();
+        assert_eq!(result.closing_txid, Some(hex::encode(expected)));
+        assert_eq!(result.channel_id, channel_point());
+        assert!(result.force);
+    }
+
+    #[tokio::test]
+    async fn fails_close_refused_by_the_node() {
+        let lnd = StubLnd {
+            close_error: Some("channel is locked by a pending HTLC"),
+            ..Default::default()
+        };
+        let error = lnd
+            .start(MACAROON)
+            .await
+            .close_channel(&channel_point(), &CloseOptions::default())
+            .await
+            .unwrap_err();
+
+        assert!(
+            format!("{:#}", error).contains("channel is locked by a pending HTLC"),
+            "{:#}",
+            error
+        );
+    }
+}
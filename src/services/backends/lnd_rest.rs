@@ -0,0 +1,1611 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use reqwest::{Client, Method, RequestBuilder, Response};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tracing::{info, instrument};
+
+use super::{
+    base64_to_hex, fee_limit_msat, format_scid, i64_from_any, lnd_transaction_type, parse_scid,
+    u64_from_any, value_as_u64, BackendKind, ForwardingPage, LndCredentials, NodeBackend,
+    DEFAULT_INVOICE_EXPIRY, DEFAULT_PAYMENT_TIMEOUT,
+};
+use crate::models::lightning::{
+    BatchOpenRequest, Channel, ChannelCloseResult, ChannelOpenResult, ChannelPolicy,
+    ChannelRequest, ChannelStatus, CloseOptions, ForwardingEvent, Invoice, NodeInfo,
+    PaymentOptions, PaymentResult, PaymentRoute, PendingChannel, PendingChannels, PendingState,
+    PolicyUpdate, PsbtFunding, RouteHop, RouteQuote, Transaction, TransactionStatus,
+    TransactionType,
+};
+use crate::models::onchain::{AddressType, OnchainSendRequest, Utxo, WalletBalance};
+use crate::models::pnl::{ChannelCost, CostKind};
+use crate::services::bolt11;
+use crate::services::keysend::{self, KEYSEND_PREIMAGE_RECORD};
+
+/// Backend pour l'API REST de LND (`/v1/...`)
+pub struct LndRestBackend {
+    client: Client,
+    base_url: String,
+    macaroon_hex: String,
+    /// Flux d'ouverture des financements PSBT en cours, par `pending_chan_id` :
+    /// LND y publie le canal en attente une fois la transaction finalisée
+    psbt_fundings: Mutex<HashMap<String, LndStream>>,
+}
+
+/// Flux de mises à jour d'un appel LND en streaming, un objet JSON par ligne
+struct LndStream {
+    response: Response,
+    buffer: String,
+}
+
+impl LndStream {
+    fn new(response: Response) -> Self {
+        Self {
+            response,
+            buffer: String::new(),
+        }
+    }
+
+    /// Prochain `result` du flux ; une ligne `error` interrompt la lecture
+    async fn next(&mut self) -> Result<Value> {
+        loop {
+            if let Some(end) = self.buffer.find('\n') {
+                let line: String = self.buffer.drain(..=end).collect();
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let mut update: Value = serde_json::from_str(&line)?;
+                if let Some(error) = update.get("error") {
+                    bail!(
+                        "Erreur LND: {}",
+                        error["message"].as_str().unwrap_or("erreur inconnue")
+                    );
+                }
+                return Ok(update["result"].take());
+            }
+            let chunk = self
+                .response
+                .chunk()
+                .await?
+                .context("Flux LND interrompu avant la mise à jour attendue")?;
+            self.buffer.push_str(&String::from_utf8_lossy(&chunk));
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct LndGetInfo {
+    identity_pubkey: String,
+    alias: String,
+    version: String,
+    #[serde(default)]
+    block_height: u32,
+    #[serde(default)]
+    num_active_channels: u32,
+    #[serde(default)]
+    num_inactive_channels: u32,
+    #[serde(default)]
+    num_pending_channels: u32,
+    #[serde(default)]
+    chains: Vec<LndChain>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndChain {
+    network: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndChannels {
+    #[serde(default)]
+    channels: Vec<LndChannel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndChannel {
+    channel_point: String,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    chan_id: u64,
+    remote_pubkey: String,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    capacity: u64,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    local_balance: u64,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    remote_balance: u64,
+    #[serde(default)]
+    active: bool,
+    /// Vrai si nous avons ouvert, et donc financé, le canal
+    #[serde(default)]
+    initiator: bool,
+    local_constraints: Option<LndChannelConstraints>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndClosedChannels {
+    #[serde(default)]
+    channels: Vec<LndClosedChannel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndClosedChannel {
+    channel_point: String,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    chan_id: u64,
+    #[serde(default)]
+    remote_pubkey: String,
+    #[serde(default)]
+    closing_tx_hash: String,
+    /// `INITIATOR_LOCAL`, `INITIATOR_REMOTE`...
+    #[serde(default)]
+    open_initiator: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndWalletBalance {
+    #[serde(deserialize_with = "u64_from_any", default)]
+    confirmed_balance: u64,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    unconfirmed_balance: u64,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    locked_balance: u64,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    reserved_balance_anchor_chan: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndUtxos {
+    #[serde(default)]
+    utxos: Vec<LndUtxo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndUtxo {
+    address: String,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    amount_sat: u64,
+    outpoint: LndOutPoint,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    confirmations: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndOutPoint {
+    txid_str: String,
+    output_index: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndOnchainTransactions {
+    #[serde(default)]
+    transactions: Vec<LndOnchainTransaction>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndOnchainTransaction {
+    tx_hash: String,
+    /// Négatif pour une dépense du portefeuille
+    #[serde(deserialize_with = "i64_from_any", default)]
+    amount: i64,
+    #[serde(default)]
+    num_confirmations: i32,
+    #[serde(deserialize_with = "i64_from_any", default)]
+    time_stamp: i64,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    total_fees: u64,
+    #[serde(default)]
+    label: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndChannelConstraints {
+    #[serde(default)]
+    csv_delay: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndFeeEstimate {
+    #[serde(deserialize_with = "u64_from_any", default)]
+    sat_per_kw: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndChannelEdge {
+    node1_pub: String,
+    node1_policy: Option<LndRoutingPolicy>,
+    node2_policy: Option<LndRoutingPolicy>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndRoutingPolicy {
+    #[serde(default)]
+    time_lock_delta: u32,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    min_htlc: u64,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    max_htlc_msat: u64,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    fee_base_msat: u64,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    fee_rate_milli_msat: u64,
+    #[serde(default)]
+    inbound_fee_base_msat: i32,
+    #[serde(default)]
+    inbound_fee_rate_milli_msat: i32,
+    #[serde(default)]
+    disabled: bool,
+}
+
+impl From<LndRoutingPolicy> for ChannelPolicy {
+    fn from(policy: LndRoutingPolicy) -> Self {
+        ChannelPolicy {
+            base_fee_msat: policy.fee_base_msat,
+            fee_rate_ppm: policy.fee_rate_milli_msat as u32,
+            time_lock_delta: policy.time_lock_delta,
+            min_htlc_msat: policy.min_htlc,
+            max_htlc_msat: policy.max_htlc_msat,
+            inbound_base_fee_msat: policy.inbound_fee_base_msat,
+            inbound_fee_rate_ppm: policy.inbound_fee_rate_milli_msat,
+            disabled: policy.disabled,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct LndPolicyUpdateResponse {
+    #[serde(default)]
+    failed_updates: Vec<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndPendingChannels {
+    #[serde(default)]
+    pending_open_channels: Vec<LndPendingOpen>,
+    #[serde(default)]
+    pending_closing_channels: Vec<LndClosingChannel>,
+    #[serde(default)]
+    pending_force_closing_channels: Vec<LndForceClosingChannel>,
+    #[serde(default)]
+    waiting_close_channels: Vec<LndClosingChannel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndPendingOpen {
+    channel: LndPendingChannel,
+}
+
+/// Canal en fermeture coopérative (`pending_closing_channels`) ou dont la
+/// transaction de fermeture attend sa confirmation (`waiting_close_channels`)
+#[derive(Debug, Deserialize)]
+struct LndClosingChannel {
+    channel: LndPendingChannel,
+    #[serde(default)]
+    closing_txid: String,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    limbo_balance: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndForceClosingChannel {
+    channel: LndPendingChannel,
+    #[serde(default)]
+    closing_txid: String,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    limbo_balance: u64,
+    #[serde(default)]
+    maturity_height: u32,
+    /// Négatif une fois la maturité dépassée
+    #[serde(default)]
+    blocks_til_maturity: i32,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndPendingChannel {
+    remote_node_pub: String,
+    channel_point: String,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    capacity: u64,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    local_balance: u64,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    remote_balance: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndPayments {
+    #[serde(default)]
+    payments: Vec<LndPayment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndPayment {
+    payment_hash: String,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    value_sat: u64,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    fee_sat: u64,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    fee_msat: u64,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    creation_date: u64,
+    status: String,
+    #[serde(default)]
+    payment_preimage: String,
+    #[serde(default)]
+    failure_reason: String,
+    #[serde(default)]
+    htlcs: Vec<LndPaymentHtlc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndPaymentHtlc {
+    status: String,
+    route: Option<LndRoute>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndRoute {
+    #[serde(default)]
+    hops: Vec<LndHop>,
+    #[serde(default)]
+    total_time_lock: u32,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    total_amt_msat: u64,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    total_fees_msat: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndHop {
+    #[serde(deserialize_with = "u64_from_any", default)]
+    chan_id: u64,
+    #[serde(default)]
+    pub_key: String,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    amt_to_forward_msat: u64,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    fee_msat: u64,
+    /// Valeurs encodées en base64, indexées par le type en chaîne
+    #[serde(default)]
+    custom_records: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndInvoices {
+    #[serde(default)]
+    invoices: Vec<LndInvoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndInvoice {
+    r_hash: String,
+    #[serde(default)]
+    memo: String,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    value: u64,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    amt_paid_sat: u64,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    creation_date: u64,
+    state: String,
+    #[serde(default)]
+    is_keysend: bool,
+    #[serde(default)]
+    htlcs: Vec<LndInvoiceHtlc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndInvoiceHtlc {
+    #[serde(default)]
+    custom_records: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndForwardingHistory {
+    #[serde(default)]
+    forwarding_events: Vec<LndForwardingEvent>,
+    #[serde(default)]
+    last_offset_index: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndForwardingEvent {
+    #[serde(deserialize_with = "u64_from_any", default)]
+    chan_id_in: u64,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    chan_id_out: u64,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    amt_in_msat: u64,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    amt_out_msat: u64,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    fee_msat: u64,
+    #[serde(deserialize_with = "u64_from_any", default)]
+    timestamp_ns: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct LndQueryRoutes {
+    #[serde(default)]
+    routes: Vec<LndRoute>,
+}
+
+fn route_hops(hops: Vec<LndHop>) -> Vec<RouteHop> {
+    hops.into_iter()
+        .map(|hop| RouteHop {
+            pubkey: hop.pub_key,
+            channel_id: format_scid(hop.chan_id),
+            amount_msat: hop.amt_to_forward_msat,
+            fee_msat: hop.fee_msat,
+        })
+        .collect()
+}
+
+impl From<LndPayment> for PaymentResult {
+    fn from(payment: LndPayment) -> Self {
+        let status = match payment.status.as_str() {
+            "SUCCEEDED" => TransactionStatus::Completed,
+            "FAILED" => TransactionStatus::Failed,
+            _ => TransactionStatus::Pending,
+        };
+        PaymentResult {
+            preimage: (status == TransactionStatus::Completed)
+                .then(|| payment.payment_preimage.clone()),
+            failure_reason: (status == TransactionStatus::Failed)
+                .then(|| payment.failure_reason.clone()),
+            status,
+            attempts: payment.htlcs.len() as u32,
+            routes: payment
+                .htlcs
+                .into_iter()
+                .filter(|h| h.status == "SUCCEEDED")
+                .filter_map(|h| h.route)
+                .map(|route| PaymentRoute {
+                    hops: route_hops(route.hops),
+                    amount_msat: route.total_amt_msat,
+                    fee_msat: route.total_fees_msat,
+                })
+                .collect(),
+            payment_hash: payment.payment_hash,
+            amount: payment.value_sat,
+            fee: payment.fee_sat,
+        }
+    }
+}
+
+/// Décode les enregistrements personnalisés de l'API REST (`{"type": "base64"}`)
+fn lnd_custom_records(records: HashMap<String, String>) -> BTreeMap<u64, String> {
+    keysend::custom_records(
+        records
+            .into_iter()
+            .filter_map(|(record, value)| Some((record.parse().ok()?, BASE64.decode(value).ok()?))),
+    )
+}
+
+/// LND transmet les txid en octets little-endian : on les remet dans l'ordre d'affichage
+fn reversed_txid(hex_txid: &str) -> String {
+    hex::decode(hex_txid)
+        .map(|mut bytes| {
+            bytes.reverse();
+            hex::encode(bytes)
+        })
+        .unwrap_or_else(|_| hex_txid.to_string())
+}
+
+/// Canal en attente, les confirmations de la transaction attendue étant lues
+/// dans le portefeuille (`confirmations`, par txid)
+fn to_pending_channel(
+    channel: LndPendingChannel,
+    state: PendingState,
+    closing_txid: String,
+    limbo_balance: u64,
+    confirmations: &HashMap<String, u32>,
+) -> PendingChannel {
+    let funding_txid = channel
+        .channel_point
+        .split(':')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    let closing_txid = (!closing_txid.is_empty()).then_some(closing_txid);
+    PendingChannel {
+        confirmations: confirmations
+            .get(closing_txid.as_ref().unwrap_or(&funding_txid))
+            .copied(),
+        channel_id: channel.channel_point,
+        remote_pubkey: channel.remote_node_pub,
+        capacity: channel.capacity,
+        state,
+        funding_txid,
+        closing_txid,
+        blocks_til_maturity: None,
+        maturity_height: None,
+        limbo_balance,
+    }
+}
+
+/// Corps d'ouverture de canal commun à `/v1/channels/stream` et `/v1/channels/batch`
+fn open_request(request: &ChannelRequest) -> Result<Value> {
+    let pubkey = hex::decode(&request.pubkey).context("Clé publique invalide")?;
+    let mut body = json!({
+        "node_pubkey": BASE64.encode(pubkey),
+        "local_funding_amount": request.amount.to_string(),
+        "push_sat": request.push_amount.to_string(),
+        "private": request.private,
+    });
+    if let Some(base_fee) = request.base_fee_msat {
+        body["base_fee"] = json!(base_fee.to_string());
+        body["use_base_fee"] = json!(true);
+    }
+    if let Some(fee_rate) = request.fee_rate_ppm {
+        body["fee_rate"] = json!(fee_rate.to_string());
+        body["use_fee_rate"] = json!(true);
+    }
+    Ok(body)
+}
+
+/// Canal en attente (`PendingUpdate`), dont le txid est encodé en octets base64
+fn pending_channel(update: &Value) -> ChannelOpenResult {
+    ChannelOpenResult {
+        funding_txid: reversed_txid(&base64_to_hex(update["txid"].as_str().unwrap_or_default())),
+        output_index: update["output_index"].as_u64().map(|i| i as u32),
+    }
+}
+
+fn lnd_txid(channel_point: &Value) -> String {
+    match channel_point["funding_txid_str"].as_str() {
+        Some(txid) if !txid.is_empty() => txid.to_string(),
+        _ => reversed_txid(&base64_to_hex(
+            channel_point["funding_txid_bytes"]
+                .as_str()
+                .unwrap_or_default(),
+        )),
+    }
+}
+
+impl LndRestBackend {
+    pub fn new(base_url: String, credentials: &LndCredentials) -> Result<Self> {
+        info!(base_url = %base_url, "Création du backend LND REST");
+        Ok(Self {
+            client: credentials.http_client()?,
+            base_url,
+            macaroon_hex: credentials.macaroon_hex.clone(),
+            psbt_fundings: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let url = format!("{}{}", self.base_url, path);
+        self.client
+            .request(method, &url)
+            .header("Grpc-Metadata-macaroon", &self.macaroon_hex)
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let response = self
+            .request(Method::GET, path)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.json::<T>().await?)
+    }
+
+    /// Politiques locale et distante d'un canal, lues dans le graphe du nœud.
+    ///
+    /// Un canal absent du graphe (pas encore annoncé) n'a pas de politique connue.
+    async fn channel_policies(
+        &self,
+        chan_id: u64,
+        remote_pubkey: &str,
+    ) -> (Option<ChannelPolicy>, Option<ChannelPolicy>) {
+        let Ok(edge) = self
+            .get::<LndChannelEdge>(&format!("/v1/graph/edge/{}", chan_id))
+            .await
+        else {
+            return (None, None);
+        };
+        let (local, remote) = if edge.node1_pub == remote_pubkey {
+            (edge.node2_policy, edge.node1_policy)
+        } else {
+            (edge.node1_policy, edge.node2_policy)
+        };
+        (local.map(Into::into), remote.map(Into::into))
+    }
+
+    /// Identifiant numérique d'un canal, désigné par son point de financement
+    /// ou son short channel id
+    async fn chan_id(&self, channel_id: &str) -> Result<u64> {
+        if let Some(scid) = parse_scid(channel_id) {
+            return Ok(scid);
+        }
+        let channels: LndChannels = self.get("/v1/channels").await?;
+        channels
+            .channels
+            .into_iter()
+            .find(|c| c.channel_point == channel_id)
+            .map(|c| c.chan_id)
+            .with_context(|| format!("Canal {} introuvable parmi les canaux ouverts", channel_id))
+    }
+
+    /// Corps de `/v2/router/send` pour une facture, avec les contraintes de `options`
+    async fn payment_body(&self, payment_request: &str, options: &PaymentOptions) -> Result<Value> {
+        let amount_msat = bolt11::decode(payment_request)?.amount_msat.unwrap_or(0);
+        let mut request = json!({
+            "payment_request": payment_request,
+            "fee_limit_msat": fee_limit_msat(options, amount_msat).to_string(),
+            "timeout_seconds": options.timeout.unwrap_or(DEFAULT_PAYMENT_TIMEOUT),
+        });
+        if let Some(max_parts) = options.max_parts {
+            request["max_parts"] = max_parts.into();
+        }
+        if let Some(channel_id) = &options.outgoing_channel {
+            request["outgoing_chan_ids"] = json!([self.chan_id(channel_id).await?.to_string()]);
+        }
+        if let Some(last_hop) = &options.last_hop {
+            request["last_hop_pubkey"] = BASE64.encode(hex::decode(last_hop)?).into();
+        }
+        Ok(request)
+    }
+
+    /// Lance un paiement via `/v2/router/send` et attend son issue finale.
+    ///
+    /// LND diffuse une ligne JSON par mise à jour et ferme le flux une fois le paiement terminé.
+    async fn router_send(&self, mut request: Value) -> Result<PaymentResult> {
+        request["no_inflight_updates"] = true.into();
+        let body = self
+            .request(Method::POST, "/v2/router/send")
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        let mut payment = None;
+        for line in body.lines().filter(|line| !line.trim().is_empty()) {
+            let update: Value = serde_json::from_str(line)?;
+            if let Some(error) = update.get("error") {
+                bail!(
+                    "Erreur LND: {}",
+                    error["message"].as_str().unwrap_or("erreur inconnue")
+                );
+            }
+            payment = Some(update["result"].clone());
+        }
+        let payment: LndPayment = serde_json::from_value(
+            payment.context("Flux de paiement interrompu avant la fin du paiement")?,
+        )?;
+        Ok(payment.into())
+    }
+
+    /// Avance un financement PSBT d'une étape (`psbt_verify`, `psbt_finalize`, `shim_cancel`)
+    async fn funding_step(&self, step: &str, funding_id: &str, mut body: Value) -> Result<()> {
+        let pending_chan_id =
+            hex::decode(funding_id).context("Identifiant de financement invalide")?;
+        body["pending_chan_id"] = json!(BASE64.encode(pending_chan_id));
+        self.post("/v1/funding/step", json!({ step: body })).await?;
+        Ok(())
+    }
+
+    async fn post(&self, path: &str, body: Value) -> Result<Value> {
+        let response = self
+            .request(Method::POST, path)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.json().await?)
+    }
+}
+
+#[async_trait]
+impl NodeBackend for LndRestBackend {
+    fn kind(&self) -> BackendKind {
+        BackendKind::Lnd
+    }
+
+    #[instrument(skip(self), err)]
+    async fn get_node_info(&self) -> Result<NodeInfo> {
+        let info: LndGetInfo = self.get("/v1/getinfo").await?;
+        // getinfo ne donne pas la capacité : on la calcule à partir des canaux
+        let capacity = self.list_channels().await?.iter().map(|c| c.capacity).sum();
+        Ok(NodeInfo {
+            pubkey: info.identity_pubkey,
+            alias: info.alias,
+            capacity,
+            channels: info.num_active_channels
+                + info.num_inactive_channels
+                + info.num_pending_channels,
+            version: info.version,
+            network: info.chains.first().and_then(|c| c.network.parse().ok()),
+            capabilities: self.capabilities(),
+        })
+    }
+
+    #[instrument(skip(self), err)]
+    async fn list_channels(&self) -> Result<Vec<Channel>> {
+        let open: LndChannels = self.get("/v1/channels").await?;
+        let pending: LndPendingChannels = self.get("/v1/channels/pending").await?;
+
+        let mut channels = Vec::with_capacity(open.channels.len());
+        for c in open.channels {
+            let (local_policy, remote_policy) =
+                self.channel_policies(c.chan_id, &c.remote_pubkey).await;
+            channels.push(Channel {
+                id: c.channel_point,
+                short_channel_id: Some(format_scid(c.chan_id)),
+                capacity: c.capacity,
+                local_balance: c.local_balance,
+                remote_balance: c.remote_balance,
+                remote_pubkey: c.remote_pubkey,
+                status: if c.active {
+                    ChannelStatus::Active
+                } else {
+                    ChannelStatus::Inactive
+                },
+                local_policy,
+                remote_policy,
+                csv_delay: c.local_constraints.map(|l| l.csv_delay),
+            });
+        }
+
+        channels.extend(pending.pending_open_channels.into_iter().map(|p| Channel {
+            id: p.channel.channel_point,
+            short_channel_id: None,
+            capacity: p.channel.capacity,
+            local_balance: p.channel.local_balance,
+            remote_balance: p.channel.remote_balance,
+            remote_pubkey: p.channel.remote_node_pub,
+            status: ChannelStatus::Pending,
+            local_policy: None,
+            remote_policy: None,
+            csv_delay: None,
+        }));
+
+        Ok(channels)
+    }
+
+    #[instrument(skip(self), err)]
+    async fn list_pending_channels(&self) -> Result<PendingChannels> {
+        let info: LndGetInfo = self.get("/v1/getinfo").await?;
+        let pending: LndPendingChannels = self.get("/v1/channels/pending").await?;
+        // Les confirmations ne figurent pas dans /v1/channels/pending
+        let wallet: LndOnchainTransactions = self.get("/v1/transactions").await?;
+        let confirmations: HashMap<String, u32> = wallet
+            .transactions
+            .into_iter()
+            .map(|t| (t.tx_hash, t.num_confirmations.max(0) as u32))
+            .collect();
+
+        let mut channels = Vec::new();
+        for p in pending.pending_open_channels {
+            let limbo_balance = p.channel.local_balance;
+            channels.push(to_pending_channel(
+                p.channel,
+                PendingState::PendingOpen,
+                String::new(),
+                limbo_balance,
+                &confirmations,
+            ));
+        }
+        for p in pending.pending_closing_channels {
+            let limbo_balance = p.channel.local_balance;
+            channels.push(to_pending_channel(
+                p.channel,
+                PendingState::PendingClose,
+                p.closing_txid,
+                limbo_balance,
+                &confirmations,
+            ));
+        }
+        for p in pending.waiting_close_channels {
+            channels.push(to_pending_channel(
+                p.channel,
+                PendingState::WaitingClose,
+                p.closing_txid,
+                p.limbo_balance,
+                &confirmations,
+            ));
+        }
+        for p in pending.pending_force_closing_channels {
+            let mut channel = to_pending_channel(
+                p.channel,
+                PendingState::ForceClosing,
+                p.closing_txid,
+                p.limbo_balance,
+                &confirmations,
+            );
+            // La maturité reste inconnue tant que des HTLC ne sont pas résolus
+            if p.maturity_height > 0 {
+                channel.maturity_height = Some(p.maturity_height);
+                channel.blocks_til_maturity = Some(p.blocks_til_maturity.max(0) as u32);
+            }
+            channels.push(channel);
+        }
+
+        Ok(PendingChannels {
+            block_height: info.block_height,
+            channels,
+        })
+    }
+
+    #[instrument(skip(self), err)]
+    async fn list_transactions(&self) -> Result<Vec<Transaction>> {
+        let payments: LndPayments = self.get("/v1/payments?include_incomplete=true").await?;
+        let invoices: LndInvoices = self.get("/v1/invoices").await?;
+
+        let mut transactions: Vec<Transaction> = payments
+            .payments
+            .into_iter()
+            .map(|p| {
+                // Les enregistrements envoyés figurent sur le dernier saut de la route réussie
+                let custom_records = p
+                    .htlcs
+                    .into_iter()
+                    .find(|h| h.status == "SUCCEEDED")
+                    .and_then(|h| h.route)
+                    .and_then(|r| r.hops.into_iter().last())
+                    .map(|hop| lnd_custom_records(hop.custom_records))
+                    .unwrap_or_default();
+                Transaction {
+                    id: p.payment_hash,
+                    amount: p.value_sat,
+                    fee: p.fee_sat,
+                    timestamp: p.creation_date as i64,
+                    type_: TransactionType::Payment,
+                    status: match p.status.as_str() {
+                        "SUCCEEDED" => TransactionStatus::Completed,
+                        "FAILED" => TransactionStatus::Failed,
+                        _ => TransactionStatus::Pending,
+                    },
+                    description: keysend::decode_message(&custom_records),
+                    outgoing: true,
+                    custom_records,
+                }
+            })
+            .collect();
+
+        transactions.extend(invoices.invoices.into_iter().map(|i| {
+            let custom_records =
+                lnd_custom_records(i.htlcs.into_iter().flat_map(|h| h.custom_records).collect());
+            Transaction {
+                // r_hash est encodé en base64 dans l'API REST
+                id: BASE64
+                    .decode(&i.r_hash)
+                    .map(hex::encode)
+                    .unwrap_or(i.r_hash),
+                amount: if i.amt_paid_sat > 0 {
+                    i.amt_paid_sat
+                } else {
+                    i.value
+                },
+                fee: 0,
+                timestamp: i.creation_date as i64,
+                type_: if i.is_keysend {
+                    TransactionType::Keysend
+                } else {
+                    TransactionType::Invoice
+                },
+                status: match i.state.as_str() {
+                    "SETTLED" => TransactionStatus::Completed,
+                    "CANCELED" => TransactionStatus::Failed,
+                    _ => TransactionStatus::Pending,
+                },
+                description: (!i.memo.is_empty())
+                    .then_some(i.memo)
+                    .or_else(|| keysend::decode_message(&custom_records)),
+                outgoing: false,
+                custom_records,
+            }
+        }));
+
+        transactions.sort_by_key(|t| Reverse(t.timestamp));
+        Ok(transactions)
+    }
+
+    #[instrument(skip(self), err)]
+    async fn forwarding_history(
+        &self,
+        start_time: i64,
+        offset: u64,
+        limit: u32,
+    ) -> Result<ForwardingPage> {
+        let response = self
+            .post(
+                "/v1/switch",
+                json!({
+                    "start_time": start_time.max(0).to_string(),
+                    "index_offset": offset,
+                    "num_max_events": limit,
+                }),
+            )
+            .await?;
+        let history: LndForwardingHistory = serde_json::from_value(response)?;
+
+        Ok(ForwardingPage {
+            events: history
+                .forwarding_events
+                .into_iter()
+                .map(|e| ForwardingEvent {
+                    timestamp: (e.timestamp_ns / 1_000_000_000) as i64,
+                    chan_id_in: format_scid(e.chan_id_in),
+                    chan_id_out: format_scid(e.chan_id_out),
+                    amt_in_msat: e.amt_in_msat,
+                    amt_out_msat: e.amt_out_msat,
+                    fee_msat: e.fee_msat,
+                })
+                .collect(),
+            next_offset: history.last_offset_index as u64,
+        })
+    }
+
+    async fn send_payment(
+        &self,
+        payment_request: &str,
+        options: &PaymentOptions,
+    ) -> Result<PaymentResult> {
+        self.router_send(self.payment_body(payment_request, options).await?)
+            .await
+    }
+
+    async fn send_circular_payment(
+        &self,
+        payment_request: &str,
+        options: &PaymentOptions,
+    ) -> Result<PaymentResult> {
+        let mut request = self.payment_body(payment_request, options).await?;
+        request["allow_self_payment"] = true.into();
+        self.router_send(request).await
+    }
+
+    async fn send_keysend(
+        &self,
+        pubkey: &str,
+        amount: u64,
+        custom_records: &BTreeMap<u64, Vec<u8>>,
+    ) -> Result<PaymentResult> {
+        let (preimage, payment_hash) = keysend::new_preimage();
+        let mut records: HashMap<String, String> = custom_records
+            .iter()
+            .map(|(record, value)| (record.to_string(), BASE64.encode(value)))
+            .collect();
+        records.insert(KEYSEND_PREIMAGE_RECORD.to_string(), BASE64.encode(preimage));
+
+        self.router_send(json!({
+            "dest": BASE64.encode(hex::decode(pubkey)?),
+            "amt": amount.to_string(),
+            "payment_hash": BASE64.encode(payment_hash),
+            "dest_custom_records": records,
+            "fee_limit_msat": fee_limit_msat(&PaymentOptions::default(), amount * 1000).to_string(),
+            "timeout_seconds": DEFAULT_PAYMENT_TIMEOUT,
+        }))
+        .await
+    }
+
+    /// LND donne un verrou temporel absolu : il est ramené à la hauteur actuelle
+    async fn query_routes(&self, destination: &str, amount: u64) -> Result<Vec<RouteQuote>> {
+        let info: LndGetInfo = self.get("/v1/getinfo").await?;
+        let response: LndQueryRoutes = self
+            .get(&format!("/v1/graph/routes/{}/{}", destination, amount))
+            .await?;
+        Ok(response
+            .routes
+            .into_iter()
+            .map(|route| {
+                RouteQuote::new(
+                    route_hops(route.hops),
+                    route.total_amt_msat,
+                    route.total_fees_msat,
+                    route.total_time_lock.saturating_sub(info.block_height),
+                )
+            })
+            .collect())
+    }
+
+    async fn create_invoice(
+        &self,
+        amount: u64,
+        description: &str,
+        expiry: Option<u64>,
+    ) -> Result<Invoice> {
+        let expiry = expiry.unwrap_or(DEFAULT_INVOICE_EXPIRY);
+        let response = self
+            .post(
+                "/v1/invoices",
+                json!({
+                    "value": amount.to_string(),
+                    "memo": description,
+                    "expiry": expiry.to_string(),
+                }),
+            )
+            .await?;
+        Ok(Invoice {
+            payment_hash: base64_to_hex(response["r_hash"].as_str().unwrap_or_default()),
+            payment_request: response["payment_request"]
+                .as_str()
+                .context("Réponse LND sans payment_request")?
+                .to_string(),
+            amount,
+            description: description.to_string(),
+            expiry,
+        })
+    }
+
+    async fn wallet_balance(&self) -> Result<WalletBalance> {
+        let balance: LndWalletBalance = self.get("/v1/balance/blockchain").await?;
+        Ok(WalletBalance {
+            confirmed: balance.confirmed_balance,
+            unconfirmed: balance.unconfirmed_balance,
+            locked: balance.locked_balance + balance.reserved_balance_anchor_chan,
+        })
+    }
+
+    async fn list_utxos(&self) -> Result<Vec<Utxo>> {
+        let response: LndUtxos = self
+            .get(&format!("/v1/utxos?min_confs=0&max_confs={}", i32::MAX))
+            .await?;
+        Ok(response
+            .utxos
+            .into_iter()
+            .map(|u| Utxo {
+                txid: u.outpoint.txid_str,
+                output_index: u.outpoint.output_index,
+                address: u.address,
+                amount: u.amount_sat,
+                confirmations: u.confirmations as u32,
+            })
+            .collect())
+    }
+
+    async fn new_address(&self, address_type: AddressType) -> Result<String> {
+        let lnd_type = match address_type {
+            AddressType::P2wkh => "WITNESS_PUBKEY_HASH",
+            AddressType::P2tr => "TAPROOT_PUBKEY",
+        };
+        let response: Value = self
+            .get(&format!("/v1/newaddress?type={}", lnd_type))
+            .await?;
+        Ok(response["address"]
+            .as_str()
+            .context("Adresse absente de la réponse LND")?
+            .to_string())
+    }
+
+    async fn send_onchain(&self, request: &OnchainSendRequest) -> Result<String> {
+        let mut body = json!({
+            "addr": request.address,
+            "amount": request.amount.unwrap_or(0).to_string(),
+            "send_all": request.send_all,
+        });
+        if let Some(target_conf) = request.target_conf {
+            body["target_conf"] = json!(target_conf);
+        }
+        if let Some(sat_per_vbyte) = request.sat_per_vbyte {
+            body["sat_per_vbyte"] = json!(sat_per_vbyte.to_string());
+        }
+        if !request.utxos.is_empty() {
+            let outpoints = request
+                .utxos
+                .iter()
+                .map(|outpoint| {
+                    let (txid, index) = outpoint
+                        .split_once(':')
+                        .context("UTXO attendu au format txid:index")?;
+                    Ok(json!({ "txid_str": txid, "output_index": index.parse::<u32>()? }))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            body["outpoints"] = json!(outpoints);
+        }
+        let response = self.post("/v1/transactions", body).await?;
+        Ok(response["txid"]
+            .as_str()
+            .context("Transaction absente de la réponse LND")?
+            .to_string())
+    }
+
+    async fn list_onchain_transactions(&self) -> Result<Vec<Transaction>> {
+        let response: LndOnchainTransactions = self.get("/v1/transactions").await?;
+        Ok(response
+            .transactions
+            .into_iter()
+            .map(|t| Transaction {
+                type_: lnd_transaction_type(&t.label),
+                id: t.tx_hash,
+                amount: t.amount.unsigned_abs(),
+                fee: t.total_fees,
+                timestamp: t.time_stamp,
+                status: if t.num_confirmations > 0 {
+                    TransactionStatus::Completed
+                } else {
+                    TransactionStatus::Pending
+                },
+                description: (!t.label.is_empty()).then_some(t.label),
+                outgoing: t.amount < 0,
+                custom_records: BTreeMap::new(),
+            })
+            .collect())
+    }
+
+    #[instrument(skip(self), err)]
+    async fn channel_costs(&self, since: i64) -> Result<Vec<ChannelCost>> {
+        let info: LndGetInfo = self.get("/v1/getinfo").await?;
+        let open: LndChannels = self.get("/v1/channels").await?;
+        let closed: LndClosedChannels = self.get("/v1/channels/closed").await?;
+        let wallet: LndOnchainTransactions = self.get("/v1/transactions").await?;
+        let payments: LndPayments = self
+            .get("/v1/payments?reversed=true&max_payments=10000")
+            .await?;
+
+        // Frais payés par le portefeuille, par transaction
+        let fees: HashMap<String, (u64, i64)> = wallet
+            .transactions
+            .into_iter()
+            .filter(|t| t.total_fees > 0)
+            .map(|t| (t.tx_hash, (t.total_fees, t.time_stamp)))
+            .collect();
+        let mut costs = Vec::new();
+        let mut onchain_cost = |txid: &str, channel_id: u64, remote: &str, kind| {
+            if let Some(&(fee, timestamp)) = fees.get(txid) {
+                if timestamp >= since {
+                    costs.push(ChannelCost {
+                        timestamp,
+                        channel_id: format_scid(channel_id),
+                        remote_pubkey: Some(remote.to_string()),
+                        kind,
+                        amount_msat: fee * 1000,
+                        reference: txid.to_string(),
+                    });
+                }
+            }
+        };
+
+        for c in open.channels.iter().filter(|c| c.initiator) {
+            let funding_txid = c.channel_point.split(':').next().unwrap_or_default();
+            onchain_cost(
+                funding_txid,
+                c.chan_id,
+                &c.remote_pubkey,
+                CostKind::OpeningFee,
+            );
+        }
+        for c in &closed.channels {
+            if c.open_initiator == "INITIATOR_LOCAL" {
+                let funding_txid = c.channel_point.split(':').next().unwrap_or_default();
+                onchain_cost(
+                    funding_txid,
+                    c.chan_id,
+                    &c.remote_pubkey,
+                    CostKind::OpeningFee,
+                );
+            }
+            onchain_cost(
+                &c.closing_tx_hash,
+                c.chan_id,
+                &c.remote_pubkey,
+                CostKind::ClosingFee,
+            );
+        }
+
+        // Un paiement vers notre propre nœud recharge le canal de son dernier saut
+        for p in payments.payments {
+            if p.status != "SUCCEEDED" || (p.creation_date as i64) < since {
+                continue;
+            }
+            let Some(route) = p
+                .htlcs
+                .into_iter()
+                .find(|h| h.status == "SUCCEEDED")
+                .and_then(|h| h.route)
+            else {
+                continue;
+            };
+            let hops = route.hops;
+            match hops.last() {
+                Some(last) if last.pub_key == info.identity_pubkey && hops.len() > 1 => {
+                    costs.push(ChannelCost {
+                        timestamp: p.creation_date as i64,
+                        channel_id: format_scid(last.chan_id),
+                        remote_pubkey: Some(hops[hops.len() - 2].pub_key.clone()),
+                        kind: CostKind::Rebalance,
+                        amount_msat: if p.fee_msat > 0 {
+                            p.fee_msat
+                        } else {
+                            p.fee_sat * 1000
+                        },
+                        reference: p.payment_hash,
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        Ok(costs)
+    }
+
+    async fn open_channel(&self, pubkey: &str, amount: u64) -> Result<ChannelOpenResult> {
+        let pubkey_bytes = hex::decode(pubkey).context("Clé publique invalide")?;
+        let response = self
+            .post(
+                "/v1/channels",
+                json!({
+                    "node_pubkey": BASE64.encode(pubkey_bytes),
+                    "local_funding_amount": amount.to_string(),
+                }),
+            )
+            .await?;
+        Ok(ChannelOpenResult {
+            funding_txid: lnd_txid(&response),
+            output_index: response["output_index"].as_u64().map(|i| i as u32),
+        })
+    }
+
+    async fn open_channels(&self, request: &BatchOpenRequest) -> Result<Vec<ChannelOpenResult>> {
+        let channels = request
+            .channels
+            .iter()
+            .map(open_request)
+            .collect::<Result<Vec<_>>>()?;
+        let mut body = json!({ "channels": channels });
+        if let Some(target_conf) = request.target_conf {
+            body["target_conf"] = json!(target_conf);
+        }
+        if let Some(sat_per_vbyte) = request.sat_per_vbyte {
+            body["sat_per_vbyte"] = json!(sat_per_vbyte.to_string());
+        }
+        let response = self.post("/v1/channels/batch", body).await?;
+        Ok(response["pending_channels"]
+            .as_array()
+            .map(|pending| pending.iter().map(pending_channel).collect())
+            .unwrap_or_default())
+    }
+
+    async fn start_psbt_funding(&self, request: &ChannelRequest) -> Result<PsbtFunding> {
+        let pending_chan_id: [u8; 32] = rand::random();
+        let mut body = open_request(request)?;
+        body["funding_shim"] = json!({
+            "psbt_shim": { "pending_chan_id": BASE64.encode(pending_chan_id) }
+        });
+        let response = self
+            .request(Method::POST, "/v1/channels/stream")
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        // Le flux reste ouvert jusqu'à la finalisation : LND y annonce le canal en attente
+        let mut stream = LndStream::new(response);
+        let update = stream.next().await?;
+        let fund = update
+            .get("psbt_fund")
+            .context("LND n'a pas demandé de financement PSBT")?;
+        let funding_id = hex::encode(pending_chan_id);
+        let funding = PsbtFunding {
+            funding_id: funding_id.clone(),
+            funding_address: fund["funding_address"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            amount: value_as_u64(&fund["funding_amount"]),
+            psbt: fund["psbt"]
+                .as_str()
+                .filter(|psbt| !psbt.is_empty())
+                .map(str::to_string),
+        };
+        self.psbt_fundings
+            .lock()
+            .unwrap()
+            .insert(funding_id, stream);
+        Ok(funding)
+    }
+
+    async fn verify_psbt_funding(&self, funding_id: &str, psbt: &str) -> Result<()> {
+        // Les champs `bytes` de l'API REST sont en base64, comme la PSBT elle-même
+        self.funding_step("psbt_verify", funding_id, json!({ "funded_psbt": psbt }))
+            .await
+    }
+
+    async fn finalize_psbt_funding(
+        &self,
+        funding_id: &str,
+        signed_psbt: &str,
+    ) -> Result<ChannelOpenResult> {
+        let mut stream = self
+            .psbt_fundings
+            .lock()
+            .unwrap()
+            .remove(funding_id)
+            .with_context(|| format!("Financement PSBT inconnu: {}", funding_id))?;
+        self.funding_step(
+            "psbt_finalize",
+            funding_id,
+            json!({ "signed_psbt": signed_psbt }),
+        )
+        .await?;
+        let update = stream.next().await?;
+        let pending = update
+            .get("chan_pending")
+            .context("LND n'a pas confirmé l'ouverture du canal")?;
+        Ok(pending_channel(pending))
+    }
+
+    async fn cancel_psbt_funding(&self, funding_id: &str) -> Result<()> {
+        // Le flux d'ouverture est abandonné même si l'annulation échoue : une nouvelle
+        // tentative ne doit pas retomber sur un financement périmé
+        self.psbt_fundings.lock().unwrap().remove(funding_id);
+        self.funding_step("shim_cancel", funding_id, json!({}))
+            .await
+    }
+
+    async fn close_channel(
+        &self,
+        channel_id: &str,
+        options: &CloseOptions,
+    ) -> Result<ChannelCloseResult> {
+        // Les canaux LND sont identifiés par leur channel point `txid:index`
+        let (txid, index) = channel_id
+            .split_once(':')
+            .context("Identifiant de canal LND attendu au format txid:index")?;
+
+        let mut query = vec![("force", options.force.to_string())];
+        if let Some(target_conf) = options.target_conf {
+            query.push(("target_conf", target_conf.to_string()));
+        }
+        if let Some(sat_per_vbyte) = options.sat_per_vbyte {
+            query.push(("sat_per_vbyte", sat_per_vbyte.to_string()));
+        }
+        if let Some(address) = &options.delivery_address {
+            query.push(("delivery_address", address.clone()));
+        }
+        if let Some(max_fee) = options.max_fee_per_vbyte {
+            query.push(("max_fee_per_vbyte", max_fee.to_string()));
+        }
+
+        // La fermeture est diffusée en flux : on s'arrête à la diffusion de la transaction
+        let response = self
+            .request(Method::DELETE, &format!("/v1/channels/{}/{}", txid, index))
+            .query(&query)
+            .send()
+            .await?
+            .error_for_status()?;
+        let update = LndStream::new(response).next().await?;
+        let closing_txid = update
+            .pointer("/close_pending/txid")
+            .and_then(Value::as_str)
+            .filter(|txid| !txid.is_empty())
+            .with_context(|| {
+                format!(
+                    "Mise à jour inattendue pour la fermeture du canal: {}",
+                    update
+                )
+            })?;
+        Ok(ChannelCloseResult {
+            channel_id: channel_id.to_string(),
+            closing_txid: Some(reversed_txid(&base64_to_hex(closing_txid))),
+            force: options.force,
+            csv_delay: None,
+        })
+    }
+
+    async fn estimate_fee_rate(&self, target_conf: u32) -> Result<u64> {
+        let estimate: LndFeeEstimate = self
+            .get(&format!("/v2/wallet/estimatefee/{}", target_conf))
+            .await?;
+        // 1 vbyte = 4 unités de poids
+        Ok((estimate.sat_per_kw * 4).div_ceil(1000))
+    }
+
+    async fn set_channel_policy(
+        &self,
+        channel_id: &str,
+        _update: &PolicyUpdate,
+        policy: &ChannelPolicy,
+    ) -> Result<()> {
+        let (txid, index) = channel_id
+            .split_once(':')
+            .context("Identifiant de canal LND attendu au format txid:index")?;
+        // LND remet à zéro les champs absents : la politique est envoyée en entier
+        let response: LndPolicyUpdateResponse = serde_json::from_value(
+            self.post(
+                "/v1/chanpolicy",
+                json!({
+                    "chan_point": {
+                        "funding_txid_str": txid,
+                        "output_index": index.parse::<u32>()?,
+                    },
+                    "base_fee_msat": policy.base_fee_msat.to_string(),
+                    "fee_rate_ppm": policy.fee_rate_ppm,
+                    "time_lock_delta": policy.time_lock_delta,
+                    "min_htlc_msat": policy.min_htlc_msat.to_string(),
+                    "min_htlc_msat_specified": true,
+                    "max_htlc_msat": policy.max_htlc_msat.to_string(),
+                    "inbound_fee": {
+                        "base_fee_msat": policy.inbound_base_fee_msat,
+                        "fee_rate_ppm": policy.inbound_fee_rate_ppm,
+                    },
+                }),
+            )
+            .await?,
+        )?;
+        if let Some(failure) = response.failed_updates.first() {
+            bail!(
+                "Politique refusée par LND: {}",
+                failure["update_error"]
+                    .as_str()
+                    .unwrap_or("raison inconnue")
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    /// Certificat auto-signé quelconque : le faux nœud répond en clair, mais le
+    /// client HTTP du backend exige un certificat à épingler
+    const TLS_CERT: &str = "-----BEGIN CERTIFICATE-----
+MIIBgDCCASWgAwIBAgIUblz49I73/KNA9gKRGchGSK40ickwCgYIKoZIzj0EAwIw
+FDESMBAGA1UEAwwJbG9jYWxob3N0MCAXDTI2MTAxODE0MzE1N1oYDzIxMjYwOTI0
+MTQzMTU3WjAUMRIwEAYDVQQDDAlsb2NhbGhvc3QwWTATBgcqhkjOPQIBBggqhkjO
+PQMBBwNCAARmjfB2AJ0gIBNpfFL/9obGm3M52jLJ/MP0S1z9lVOmkbifYyWwEcza
+uY3eHg6X6cWhSjHL7yLVXWyi5+R3QuGJo1MwUTAdBgNVHQ4EFgQU5VCnmcJohfbU
+cfZQOUM6xBo2Uc4wHwYDVR0jBBgwFoAU5VCnmcJohfbUcfZQOUM6xBo2Uc4wDwYD
+VR0TAQH/BAUwAwEB/zAKBggqhkjOPQQDAgNJADBGAiEAu3PXbU4pgYuVIg02c98I
+gmTo62ro5DZMRG5ULt4QyJACIQC1SJzg8dKeP9wdrIm7iZQILGmZrZgQ1Ng3mBAH
+CTSApQ==
+-----END CERTIFICATE-----
+";
+    const MACAROON: &str = "0201036c6e64";
+    const CHANNEL_POINT: &str =
+        "a1b2c3d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8f90:1";
+
+    /// Faux `lnd` REST : renvoie `body` à chaque requête, en deux morceaux pour
+    /// éprouver la lecture des flux, et consigne `METHODE cible`
+    struct StandIn {
+        base: String,
+        requests: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl StandIn {
+        async fn start(body: &str) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let base = format!("http://{}", listener.local_addr().unwrap());
+            let body: Arc<str> = body.into();
+            let requests = Arc::new(Mutex::new(Vec::new()));
+            let log = requests.clone();
+            tokio::spawn(async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    tokio::spawn(Self::serve(stream, body.clone(), log.clone()));
+                }
+            });
+            Self { base, requests }
+        }
+
+        async fn serve(mut stream: TcpStream, body: Arc<str>, log: Arc<Mutex<Vec<String>>>) {
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 4096];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                let read = stream.read(&mut buffer).await.unwrap();
+                request.extend_from_slice(&buffer[..read]);
+            }
+            let head = String::from_utf8_lossy(&request).to_string();
+            let mut request_line = head.split_whitespace();
+            let method = request_line.next().unwrap_or_default();
+            let target = request_line.next().unwrap_or_default();
+            log.lock().unwrap().push(format!("{} {}", method, target));
+
+            let head = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+                 Connection: close\r\n\r\n",
+                body.len()
+            );
+            let (first, rest) = body.split_at(body.len() / 2);
+            for part in [head.as_str(), first, rest] {
+                stream.write_all(part.as_bytes()).await.unwrap();
+                stream.flush().await.unwrap();
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        }
+
+        fn backend(&self) -> LndRestBackend {
+            let credentials = LndCredentials::from_values(MACAROON, TLS_CERT).unwrap();
+            LndRestBackend::new(self.base.clone(), &credentials).unwrap()
+        }
+
+        fn requests(&self) -> Vec<String> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    fn close_options() -> CloseOptions {
+        CloseOptions {
+            sat_per_vbyte: Some(5),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn closes_channel_with_the_pending_closing_txid() {
+        // LND transmet le txid en octets little-endian, encodés en base64
+        let txid = BASE64.encode((0u8..32).collect::<Vec<_>>());
+        let lnd = StandIn::start(&format!(
+            "{}\n{}\n",
+            json!({ "result": { "close_pending": { "txid": txid, "output_index": 0 } } }),
+            json!({ "result": { "chan_close": { "success": true } } })
+        ))
+        .await;
+
+        let result = lnd
+            .backend()
+            .close_channel(CHANNEL_POINT, &close_options())
+            .await
+            .unwrap();
+
+        let expected: Vec<u8> = (0u8..32).rev().collect();
+        assert_eq!(result.closing_txid, Some(hex::encode(expected)));
+        assert_eq!(result.channel_id, CHANNEL_POINT);
+        assert!(!result.force);
+        assert_eq!(
+            lnd.requests(),
+            [format!(
+                "DELETE /v1/channels/{}?force=false&sat_per_vbyte=5",
+                CHANNEL_POINT.replace(':', "/")
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn fails_close_on_error_line() {
+        let lnd = StandIn::start(&format!(
+            "{}\n",
+            json!({ "error": { "code": 2, "message": "channel is locked by a pending HTLC" } })
+        ))
+        .await;
+
+        let error = lnd
+            .backend()
+            .close_channel(CHANNEL_POINT, &close_options())
+            .await
+            .unwrap_err();
+
+        assert!(
+            error
+                .to_string()
+                .contains("channel is locked by a pending HTLC"),
+            "{:#}",
+            error
+        );
+    }
+
+    #[tokio::test]
+    async fn fails_close_without_pending_txid() {
+        let lnd = StandIn::start(&format!(
+            "{}\n",
+            json!({ "result": { "close_instant": {} } })
+        ))
+        .await;
+
+        let error = lnd
+            .backend()
+            .close_channel(CHANNEL_POINT, &close_options())
+            .await
+            .unwrap_err();
+
+        assert!(
+            error.to_string().contains("Mise à jour inattendue"),
+            "{:#}",
+            error
+        );
+    }
+}
@@ -0,0 +1,443 @@
+pub mod cln;
+pub mod cln_rpc;
+pub mod eclair;
+pub mod lnd_auth;
+pub mod lnd_grpc;
+pub mod lnd_rest;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::models::lightning::{
+    BatchOpenRequest, Channel, ChannelCloseResult, ChannelOpenResult, ChannelPolicy,
+    ChannelRequest, CloseOptions, ForwardingEvent, Invoice, NodeCapabilities, NodeInfo, Offer,
+    PaymentOptions, PaymentResult, PendingChannels, PolicyUpdate, PsbtFunding, RouteQuote,
+    Transaction, TransactionType,
+};
+use crate::models::onchain::{AddressType, OnchainSendRequest, Utxo, WalletBalance};
+use crate::models::pnl::ChannelCost;
+use crate::services::events::{EventPoller, EventSink, EVENT_POLL_INTERVAL};
+
+pub use cln::ClnRestBackend;
+pub use cln_rpc::ClnRpcBackend;
+pub use eclair::EclairBackend;
+pub use lnd_auth::LndCredentials;
+pub use lnd_grpc::LndGrpcBackend;
+pub use lnd_rest::LndRestBackend;
+
+/// Implémentations de nœud disponibles
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BackendKind {
+    Lnd,
+    LndGrpc,
+    Cln,
+    ClnRpc,
+    Eclair,
+}
+
+impl fmt::Display for BackendKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BackendKind::Lnd => write!(f, "lnd"),
+            BackendKind::LndGrpc => write!(f, "lnd-grpc"),
+            BackendKind::Cln => write!(f, "cln"),
+            BackendKind::ClnRpc => write!(f, "cln-rpc"),
+            BackendKind::Eclair => write!(f, "eclair"),
+        }
+    }
+}
+
+impl FromStr for BackendKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "lnd" => Ok(BackendKind::Lnd),
+            "lnd-grpc" => Ok(BackendKind::LndGrpc),
+            "cln" | "clightning" | "core-lightning" => Ok(BackendKind::Cln),
+            "cln-rpc" => Ok(BackendKind::ClnRpc),
+            "eclair" => Ok(BackendKind::Eclair),
+            other => Err(anyhow!("Backend Lightning inconnu: {}", other)),
+        }
+    }
+}
+
+/// Opération que le backend du nœud ne sait pas réaliser.
+///
+/// Les handlers la retrouvent par `downcast_ref` pour répondre 501 plutôt que 500.
+#[derive(Debug)]
+pub struct Unsupported {
+    pub backend: BackendKind,
+    pub operation: &'static str,
+}
+
+impl fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} n'est pas disponible avec le backend {}",
+            self.operation, self.backend
+        )
+    }
+}
+
+impl std::error::Error for Unsupported {}
+
+/// Accès à un nœud Lightning, quelle que soit son implémentation.
+///
+/// Chaque backend traduit les réponses natives de son API vers les types
+/// de `models::lightning`, de sorte que les handlers n'ont pas à savoir
+/// quel nœud se trouve derrière le `LightningService`.
+#[async_trait]
+pub trait NodeBackend: Send + Sync {
+    fn kind(&self) -> BackendKind;
+
+    /// Fonctionnalités optionnelles ; aucune par défaut
+    fn capabilities(&self) -> NodeCapabilities {
+        NodeCapabilities::default()
+    }
+
+    async fn get_node_info(&self) -> Result<NodeInfo>;
+
+    async fn list_channels(&self) -> Result<Vec<Channel>>;
+
+    /// Canaux en cours d'ouverture ou de fermeture, avec la hauteur de bloc courante
+    async fn list_pending_channels(&self) -> Result<PendingChannels>;
+
+    async fn list_transactions(&self) -> Result<Vec<Transaction>>;
+
+    /// Paie une facture BOLT11 en respectant les contraintes de `options`.
+    ///
+    /// Une contrainte que le backend ne sait pas appliquer est refusée
+    /// (`Unsupported`) plutôt qu'ignorée.
+    async fn send_payment(
+        &self,
+        payment_request: &str,
+        options: &PaymentOptions,
+    ) -> Result<PaymentResult>;
+
+    /// Paie une facture émise par notre propre nœud, pour un rééquilibrage circulaire :
+    /// `options` impose le canal de sortie et le dernier saut, donc le canal d'entrée.
+    async fn send_circular_payment(
+        &self,
+        _payment_request: &str,
+        _options: &PaymentOptions,
+    ) -> Result<PaymentResult> {
+        Err(Unsupported {
+            backend: self.kind(),
+            operation: "Le rééquilibrage circulaire",
+        }
+        .into())
+    }
+
+    /// Paiement spontané de `amount` sats, sans facture.
+    ///
+    /// `custom_records` ne contient que les enregistrements de l'appelant :
+    /// la preimage est générée et ajoutée par le backend ou par le nœud.
+    async fn send_keysend(
+        &self,
+        pubkey: &str,
+        amount: u64,
+        custom_records: &BTreeMap<u64, Vec<u8>>,
+    ) -> Result<PaymentResult>;
+
+    /// `expiry` en secondes ; `None` laisse le nœud appliquer sa valeur par défaut
+    async fn create_invoice(
+        &self,
+        amount: u64,
+        description: &str,
+        expiry: Option<u64>,
+    ) -> Result<Invoice>;
+
+    async fn open_channel(&self, pubkey: &str, amount: u64) -> Result<ChannelOpenResult>;
+
+    /// Ouvre tous les canaux de `request` avec une seule transaction de financement
+    async fn open_channels(&self, _request: &BatchOpenRequest) -> Result<Vec<ChannelOpenResult>> {
+        Err(Unsupported {
+            backend: self.kind(),
+            operation: "L'ouverture groupée de canaux",
+        }
+        .into())
+    }
+
+    /// Démarre l'ouverture de `request` financée par une PSBT externe
+    async fn start_psbt_funding(&self, _request: &ChannelRequest) -> Result<PsbtFunding> {
+        Err(Unsupported {
+            backend: self.kind(),
+            operation: "Le financement de canal par PSBT",
+        }
+        .into())
+    }
+
+    /// Vérifie que `psbt` finance bien la sortie attendue, avant de la signer ou de la diffuser
+    async fn verify_psbt_funding(&self, _funding_id: &str, _psbt: &str) -> Result<()> {
+        Err(Unsupported {
+            backend: self.kind(),
+            operation: "Le financement de canal par PSBT",
+        }
+        .into())
+    }
+
+    /// Diffuse la transaction de financement signée et termine l'ouverture
+    async fn finalize_psbt_funding(
+        &self,
+        _funding_id: &str,
+        _signed_psbt: &str,
+    ) -> Result<ChannelOpenResult> {
+        Err(Unsupported {
+            backend: self.kind(),
+            operation: "Le financement de canal par PSBT",
+        }
+        .into())
+    }
+
+    /// Abandonne un financement par PSBT qui n'a pas été diffusé
+    async fn cancel_psbt_funding(&self, _funding_id: &str) -> Result<()> {
+        Err(Unsupported {
+            backend: self.kind(),
+            operation: "Le financement de canal par PSBT",
+        }
+        .into())
+    }
+
+    /// Ferme `channel_id` ; les options sont déjà validées (une fermeture
+    /// forcée ne porte ni frais ni adresse de réception).
+    async fn close_channel(
+        &self,
+        channel_id: &str,
+        options: &CloseOptions,
+    ) -> Result<ChannelCloseResult>;
+
+    /// Taux de frais on-chain estimé par le nœud pour une confirmation en `target_conf` blocs, en sat/vB
+    async fn estimate_fee_rate(&self, _target_conf: u32) -> Result<u64> {
+        Err(Unsupported {
+            backend: self.kind(),
+            operation: "L'estimation des frais on-chain",
+        }
+        .into())
+    }
+
+    /// Applique `policy`, la politique locale complète de `channel_id`.
+    ///
+    /// `update` indique les champs demandés, pour refuser ceux que le backend
+    /// ne sait pas modifier plutôt que de les ignorer.
+    async fn set_channel_policy(
+        &self,
+        channel_id: &str,
+        update: &PolicyUpdate,
+        policy: &ChannelPolicy,
+    ) -> Result<()>;
+
+    /// Soldes du portefeuille on-chain
+    async fn wallet_balance(&self) -> Result<WalletBalance>;
+
+    /// Sorties non dépensées du portefeuille, confirmées ou non
+    async fn list_utxos(&self) -> Result<Vec<Utxo>> {
+        Err(Unsupported {
+            backend: self.kind(),
+            operation: "La liste des UTXO",
+        }
+        .into())
+    }
+
+    async fn new_address(&self, address_type: AddressType) -> Result<String>;
+
+    /// Envoie des fonds on-chain et renvoie le txid de la transaction diffusée.
+    ///
+    /// Les options de frais et de coin control que le backend ne sait pas
+    /// appliquer sont refusées (`Unsupported`).
+    async fn send_onchain(&self, request: &OnchainSendRequest) -> Result<String>;
+
+    /// Transactions du portefeuille on-chain, avec les ouvertures et fermetures de canal
+    async fn list_onchain_transactions(&self) -> Result<Vec<Transaction>>;
+
+    /// Coûts imputables aux canaux depuis `since`, canaux fermés compris :
+    /// frais on-chain d'ouverture et de fermeture, rééquilibrages, baux
+    async fn channel_costs(&self, _since: i64) -> Result<Vec<ChannelCost>> {
+        Err(Unsupported {
+            backend: self.kind(),
+            operation: "Le calcul des coûts des canaux",
+        }
+        .into())
+    }
+
+    /// Crée une offre BOLT12 réutilisable ; `amount` en sats, `None` pour un montant libre
+    async fn create_offer(&self, _amount: Option<u64>, _description: &str) -> Result<Offer> {
+        Err(Unsupported {
+            backend: self.kind(),
+            operation: "La création d'offres BOLT12",
+        }
+        .into())
+    }
+
+    /// Paie une offre BOLT12 ; `amount` en sats, requis si l'offre n'en fixe pas
+    async fn pay_offer(
+        &self,
+        _offer: &str,
+        _amount: Option<u64>,
+        _payer_note: Option<&str>,
+    ) -> Result<PaymentResult> {
+        Err(Unsupported {
+            backend: self.kind(),
+            operation: "Le paiement d'offres BOLT12",
+        }
+        .into())
+    }
+
+    /// Routes candidates vers `destination` pour `amount` sats, avec leurs frais
+    async fn query_routes(&self, destination: &str, amount: u64) -> Result<Vec<RouteQuote>>;
+
+    /// Lit une page de l'historique de routage à partir de `offset`.
+    async fn forwarding_history(
+        &self,
+        _start_time: i64,
+        _offset: u64,
+        _limit: u32,
+    ) -> Result<ForwardingPage> {
+        Err(Unsupported {
+            backend: self.kind(),
+            operation: "L'historique de routage",
+        }
+        .into())
+    }
+
+    /// Diffuse les événements du nœud dans `sink` jusqu'à la première erreur.
+    ///
+    /// Par défaut les événements sont déduits d'une scrutation périodique ;
+    /// les backends disposant d'un flux natif remplacent cette méthode.
+    async fn subscribe_events(&self, sink: &EventSink) -> Result<()> {
+        let mut poller = EventPoller::new();
+        loop {
+            poller.poll(self, sink).await?;
+            tokio::time::sleep(EVENT_POLL_INTERVAL).await;
+        }
+    }
+}
+
+/// Page d'événements de routage, avec la position à reprendre au prochain appel.
+///
+/// Quand `next_offset` n'avance plus, l'historique a été entièrement parcouru.
+#[derive(Debug)]
+pub struct ForwardingPage {
+    pub events: Vec<ForwardingEvent>,
+    pub next_offset: u64,
+}
+
+/// Formate un short channel id numérique (LND) en `bloc x tx x sortie`
+pub(crate) fn format_scid(scid: u64) -> String {
+    format!("{}x{}x{}", scid >> 40, (scid >> 16) & 0xFF_FFFF, scid & 0xFFFF)
+}
+
+/// Lit un short channel id `bloc x tx x sortie` sous sa forme numérique (LND)
+pub(crate) fn parse_scid(scid: &str) -> Option<u64> {
+    let mut parts = scid.split('x').map(|part| part.parse::<u64>().ok());
+    let (block, tx, output) = (parts.next()??, parts.next()??, parts.next()??);
+    if parts.next().is_some() || block >= 1 << 24 || tx >= 1 << 24 || output >= 1 << 16 {
+        return None;
+    }
+    Some(block << 40 | tx << 16 | output)
+}
+
+/// Refuse la première contrainte de paiement renseignée que le backend ne sait pas appliquer.
+///
+/// `options` associe à chaque contrainte le fait qu'elle soit demandée et son libellé.
+pub(crate) fn reject_options(backend: BackendKind, options: &[(bool, &'static str)]) -> Result<()> {
+    match options.iter().find(|(requested, _)| *requested) {
+        Some(&(_, operation)) => Err(Unsupported { backend, operation }.into()),
+        None => Ok(()),
+    }
+}
+
+/// Désérialise un entier qui peut être encodé en nombre ou en chaîne
+/// (LND encode les int64 en chaînes dans son API REST).
+pub(crate) fn u64_from_any<'de, D>(deserializer: D) -> std::result::Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Value::deserialize(deserializer)? {
+        Value::Number(n) => n
+            .as_u64()
+            .ok_or_else(|| serde::de::Error::custom("entier non signé attendu")),
+        Value::String(s) if s.is_empty() => Ok(0),
+        Value::String(s) => s.parse().map_err(serde::de::Error::custom),
+        Value::Null => Ok(0),
+        other => Err(serde::de::Error::custom(format!(
+            "entier attendu, reçu {}",
+            other
+        ))),
+    }
+}
+
+/// Variante signée de `u64_from_any`, pour les montants on-chain de LND
+pub(crate) fn i64_from_any<'de, D>(deserializer: D) -> std::result::Result<i64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Value::deserialize(deserializer)? {
+        Value::Number(n) => n
+            .as_i64()
+            .ok_or_else(|| serde::de::Error::custom("entier attendu")),
+        Value::String(s) if s.is_empty() => Ok(0),
+        Value::String(s) => s.parse().map_err(serde::de::Error::custom),
+        Value::Null => Ok(0),
+        other => Err(serde::de::Error::custom(format!(
+            "entier attendu, reçu {}",
+            other
+        ))),
+    }
+}
+
+/// Lit un entier dans une valeur JSON, qu'il soit encodé en nombre ou en chaîne
+pub(crate) fn value_as_u64(value: &Value) -> u64 {
+    match value {
+        Value::Number(n) => n.as_u64().unwrap_or(0),
+        Value::String(s) => s.parse().unwrap_or(0),
+        _ => 0,
+    }
+}
+
+/// Délai d'expiration appliqué aux factures quand la requête n'en précise pas
+pub(crate) const DEFAULT_INVOICE_EXPIRY: u64 = 3600;
+
+/// Délai accordé au nœud pour aboutir un paiement quand la requête n'en précise pas
+pub(crate) const DEFAULT_PAYMENT_TIMEOUT: u32 = 60;
+
+/// Objectif de confirmation des fermetures de canal quand la requête n'en précise pas
+pub(crate) const DEFAULT_CLOSE_TARGET_CONF: u32 = 6;
+
+/// Plafond de frais des backends qui en exigent un (LND ne tente sinon que
+/// les routes sans frais) : celui de lncli, 100 % sous 1 000 sats et 5 % au-delà.
+pub(crate) fn fee_limit_msat(options: &PaymentOptions, amount_msat: u64) -> u64 {
+    options.fee_limit_msat(amount_msat).unwrap_or(if amount_msat < 1_000_000 {
+        amount_msat
+    } else {
+        amount_msat / 20
+    })
+}
+
+/// Type d'une transaction on-chain de LND d'après l'étiquette que le nœud lui donne
+/// (`0:openchannel:shortchanid-...`, `0:closechannel:...`)
+pub(crate) fn lnd_transaction_type(label: &str) -> TransactionType {
+    if label.contains(":openchannel") {
+        TransactionType::ChannelOpen
+    } else if label.contains(":closechannel") {
+        TransactionType::ChannelClose
+    } else {
+        TransactionType::Onchain
+    }
+}
+
+/// Convertit un champ `bytes` encodé en base64 (API REST de LND) en hexadécimal
+pub(crate) fn base64_to_hex(value: &str) -> String {
+    use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+    BASE64
+        .decode(value)
+        .map(hex::encode)
+        .unwrap_or_else(|_| value.to_string())
+}
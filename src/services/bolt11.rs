@@ -0,0 +1,630 @@
+use anyhow::{bail, Context, Result};
+use secp256k1::ecdsa::{RecoverableSignature, RecoveryId};
+use secp256k1::{Message, PublicKey, Secp256k1};
+use sha2::{Digest, Sha256};
+
+use crate::models::lightning::{DecodedInvoice, Network, RouteHintHop};
+use crate::services::backends::format_scid;
+
+pub(crate) const CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const CHECKSUM_WORDS: usize = 6;
+const TIMESTAMP_WORDS: usize = 7;
+/// Signature compacte de 64 octets suivie de l'identifiant de récupération
+const SIGNATURE_WORDS: usize = 104;
+/// Expiration implicite quand la facture ne porte pas de champ `x`
+const DEFAULT_EXPIRY: u64 = 3600;
+/// `min_final_cltv_expiry` implicite quand la facture ne porte pas de champ `c`
+const DEFAULT_MIN_FINAL_CLTV_EXPIRY: u64 = 18;
+/// Taille d'un saut dans un champ `r` : pubkey, scid, frais de base, frais proportionnels, delta CLTV
+const ROUTE_HINT_HOP_LEN: usize = 33 + 8 + 4 + 4 + 2;
+/// Bits de fonctionnalité connus : var_onion, payment_secret, basic_mpp,
+/// route_blinding et payment_metadata
+const KNOWN_FEATURES: [u16; 10] = [8, 9, 14, 15, 16, 17, 24, 25, 48, 49];
+
+// Types des champs étiquetés, par valeur du caractère bech32
+const TAG_PAYMENT_HASH: u8 = 1; // p
+const TAG_ROUTE_HINT: u8 = 3; // r
+const TAG_FEATURES: u8 = 5; // 9
+const TAG_EXPIRY: u8 = 6; // x
+const TAG_DESCRIPTION: u8 = 13; // d
+const TAG_PAYMENT_SECRET: u8 = 16; // s
+const TAG_PAYEE: u8 = 19; // n
+const TAG_DESCRIPTION_HASH: u8 = 23; // h
+const TAG_MIN_FINAL_CLTV_EXPIRY: u8 = 24; // c
+
+fn polymod(values: &[u8]) -> u32 {
+    const GENERATORS: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+    let mut checksum = 1u32;
+    for value in values {
+        let top = checksum >> 25;
+        checksum = ((checksum & 0x1ff_ffff) << 5) ^ *value as u32;
+        for (i, generator) in GENERATORS.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+    }
+    checksum
+}
+
+fn hrp_expand(hrp: &str) -> Vec<u8> {
+    let mut expanded: Vec<u8> = hrp.bytes().map(|b| b >> 5).collect();
+    expanded.push(0);
+    expanded.extend(hrp.bytes().map(|b| b & 31));
+    expanded
+}
+
+/// Décode une chaîne bech32 en préfixe et mots de 5 bits, somme de contrôle retirée.
+///
+/// Contrairement à BIP-173, aucune longueur maximale n'est imposée : une facture
+/// ou un LNURL dépasse largement 90 caractères. `what` nomme la chaîne dans les erreurs.
+pub(crate) fn bech32_decode(value: &str, what: &str) -> Result<(String, Vec<u8>)> {
+    if !value.is_ascii() {
+        bail!("{} invalide: caractères non ASCII", what);
+    }
+    if value.chars().any(|c| c.is_ascii_lowercase())
+        && value.chars().any(|c| c.is_ascii_uppercase())
+    {
+        bail!("{} invalide: casse mixte", what);
+    }
+    let value = value.to_lowercase();
+    let separator = value
+        .rfind('1')
+        .with_context(|| format!("{} invalide: séparateur absent", what))?;
+    if separator == 0 || separator + 1 + CHECKSUM_WORDS > value.len() {
+        bail!("{} invalide: chaîne trop courte", what);
+    }
+
+    let (hrp, data) = (&value[..separator], &value[separator + 1..]);
+    let words = data
+        .chars()
+        .map(|c| CHARSET.find(c).map(|i| i as u8))
+        .collect::<Option<Vec<u8>>>()
+        .with_context(|| format!("{} invalide: caractère hors de l'alphabet bech32", what))?;
+
+    let mut checked = hrp_expand(hrp);
+    checked.extend(&words);
+    if polymod(&checked) != 1 {
+        bail!("{} invalide: somme de contrôle incorrecte", what);
+    }
+    Ok((
+        hrp.to_string(),
+        words[..words.len() - CHECKSUM_WORDS].to_vec(),
+    ))
+}
+
+/// Regroupe des mots de 5 bits en octets ; `pad` complète le dernier octet par des zéros
+pub(crate) fn words_to_bytes(words: &[u8], pad: bool) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(words.len() * 5 / 8 + 1);
+    let mut accumulator = 0u32;
+    let mut bits = 0;
+    for word in words {
+        accumulator = (accumulator << 5) | *word as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((accumulator >> bits) as u8);
+            accumulator &= (1 << bits) - 1;
+        }
+    }
+    if pad && bits > 0 {
+        bytes.push((accumulator << (8 - bits)) as u8);
+    }
+    bytes
+}
+
+fn words_to_u64(words: &[u8]) -> Result<u64> {
+    if words.len() > 12 {
+        bail!("Facture BOLT11 invalide: entier trop grand");
+    }
+    Ok(words.iter().fold(0, |acc, word| (acc << 5) | *word as u64))
+}
+
+pub(crate) fn bytes_to_u64(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0, |acc, byte| (acc << 8) | *byte as u64)
+}
+
+/// Lit le réseau et le montant dans le préfixe, par exemple `lnbc2500u`
+fn parse_hrp(hrp: &str) -> Result<(Network, Option<u64>)> {
+    let rest = hrp
+        .strip_prefix("ln")
+        .context("Facture BOLT11 invalide: préfixe « ln » absent")?;
+    let split = rest
+        .find(|c: char| c.is_ascii_digit())
+        .unwrap_or(rest.len());
+    let (currency, amount) = rest.split_at(split);
+
+    let network = match currency {
+        "bc" => Network::Bitcoin,
+        "tb" => Network::Testnet,
+        "tbs" => Network::Signet,
+        "bcrt" => Network::Regtest,
+        "sb" => Network::Simnet,
+        other => bail!("Facture BOLT11 invalide: réseau inconnu « {} »", other),
+    };
+    let amount_msat = if amount.is_empty() {
+        None
+    } else {
+        Some(parse_amount(amount)?)
+    };
+    Ok((network, amount_msat))
+}
+
+fn parse_amount(amount: &str) -> Result<u64> {
+    let (digits, multiplier) = match amount.chars().last() {
+        Some(c) if c.is_ascii_alphabetic() => (&amount[..amount.len() - 1], Some(c)),
+        _ => (amount, None),
+    };
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        bail!("Facture BOLT11 invalide: montant « {} » illisible", amount);
+    }
+    let value: u64 = digits
+        .parse()
+        .context("Facture BOLT11 invalide: montant hors limites")?;
+
+    let amount_msat = match multiplier {
+        None => value.checked_mul(100_000_000_000),
+        Some('m') => value.checked_mul(100_000_000),
+        Some('u') => value.checked_mul(100_000),
+        Some('n') => value.checked_mul(100),
+        Some('p') => {
+            if !value.is_multiple_of(10) {
+                bail!("Facture BOLT11 invalide: montant plus précis que le millisatoshi");
+            }
+            Some(value / 10)
+        }
+        Some(other) => bail!(
+            "Facture BOLT11 invalide: multiplicateur « {} » inconnu",
+            other
+        ),
+    };
+    amount_msat.context("Facture BOLT11 invalide: montant hors limites")
+}
+
+fn parse_route_hint(bytes: &[u8]) -> Result<Vec<RouteHintHop>> {
+    if bytes.is_empty() || !bytes.len().is_multiple_of(ROUTE_HINT_HOP_LEN) {
+        bail!("Facture BOLT11 invalide: indication de route mal formée");
+    }
+    Ok(bytes
+        .chunks(ROUTE_HINT_HOP_LEN)
+        .map(|hop| RouteHintHop {
+            pubkey: hex::encode(&hop[..33]),
+            short_channel_id: format_scid(bytes_to_u64(&hop[33..41])),
+            fee_base_msat: bytes_to_u64(&hop[41..45]) as u32,
+            fee_proportional_millionths: bytes_to_u64(&hop[45..49]) as u32,
+            cltv_expiry_delta: bytes_to_u64(&hop[49..51]) as u16,
+        })
+        .collect())
+}
+
+/// Le champ `9` est un grand entier dont le bit `n` signale la fonctionnalité `n`
+fn parse_features(words: &[u8]) -> Vec<u16> {
+    let mut features = Vec::new();
+    for (position, word) in words.iter().rev().enumerate() {
+        for bit in 0..5 {
+            if (word >> bit) & 1 == 1 {
+                features.push((position * 5 + bit) as u16);
+            }
+        }
+    }
+    features
+}
+
+/// Retire le préfixe `lightning:` d'une URI de paiement
+pub fn strip_scheme(invoice: &str) -> &str {
+    let invoice = invoice.trim();
+    invoice
+        .get(..10)
+        .filter(|scheme| scheme.eq_ignore_ascii_case("lightning:"))
+        .map_or(invoice, |_| &invoice[10..])
+}
+
+/// Décode une facture BOLT11 et vérifie sa signature.
+///
+/// Le préfixe `lightning:` des URI est accepté. Quand la facture ne porte pas
+/// de champ `n`, la clé publique du destinataire est retrouvée depuis la signature.
+pub fn decode(invoice: &str) -> Result<DecodedInvoice> {
+    let (hrp, words) = bech32_decode(strip_scheme(invoice), "Facture BOLT11")?;
+    let (network, amount_msat) = parse_hrp(&hrp)?;
+    if words.len() < TIMESTAMP_WORDS + SIGNATURE_WORDS {
+        bail!("Facture BOLT11 invalide: données trop courtes");
+    }
+    let (data, signature) = words.split_at(words.len() - SIGNATURE_WORDS);
+
+    let mut decoded = DecodedInvoice {
+        network,
+        amount_msat,
+        timestamp: words_to_u64(&data[..TIMESTAMP_WORDS])? as i64,
+        expiry: DEFAULT_EXPIRY,
+        payment_hash: String::new(),
+        payment_secret: None,
+        payee: String::new(),
+        description: None,
+        description_hash: None,
+        min_final_cltv_expiry: DEFAULT_MIN_FINAL_CLTV_EXPIRY,
+        route_hints: Vec::new(),
+        features: Vec::new(),
+    };
+    let mut explicit_payee = None;
+
+    let mut fields = &data[TIMESTAMP_WORDS..];
+    while !fields.is_empty() {
+        if fields.len() < 3 {
+            bail!("Facture BOLT11 invalide: champ tronqué");
+        }
+        let tag = fields[0];
+        let len = ((fields[1] as usize) << 5) | fields[2] as usize;
+        let value = fields
+            .get(3..3 + len)
+            .context("Facture BOLT11 invalide: champ tronqué")?;
+        fields = &fields[3 + len..];
+
+        // Les champs de longueur inattendue et les types inconnus sont ignorés (BOLT11)
+        match tag {
+            TAG_PAYMENT_HASH if len == 52 => {
+                decoded.payment_hash = hex::encode(words_to_bytes(value, false));
+            }
+            TAG_PAYMENT_SECRET if len == 52 => {
+                decoded.payment_secret = Some(hex::encode(words_to_bytes(value, false)));
+            }
+            TAG_DESCRIPTION_HASH if len == 52 => {
+                decoded.description_hash = Some(hex::encode(words_to_bytes(value, false)));
+            }
+            TAG_PAYEE if len == 53 => {
+                explicit_payee = Some(
+                    PublicKey::from_slice(&words_to_bytes(value, false))
+                        .context("Facture BOLT11 invalide: clé du destinataire incorrecte")?,
+                );
+            }
+            TAG_DESCRIPTION => {
+                decoded.description = Some(
+                    String::from_utf8(words_to_bytes(value, false))
+                        .context("Facture BOLT11 invalide: description non UTF-8")?,
+                );
+            }
+            TAG_EXPIRY => decoded.expiry = words_to_u64(value)?,
+            TAG_MIN_FINAL_CLTV_EXPIRY => decoded.min_final_cltv_expiry = words_to_u64(value)?,
+            TAG_ROUTE_HINT => decoded
+                .route_hints
+                .push(parse_route_hint(&words_to_bytes(value, false))?),
+            TAG_FEATURES => decoded.features = parse_features(value),
+            _ => {}
+        }
+    }
+
+    if decoded.payment_hash.is_empty() {
+        bail!("Facture BOLT11 invalide: hash de paiement absent");
+    }
+    if decoded.description.is_none() && decoded.description_hash.is_none() {
+        bail!("Facture BOLT11 invalide: ni description ni hash de description");
+    }
+    if let Some(feature) = decoded
+        .features
+        .iter()
+        .find(|f| *f % 2 == 0 && !KNOWN_FEATURES.contains(f))
+    {
+        bail!(
+            "Facture BOLT11 invalide: fonctionnalité requise {} inconnue",
+            feature
+        );
+    }
+
+    // La signature porte sur le préfixe et les données, complétées à l'octet
+    let mut signed = hrp.as_bytes().to_vec();
+    signed.extend(words_to_bytes(data, true));
+    let message = Message::from_digest_slice(&Sha256::digest(&signed))?;
+
+    let signature = words_to_bytes(signature, false);
+    let recovery_id = RecoveryId::from_i32(signature[64] as i32)
+        .context("Facture BOLT11 invalide: identifiant de récupération incorrect")?;
+    let signature = RecoverableSignature::from_compact(&signature[..64], recovery_id)
+        .context("Facture BOLT11 invalide: signature mal formée")?;
+    let payee = Secp256k1::verification_only()
+        .recover_ecdsa(&message, &signature)
+        .context("Facture BOLT11 invalide: signature irrécupérable")?;
+    if explicit_payee.is_some_and(|explicit| explicit != payee) {
+        bail!("Facture BOLT11 invalide: signature d'un autre nœud que le destinataire");
+    }
+    decoded.payee = hex::encode(payee.serialize());
+
+    Ok(decoded)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use secp256k1::SecretKey;
+
+    /// Clé privée des exemples de BOLT11, celle du nœud
+    /// 03e7156ae33b0a208d0744199163177e909e80176e55d97a2f221ede0f934dd9ad
+    const SPEC_PRIVATE_KEY: &str =
+        "e126f68f7eafcc8b74f54d269fe206be715000f94dac067d1c04a8ca3b2db734";
+    const SPEC_PAYEE: &str = "03e7156ae33b0a208d0744199163177e909e80176e55d97a2f221ede0f934dd9ad";
+    const SPEC_TIMESTAMP: u64 = 1_496_314_658;
+    const SPEC_PAYMENT_HASH: &str =
+        "0001020304050607080900010203040506070809000102030405060708090102";
+    const SPEC_PAYMENT_SECRET: [u8; 32] = [0x11; 32];
+
+    fn bytes_to_words(bytes: &[u8]) -> Vec<u8> {
+        let mut words = Vec::with_capacity(bytes.len() * 8 / 5 + 1);
+        let mut accumulator = 0u32;
+        let mut bits = 0;
+        for byte in bytes {
+            accumulator = (accumulator << 8) | *byte as u32;
+            bits += 8;
+            while bits >= 5 {
+                bits -= 5;
+                words.push(((accumulator >> bits) & 31) as u8);
+            }
+            accumulator &= (1 << bits) - 1;
+        }
+        if bits > 0 {
+            words.push(((accumulator << (5 - bits)) & 31) as u8);
+        }
+        words
+    }
+
+    fn u64_to_words(value: u64, len: usize) -> Vec<u8> {
+        (0..len).rev().map(|i| ((value >> (5 * i)) & 31) as u8).collect()
+    }
+
+    /// Champ étiqueté dont la valeur est faite d'octets
+    pub(crate) fn field(tag: u8, bytes: &[u8]) -> (u8, Vec<u8>) {
+        (tag, bytes_to_words(bytes))
+    }
+
+    /// Champ étiqueté dont la valeur est un entier, sur le moins de mots possible
+    pub(crate) fn int_field(tag: u8, value: u64) -> (u8, Vec<u8>) {
+        let len = (64 - value.leading_zeros() as usize).div_ceil(5).max(1);
+        (tag, u64_to_words(value, len))
+    }
+
+    /// Champ `9` signalant les fonctionnalités `bits`
+    fn features_field(bits: &[u16]) -> (u8, Vec<u8>) {
+        let len = (*bits.iter().max().unwrap() as usize / 5) + 1;
+        let mut words = vec![0u8; len];
+        for bit in bits {
+            words[len - 1 - *bit as usize / 5] |= 1 << (bit % 5);
+        }
+        (TAG_FEATURES, words)
+    }
+
+    pub(crate) fn payment_hash_field(hash: &str) -> (u8, Vec<u8>) {
+        field(TAG_PAYMENT_HASH, &hex::decode(hash).unwrap())
+    }
+
+    pub(crate) fn description_hash_field(description: &str) -> (u8, Vec<u8>) {
+        field(TAG_DESCRIPTION_HASH, &Sha256::digest(description.as_bytes()))
+    }
+
+    fn data_words(fields: &[(u8, Vec<u8>)]) -> Vec<u8> {
+        let mut words = u64_to_words(SPEC_TIMESTAMP, TIMESTAMP_WORDS);
+        for (tag, value) in fields {
+            words.push(*tag);
+            words.extend(u64_to_words(value.len() as u64, 2));
+            words.extend(value);
+        }
+        words
+    }
+
+    fn sign(hrp: &str, data: &[u8], key: &SecretKey) -> Vec<u8> {
+        let mut signed = hrp.as_bytes().to_vec();
+        signed.extend(words_to_bytes(data, true));
+        let message = Message::from_digest_slice(&Sha256::digest(&signed)).unwrap();
+        let (recovery_id, signature) = Secp256k1::new()
+            .sign_ecdsa_recoverable(&message, key)
+            .serialize_compact();
+        let mut signature = signature.to_vec();
+        signature.push(recovery_id.to_i32() as u8);
+        bytes_to_words(&signature)
+    }
+
+    /// Chaîne bech32 complète, somme de contrôle comprise
+    fn assemble(hrp: &str, data: &[u8], signature: &[u8]) -> String {
+        let mut words = data.to_vec();
+        words.extend(signature);
+        let mut checked = hrp_expand(hrp);
+        checked.extend(&words);
+        checked.extend([0; CHECKSUM_WORDS]);
+        let checksum = polymod(&checked) ^ 1;
+        words.extend((0..CHECKSUM_WORDS).map(|i| ((checksum >> (5 * (5 - i))) & 31) as u8));
+        let data: String = words.iter().map(|w| CHARSET.as_bytes()[*w as usize] as char).collect();
+        format!("{}1{}", hrp, data)
+    }
+
+    /// Chaîne bech32 sans signature, comme un LNURL (LUD-01)
+    pub(crate) fn bech32_encode(hrp: &str, bytes: &[u8]) -> String {
+        assemble(hrp, &bytes_to_words(bytes), &[])
+    }
+
+    fn spec_key() -> SecretKey {
+        SecretKey::from_slice(&hex::decode(SPEC_PRIVATE_KEY).unwrap()).unwrap()
+    }
+
+    /// Facture signée par la clé des exemples de BOLT11, horodatée comme eux
+    pub(crate) fn encode(hrp: &str, fields: &[(u8, Vec<u8>)]) -> String {
+        let data = data_words(fields);
+        assemble(hrp, &data, &sign(hrp, &data, &spec_key()))
+    }
+
+    /// Champs communs aux exemples : secret et hash de paiement, var_onion et
+    /// payment_secret requis
+    fn spec_fields(description: (u8, Vec<u8>)) -> Vec<(u8, Vec<u8>)> {
+        vec![
+            field(TAG_PAYMENT_SECRET, &SPEC_PAYMENT_SECRET),
+            payment_hash_field(SPEC_PAYMENT_HASH),
+            description,
+            features_field(&[8, 14]),
+        ]
+    }
+
+    fn donation() -> (u8, Vec<u8>) {
+        field(TAG_DESCRIPTION, b"Please consider supporting this project")
+    }
+
+    #[test]
+    fn decodes_spec_donation_without_amount() {
+        let invoice = encode("lnbc", &spec_fields(donation()));
+        assert_eq!(
+            invoice,
+            "lnbc1pvjluezsp5zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zygspp5qqqsyqcyq5rqwz\
+             qfqqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqypqdpl2pkx2ctnv5sxxmmwwd5kgetjypeh2ursdae8g6twvus8\
+             g6rfwvs8qun0dfjkxaq9qrsgq357wnc5r2ueh7ck6q93dj32dlqnls087fxdwk8qakdyafkq3yap9us6v52vj\
+             jsrvywa6rt52cm9r9zqt8r2t7mlcwspyetp5h2tztugp9lfyql"
+        );
+
+        let decoded = decode(&invoice).unwrap();
+        assert_eq!(decoded.network, Network::Bitcoin);
+        assert_eq!(decoded.amount_msat, None);
+        assert_eq!(decoded.timestamp, SPEC_TIMESTAMP as i64);
+        assert_eq!(decoded.payment_hash, SPEC_PAYMENT_HASH);
+        assert_eq!(decoded.payment_secret, Some(hex::encode(SPEC_PAYMENT_SECRET)));
+        assert_eq!(
+            decoded.description.as_deref(),
+            Some("Please consider supporting this project")
+        );
+        assert_eq!(decoded.expiry, DEFAULT_EXPIRY);
+        assert_eq!(decoded.min_final_cltv_expiry, DEFAULT_MIN_FINAL_CLTV_EXPIRY);
+        assert_eq!(decoded.features, vec![8, 14]);
+        assert_eq!(decoded.payee, SPEC_PAYEE);
+    }
+
+    #[test]
+    fn decodes_spec_coffee_with_amount_and_expiry() {
+        let mut fields = spec_fields(field(TAG_DESCRIPTION, "1 cup coffee".as_bytes()));
+        fields.push(int_field(TAG_EXPIRY, 60));
+        let decoded = decode(&encode("lnbc2500u", &fields)).unwrap();
+        assert_eq!(decoded.amount_msat, Some(250_000_000));
+        assert_eq!(decoded.description.as_deref(), Some("1 cup coffee"));
+        assert_eq!(decoded.expiry, 60);
+        assert_eq!(decoded.payee, SPEC_PAYEE);
+    }
+
+    #[test]
+    fn decodes_spec_description_hash_and_route_hints() {
+        let cake = "One piece of chocolate cake, one icecream cone, one pickle, one slice of \
+                    swiss cheese, one slice of salami, one lollypop, one piece of cherry pie, \
+                    one sausage, one cupcake, and one slice of watermelon";
+        // Pubkey, scid, frais de base, frais proportionnels et delta CLTV de chaque saut
+        let hops: [(&str, &str, u32, u32, u16); 2] = [
+            (
+                "029e03a901b85534ff1e92c43c74431f7ce72046060fcf7a95c37e148f78c77255",
+                "0102030405060708",
+                1,
+                20,
+                3,
+            ),
+            (
+                "039e03a901b85534ff1e92c43c74431f7ce72046060fcf7a95c37e148f78c77255",
+                "030405060708090a",
+                2,
+                30,
+                4,
+            ),
+        ];
+        let mut route = Vec::new();
+        for (pubkey, scid, fee_base, fee_ppm, cltv) in hops {
+            route.extend(hex::decode(pubkey).unwrap());
+            route.extend(hex::decode(scid).unwrap());
+            route.extend(fee_base.to_be_bytes());
+            route.extend(fee_ppm.to_be_bytes());
+            route.extend(cltv.to_be_bytes());
+        }
+        let mut fields = spec_fields(description_hash_field(cake));
+        fields.push(field(TAG_ROUTE_HINT, &route));
+        fields.push(int_field(TAG_MIN_FINAL_CLTV_EXPIRY, 12));
+
+        let decoded = decode(&encode("lnbc20m", &fields)).unwrap();
+        assert_eq!(decoded.amount_msat, Some(2_000_000_000));
+        assert_eq!(decoded.description, None);
+        assert_eq!(
+            decoded.description_hash.as_deref(),
+            Some("3925b6f67e2c340036ed12093dd44e0368df1b6ea26c53dbe4811f58fd5db8c1")
+        );
+        assert_eq!(decoded.min_final_cltv_expiry, 12);
+        let hints = &decoded.route_hints[0];
+        assert_eq!(hints.len(), 2);
+        assert_eq!(hints[0].pubkey, hops[0].0);
+        assert_eq!(hints[0].short_channel_id, format_scid(0x0102030405060708));
+        assert_eq!(hints[0].fee_base_msat, 1);
+        assert_eq!(hints[0].fee_proportional_millionths, 20);
+        assert_eq!(hints[0].cltv_expiry_delta, 3);
+        assert_eq!(hints[1].fee_base_msat, 2);
+        assert_eq!(hints[1].cltv_expiry_delta, 4);
+    }
+
+    #[test]
+    fn decodes_spec_uppercase_uri_and_pico_amount() {
+        let invoice = encode("lnbc9678785340p", &spec_fields(donation()));
+        let decoded = decode(&format!("LIGHTNING:{}", invoice.to_uppercase())).unwrap();
+        assert_eq!(decoded.amount_msat, Some(967_878_534));
+        assert_eq!(decoded.payee, SPEC_PAYEE);
+    }
+
+    #[test]
+    fn ignores_unknown_odd_features() {
+        let mut fields = spec_fields(donation());
+        fields[3] = features_field(&[8, 14, 99]);
+        let decoded = decode(&encode("lnbc25m", &fields)).unwrap();
+        assert_eq!(decoded.features, vec![8, 14, 99]);
+    }
+
+    fn error_of(invoice: &str) -> String {
+        format!("{:#}", decode(invoice).unwrap_err())
+    }
+
+    #[test]
+    fn rejects_bad_checksum() {
+        let mut invoice = encode("lnbc2500u", &spec_fields(donation()));
+        let last = invoice.pop().unwrap();
+        invoice.push(if last == 'q' { 'p' } else { 'q' });
+        assert!(error_of(&invoice).contains("somme de contrôle incorrecte"));
+    }
+
+    #[test]
+    fn rejects_malformed_bech32() {
+        let invoice = encode("lnbc2500u", &spec_fields(donation()));
+        assert!(error_of(&invoice.replace('1', "b")).contains("séparateur absent"));
+        let mixed = format!("LNBC2500U{}", &invoice[9..]);
+        assert!(error_of(&mixed).contains("casse mixte"));
+        assert!(error_of("lnbc1pvjlue").contains("somme de contrôle incorrecte"));
+        assert!(error_of(&assemble("lnbc", &[], &[])).contains("données trop courtes"));
+    }
+
+    #[test]
+    fn rejects_invalid_multiplier_and_sub_msat_precision() {
+        let invoice = encode("lnbc2500x", &spec_fields(donation()));
+        assert!(error_of(&invoice).contains("multiplicateur « x » inconnu"));
+        let invoice = encode("lnbc2500000001p", &spec_fields(donation()));
+        assert!(error_of(&invoice).contains("plus précis que le millisatoshi"));
+    }
+
+    #[test]
+    fn rejects_bad_signature() {
+        let data = data_words(&spec_fields(donation()));
+        let mut signature = sign("lnbc2500u", &data, &spec_key());
+        // Identifiant de récupération hors de 0..=3
+        *signature.last_mut().unwrap() = 0b11100;
+        assert!(error_of(&assemble("lnbc2500u", &data, &signature)).contains("récupération"));
+        // r et s nuls : la clé publique ne peut pas être retrouvée
+        let zero = bytes_to_words(&[0; 65]);
+        assert!(error_of(&assemble("lnbc2500u", &data, &zero)).contains("signature"));
+    }
+
+    #[test]
+    fn rejects_signature_from_another_node_than_payee() {
+        let mut fields = spec_fields(donation());
+        let other = SecretKey::from_slice(&[0x42; 32]).unwrap();
+        let other_pubkey = PublicKey::from_secret_key(&Secp256k1::new(), &other);
+        fields.push(field(TAG_PAYEE, &other_pubkey.serialize()));
+        assert!(error_of(&encode("lnbc2500u", &fields)).contains("autre nœud"));
+    }
+
+    #[test]
+    fn rejects_unknown_required_feature() {
+        let mut fields = spec_fields(donation());
+        fields[3] = features_field(&[8, 14, 100]);
+        let invoice = encode("lnbc25m", &fields);
+        assert!(error_of(&invoice).contains("fonctionnalité requise 100 inconnue"));
+    }
+}
@@ -0,0 +1,333 @@
+use anyhow::{bail, Context, Result};
+
+use crate::models::lightning::{DecodedBolt12, DecodedBolt12Invoice, DecodedOffer, Network};
+use crate::services::bolt11::{bytes_to_u64, strip_scheme, words_to_bytes, CHARSET};
+
+/// Durée de validité implicite d'une facture sans champ `invoice_relative_expiry`
+const DEFAULT_RELATIVE_EXPIRY: u64 = 7200;
+/// Bits de fonctionnalité connus : basic_mpp
+const KNOWN_FEATURES: [u16; 2] = [16, 17];
+
+/// Empreintes des blocs de genèse, dans l'ordre d'octets du champ `chain_hash`
+const CHAINS: [(&str, Network); 5] = [
+    (
+        "6fe28c0ab6f1b372c1a6a246ae63f74f931e8365e15a089c68d6190000000000",
+        Network::Bitcoin,
+    ),
+    (
+        "43497fd7f826957108f4a30fd9cec3aeba79972084e90ead01ea330900000000",
+        Network::Testnet,
+    ),
+    (
+        "43f08bdab050e35b567c864b91f47f50ae725ae2de53bcfbbaf284da00000000",
+        Network::Testnet,
+    ),
+    (
+        "efe1be33360a487a073af62a23bb9bac7c9ff9f01f2c4225e973988108000000",
+        Network::Signet,
+    ),
+    (
+        "06226e46111a0b59caaf126043eb5bbf28c34f3a5e332a1fc7b2b73cf188910f",
+        Network::Regtest,
+    ),
+];
+
+// Types des enregistrements TLV de l'offre
+const OFFER_CHAINS: u64 = 2;
+const OFFER_CURRENCY: u64 = 6;
+const OFFER_AMOUNT: u64 = 8;
+const OFFER_DESCRIPTION: u64 = 10;
+const OFFER_FEATURES: u64 = 12;
+const OFFER_ABSOLUTE_EXPIRY: u64 = 14;
+const OFFER_PATHS: u64 = 16;
+const OFFER_ISSUER: u64 = 18;
+const OFFER_QUANTITY_MAX: u64 = 20;
+const OFFER_ISSUER_ID: u64 = 22;
+
+// Types des enregistrements TLV de la demande de facture reprise dans la facture
+const INVREQ_QUANTITY: u64 = 86;
+const INVREQ_PAYER_NOTE: u64 = 89;
+
+// Types des enregistrements TLV de la facture
+const INVOICE_PATHS: u64 = 160;
+const INVOICE_CREATED_AT: u64 = 164;
+const INVOICE_RELATIVE_EXPIRY: u64 = 166;
+const INVOICE_PAYMENT_HASH: u64 = 168;
+const INVOICE_AMOUNT: u64 = 170;
+const INVOICE_FEATURES: u64 = 174;
+const INVOICE_NODE_ID: u64 = 176;
+
+/// Plages de types réservées à l'offre : un type pair inconnu y est une erreur
+fn is_offer_type(tlv_type: u64) -> bool {
+    (1..80).contains(&tlv_type) || (1_000_000_000..2_000_000_000).contains(&tlv_type)
+}
+
+/// Retire les `n` premiers octets de `bytes`
+fn take<'a>(bytes: &mut &'a [u8], n: usize) -> Result<&'a [u8]> {
+    if bytes.len() < n {
+        bail!("Chaîne BOLT12 invalide: enregistrement tronqué");
+    }
+    let (head, tail) = bytes.split_at(n);
+    *bytes = tail;
+    Ok(head)
+}
+
+/// Lit un entier BigSize, en refusant les encodages non minimaux
+fn read_bigsize(bytes: &mut &[u8]) -> Result<u64> {
+    let prefix = take(bytes, 1)?[0];
+    let (value, minimum) = match prefix {
+        0xfd => (bytes_to_u64(take(bytes, 2)?), 0xfd),
+        0xfe => (bytes_to_u64(take(bytes, 4)?), 0x1_0000),
+        0xff => (bytes_to_u64(take(bytes, 8)?), 0x1_0000_0000),
+        value => return Ok(value as u64),
+    };
+    if value < minimum {
+        bail!("Chaîne BOLT12 invalide: entier BigSize non minimal");
+    }
+    Ok(value)
+}
+
+/// Découpe un flux TLV en enregistrements, dont les types doivent être strictement croissants
+fn read_records(mut bytes: &[u8]) -> Result<Vec<(u64, &[u8])>> {
+    let mut records: Vec<(u64, &[u8])> = Vec::new();
+    while !bytes.is_empty() {
+        let tlv_type = read_bigsize(&mut bytes)?;
+        if records.last().is_some_and(|(last, _)| *last >= tlv_type) {
+            bail!("Chaîne BOLT12 invalide: enregistrements TLV désordonnés");
+        }
+        let length = read_bigsize(&mut bytes)?;
+        let value = take(&mut bytes, length as usize)?;
+        records.push((tlv_type, value));
+    }
+    Ok(records)
+}
+
+/// Entier tronqué (`tu64`) : big-endian, sans zéro de tête
+fn read_tu64(value: &[u8]) -> Result<u64> {
+    if value.len() > 8 || value.first() == Some(&0) {
+        bail!("Chaîne BOLT12 invalide: entier tronqué mal encodé");
+    }
+    Ok(bytes_to_u64(value))
+}
+
+fn read_utf8(value: &[u8]) -> Result<String> {
+    String::from_utf8(value.to_vec()).context("Chaîne BOLT12 invalide: texte non UTF-8")
+}
+
+fn read_point(value: &[u8]) -> Result<String> {
+    if value.len() != 33 {
+        bail!("Chaîne BOLT12 invalide: clé publique de taille incorrecte");
+    }
+    Ok(hex::encode(value))
+}
+
+/// Le champ de fonctionnalités est un grand entier dont le bit `n` signale la fonctionnalité `n`
+fn read_features(value: &[u8]) -> Result<Vec<u16>> {
+    let mut features = Vec::new();
+    for (position, byte) in value.iter().rev().enumerate() {
+        for bit in 0..8 {
+            if (byte >> bit) & 1 == 1 {
+                features.push((position * 8 + bit) as u16);
+            }
+        }
+    }
+    if let Some(unknown) = features
+        .iter()
+        .find(|f| *f % 2 == 0 && !KNOWN_FEATURES.contains(f))
+    {
+        bail!(
+            "Chaîne BOLT12 non prise en charge: fonctionnalité obligatoire {} inconnue",
+            unknown
+        );
+    }
+    Ok(features)
+}
+
+/// Compte les chemins aveuglés d'un champ `*_paths` en vérifiant leur structure
+fn count_blinded_paths(mut value: &[u8]) -> Result<usize> {
+    let mut count = 0;
+    while !value.is_empty() {
+        // Premier nœud : clé publique, ou short channel id précédé de sa direction
+        let first_node = match value[0] {
+            0 | 1 => 9,
+            2 | 3 => 33,
+            _ => bail!("Chaîne BOLT12 invalide: chemin aveuglé mal formé"),
+        };
+        take(&mut value, first_node + 33)?;
+        let hops = take(&mut value, 1)?[0];
+        if hops == 0 {
+            bail!("Chaîne BOLT12 invalide: chemin aveuglé sans saut");
+        }
+        for _ in 0..hops {
+            take(&mut value, 33)?;
+            let length = bytes_to_u64(take(&mut value, 2)?) as usize;
+            take(&mut value, length)?;
+        }
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Décode la partie de données d'une chaîne BOLT12 : bech32 sans somme de contrôle,
+/// éventuellement découpée en morceaux joints par `+`.
+fn bech32_decode(value: &str) -> Result<(String, Vec<u8>)> {
+    if !value.is_ascii() {
+        bail!("Chaîne BOLT12 invalide: caractères non ASCII");
+    }
+    let parts: Vec<&str> = value.split('+').map(str::trim).collect();
+    if parts.iter().any(|part| part.is_empty()) {
+        bail!("Chaîne BOLT12 invalide: « + » mal placé");
+    }
+    let value = parts.concat();
+    if value.chars().any(|c| c.is_ascii_lowercase())
+        && value.chars().any(|c| c.is_ascii_uppercase())
+    {
+        bail!("Chaîne BOLT12 invalide: casse mixte");
+    }
+    let value = value.to_lowercase();
+    let (hrp, data) = value
+        .split_once('1')
+        .context("Chaîne BOLT12 invalide: séparateur absent")?;
+
+    let words = data
+        .chars()
+        .map(|c| CHARSET.find(c).map(|i| i as u8))
+        .collect::<Option<Vec<u8>>>()
+        .context("Chaîne BOLT12 invalide: caractère hors de l'alphabet bech32")?;
+    if (words.len() * 5) % 8 >= 5 {
+        bail!("Chaîne BOLT12 invalide: longueur incorrecte");
+    }
+    Ok((hrp.to_string(), words_to_bytes(&words, false)))
+}
+
+/// Lit les champs de l'offre, communs aux offres et aux factures qui en découlent
+fn read_offer(records: &[(u64, &[u8])]) -> Result<DecodedOffer> {
+    let mut offer = DecodedOffer {
+        chains: vec![Network::Bitcoin],
+        amount: None,
+        currency: None,
+        description: None,
+        issuer: None,
+        issuer_id: None,
+        absolute_expiry: None,
+        quantity_max: None,
+        paths: 0,
+        features: Vec::new(),
+    };
+
+    for (tlv_type, value) in records.iter().filter(|(t, _)| is_offer_type(*t)) {
+        match *tlv_type {
+            OFFER_CHAINS => {
+                if value.is_empty() || value.len() % 32 != 0 {
+                    bail!("Chaîne BOLT12 invalide: champ offer_chains mal formé");
+                }
+                offer.chains = value
+                    .chunks(32)
+                    .filter_map(|chain| {
+                        let chain = hex::encode(chain);
+                        CHAINS
+                            .iter()
+                            .find(|(hash, _)| *hash == chain)
+                            .map(|(_, network)| *network)
+                    })
+                    .collect();
+            }
+            OFFER_CURRENCY => offer.currency = Some(read_utf8(value)?),
+            OFFER_AMOUNT => offer.amount = Some(read_tu64(value)?),
+            OFFER_DESCRIPTION => offer.description = Some(read_utf8(value)?),
+            OFFER_FEATURES => offer.features = read_features(value)?,
+            OFFER_ABSOLUTE_EXPIRY => offer.absolute_expiry = Some(read_tu64(value)? as i64),
+            OFFER_PATHS => {
+                offer.paths = count_blinded_paths(value)?;
+                if offer.paths == 0 {
+                    bail!("Chaîne BOLT12 invalide: champ offer_paths vide");
+                }
+            }
+            OFFER_ISSUER => offer.issuer = Some(read_utf8(value)?),
+            OFFER_QUANTITY_MAX => offer.quantity_max = Some(read_tu64(value)?),
+            OFFER_ISSUER_ID => offer.issuer_id = Some(read_point(value)?),
+            other if other % 2 == 0 => {
+                bail!(
+                    "Chaîne BOLT12 non prise en charge: champ obligatoire {} inconnu",
+                    other
+                )
+            }
+            _ => {}
+        }
+    }
+
+    if offer.currency.is_some() && offer.amount.is_none() {
+        bail!("Chaîne BOLT12 invalide: devise sans montant");
+    }
+    Ok(offer)
+}
+
+fn decode_offer(records: &[(u64, &[u8])]) -> Result<DecodedOffer> {
+    if let Some((other, _)) = records.iter().find(|(t, _)| !is_offer_type(*t)) {
+        bail!("Offre BOLT12 invalide: champ {} hors de l'offre", other);
+    }
+    let offer = read_offer(records)?;
+    if offer.amount.is_some() && offer.description.is_none() {
+        bail!("Offre BOLT12 invalide: montant sans description");
+    }
+    if offer.issuer_id.is_none() && offer.paths == 0 {
+        bail!("Offre BOLT12 invalide: ni émetteur ni chemin vers l'émetteur");
+    }
+    Ok(offer)
+}
+
+fn decode_invoice(records: &[(u64, &[u8])]) -> Result<DecodedBolt12Invoice> {
+    let offer = read_offer(records)?;
+    let field = |tlv_type: u64| {
+        records
+            .iter()
+            .find(|(t, _)| *t == tlv_type)
+            .map(|(_, value)| *value)
+    };
+    let required = |tlv_type: u64, name: &str| {
+        field(tlv_type).with_context(|| format!("Facture BOLT12 invalide: champ {} absent", name))
+    };
+
+    let payment_hash = required(INVOICE_PAYMENT_HASH, "invoice_payment_hash")?;
+    if payment_hash.len() != 32 {
+        bail!("Facture BOLT12 invalide: empreinte de paiement de taille incorrecte");
+    }
+    let paths = count_blinded_paths(required(INVOICE_PATHS, "invoice_paths")?)?;
+    if paths == 0 {
+        bail!("Facture BOLT12 invalide: champ invoice_paths vide");
+    }
+
+    Ok(DecodedBolt12Invoice {
+        payer_note: field(INVREQ_PAYER_NOTE).map(read_utf8).transpose()?,
+        quantity: field(INVREQ_QUANTITY).map(read_tu64).transpose()?,
+        amount_msat: read_tu64(required(INVOICE_AMOUNT, "invoice_amount")?)?,
+        created_at: read_tu64(required(INVOICE_CREATED_AT, "invoice_created_at")?)? as i64,
+        relative_expiry: field(INVOICE_RELATIVE_EXPIRY)
+            .map(read_tu64)
+            .transpose()?
+            .unwrap_or(DEFAULT_RELATIVE_EXPIRY),
+        payment_hash: hex::encode(payment_hash),
+        node_id: read_point(required(INVOICE_NODE_ID, "invoice_node_id")?)?,
+        paths,
+        features: field(INVOICE_FEATURES)
+            .map(read_features)
+            .transpose()?
+            .unwrap_or_default(),
+        offer,
+    })
+}
+
+/// Décode une offre (`lno1...`) ou une facture (`lni1...`) BOLT12.
+///
+/// Le préfixe `lightning:` des URI est accepté. La signature des factures
+/// n'est pas vérifiée : le nœud qui les paie s'en charge.
+pub fn decode(value: &str) -> Result<DecodedBolt12> {
+    let (hrp, bytes) = bech32_decode(strip_scheme(value))?;
+    let records = read_records(&bytes)?;
+    match hrp.as_str() {
+        "lno" => Ok(DecodedBolt12::Offer(decode_offer(&records)?)),
+        "lni" => Ok(DecodedBolt12::Invoice(decode_invoice(&records)?)),
+        "lnr" => bail!("Les demandes de facture BOLT12 ne sont pas prises en charge"),
+        other => bail!("Chaîne BOLT12 invalide: préfixe « {} » inconnu", other),
+    }
+}
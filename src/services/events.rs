@@ -0,0 +1,265 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::models::lightning::{
+    Channel, ChannelStatus, NodeEvent, NodeEventMessage, Transaction, TransactionStatus,
+    TransactionType,
+};
+use crate::services::backends::NodeBackend;
+use crate::services::lightning::LightningService;
+
+/// Nombre d'événements conservés pour un abonné lent avant qu'il ne décroche
+const EVENT_BUFFER: usize = 256;
+/// Délai avant de relancer l'abonnement d'un nœud dont le flux s'est interrompu
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(10);
+/// Période de scrutation des backends qui n'offrent pas de flux natif
+pub(crate) const EVENT_POLL_INTERVAL: Duration = Duration::from_secs(15);
+/// Taille des pages lues dans l'historique de routage à chaque scrutation
+const FORWARDS_PAGE_SIZE: u32 = 500;
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// Canal de diffusion des événements de tous les nœuds vers les clients
+/// connectés à `/api/events`.
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<NodeEventMessage>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_BUFFER);
+        Self { sender }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<NodeEventMessage> {
+        self.sender.subscribe()
+    }
+
+    pub fn sink(&self, node: &str) -> EventSink {
+        EventSink {
+            node: node.to_string(),
+            sender: self.sender.clone(),
+        }
+    }
+
+    /// Suit les événements de `service` en tâche de fond.
+    ///
+    /// L'abonnement est relancé tant que la tâche n'est pas annulée, de sorte
+    /// qu'un redémarrage du nœud ne coupe pas définitivement le flux.
+    pub fn spawn(&self, node: &str, service: LightningService) -> JoinHandle<()> {
+        let sink = self.sink(node);
+        tokio::spawn(async move {
+            loop {
+                info!(node = %sink.node, "Abonnement aux événements du nœud");
+                if let Err(e) = service.subscribe_events(&sink).await {
+                    warn!(node = %sink.node, error = %e, "Flux d'événements interrompu");
+                }
+                tokio::time::sleep(RESUBSCRIBE_DELAY).await;
+            }
+        })
+    }
+}
+
+/// Point d'émission des événements d'un nœud donné
+#[derive(Clone)]
+pub struct EventSink {
+    node: String,
+    sender: broadcast::Sender<NodeEventMessage>,
+}
+
+impl EventSink {
+    pub fn emit(&self, event: NodeEvent) {
+        debug!(node = %self.node, event = ?event, "Événement du nœud");
+        // Aucun abonné connecté : l'événement est simplement perdu
+        let _ = self.sender.send(NodeEventMessage {
+            node: self.node.clone(),
+            timestamp: now(),
+            event,
+        });
+    }
+}
+
+struct ChannelSnapshot {
+    status: ChannelStatus,
+    remote_pubkey: String,
+    capacity: u64,
+}
+
+/// Déduit les événements d'un nœud en comparant deux relevés successifs,
+/// pour les backends qui n'offrent pas de flux d'événements.
+///
+/// Le premier relevé sert de référence et n'émet rien.
+pub(crate) struct EventPoller {
+    started_at: i64,
+    channels: Option<HashMap<String, ChannelSnapshot>>,
+    payments: Option<HashMap<String, TransactionStatus>>,
+    invoices: Option<HashMap<String, TransactionStatus>>,
+    forwards_offset: u64,
+}
+
+impl EventPoller {
+    pub(crate) fn new() -> Self {
+        Self {
+            started_at: now(),
+            channels: None,
+            payments: None,
+            invoices: None,
+            forwards_offset: 0,
+        }
+    }
+
+    pub(crate) async fn poll<B: NodeBackend + ?Sized>(
+        &mut self,
+        backend: &B,
+        sink: &EventSink,
+    ) -> Result<()> {
+        self.diff_channels(backend.list_channels().await?, sink);
+        self.diff_transactions(backend.list_transactions().await?, sink);
+        if let Err(e) = self.poll_forwards(backend, sink).await {
+            debug!(error = %e, "Routages non suivis pour ce nœud");
+        }
+        Ok(())
+    }
+
+    fn diff_channels(&mut self, channels: Vec<Channel>, sink: &EventSink) {
+        let current: HashMap<String, ChannelSnapshot> = channels
+            .into_iter()
+            .map(|c| {
+                (
+                    c.id,
+                    ChannelSnapshot {
+                        status: c.status,
+                        remote_pubkey: c.remote_pubkey,
+                        capacity: c.capacity,
+                    },
+                )
+            })
+            .collect();
+
+        if let Some(previous) = &self.channels {
+            let mut vanished: Vec<(&String, &ChannelSnapshot)> = previous
+                .iter()
+                .filter(|(id, _)| !current.contains_key(*id))
+                .collect();
+
+            for (id, channel) in &current {
+                let before = previous.get(id).map(|c| c.status);
+                match (before, channel.status) {
+                    (
+                        None | Some(ChannelStatus::Pending),
+                        ChannelStatus::Active | ChannelStatus::Inactive,
+                    ) => {
+                        // Core Lightning change d'identifiant à la confirmation :
+                        // le canal en attente disparu est le même canal, pas une fermeture.
+                        vanished.retain(|(_, old)| {
+                            !(old.status == ChannelStatus::Pending
+                                && old.remote_pubkey == channel.remote_pubkey
+                                && old.capacity == channel.capacity)
+                        });
+                        sink.emit(NodeEvent::ChannelOpened {
+                            channel_id: id.clone(),
+                            remote_pubkey: channel.remote_pubkey.clone(),
+                            capacity: channel.capacity,
+                        });
+                    }
+                    (Some(ChannelStatus::Inactive), ChannelStatus::Active) => {
+                        sink.emit(NodeEvent::ChannelActive {
+                            channel_id: id.clone(),
+                        });
+                    }
+                    (Some(ChannelStatus::Active), ChannelStatus::Inactive) => {
+                        sink.emit(NodeEvent::ChannelInactive {
+                            channel_id: id.clone(),
+                        });
+                    }
+                    _ => {}
+                }
+            }
+
+            for (id, _) in vanished {
+                sink.emit(NodeEvent::ChannelClosed {
+                    channel_id: id.clone(),
+                });
+            }
+        }
+
+        self.channels = Some(current);
+    }
+
+    fn diff_transactions(&mut self, transactions: Vec<Transaction>, sink: &EventSink) {
+        let mut payments = HashMap::new();
+        let mut invoices = HashMap::new();
+
+        for transaction in transactions {
+            match transaction.type_ {
+                TransactionType::Payment => {
+                    if let Some(previous) = &self.payments {
+                        if previous.get(&transaction.id) != Some(&transaction.status) {
+                            sink.emit(NodeEvent::PaymentUpdated {
+                                payment_hash: transaction.id.clone(),
+                                status: transaction.status,
+                                amount: transaction.amount,
+                                fee: transaction.fee,
+                            });
+                        }
+                    }
+                    payments.insert(transaction.id, transaction.status);
+                }
+                TransactionType::Invoice | TransactionType::Keysend => {
+                    if let Some(previous) = &self.invoices {
+                        if transaction.status == TransactionStatus::Completed
+                            && previous.get(&transaction.id) != Some(&TransactionStatus::Completed)
+                        {
+                            sink.emit(NodeEvent::InvoiceSettled {
+                                payment_hash: transaction.id.clone(),
+                                amount: transaction.amount,
+                            });
+                        }
+                    }
+                    invoices.insert(transaction.id, transaction.status);
+                }
+                _ => {}
+            }
+        }
+
+        self.payments = Some(payments);
+        self.invoices = Some(invoices);
+    }
+
+    /// Parcourt les routages survenus depuis le démarrage de la scrutation,
+    /// en reprenant à la position atteinte au passage précédent.
+    async fn poll_forwards<B: NodeBackend + ?Sized>(
+        &mut self,
+        backend: &B,
+        sink: &EventSink,
+    ) -> Result<()> {
+        loop {
+            let page = backend
+                .forwarding_history(self.started_at, self.forwards_offset, FORWARDS_PAGE_SIZE)
+                .await?;
+            for event in page.events {
+                sink.emit(NodeEvent::HtlcForwarded(event));
+            }
+            if page.next_offset <= self.forwards_offset {
+                return Ok(());
+            }
+            self.forwards_offset = page.next_offset;
+        }
+    }
+}
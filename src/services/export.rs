@@ -0,0 +1,292 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::HashSet;
+use tracing::{info, instrument};
+
+use crate::models::export::{EntryKind, ExportFormat, ExportRequest, LedgerEntry};
+use crate::models::lightning::{TransactionStatus, TransactionType};
+use crate::services::history::HistoryStore;
+use crate::services::lightning::LightningService;
+use crate::services::prices::{day_of, PriceClient};
+use crate::services::registry::NodeRegistry;
+
+/// Étiquette BIP-329, champs dans l'ordre de la spécification
+#[derive(Serialize)]
+struct Bip329Label<'a> {
+    #[serde(rename = "type")]
+    type_: &'a str,
+    #[serde(rename = "ref")]
+    reference: &'a str,
+    label: &'a str,
+}
+
+/// Fenêtre exportée quand ni `range` ni `from` ne sont précisés
+pub const DEFAULT_EXPORT_RANGE: &str = "30d";
+
+const KOINLY_HEADER: &str = "Date,Sent Amount,Sent Currency,Received Amount,Received Currency,\
+Fee Amount,Fee Currency,Net Worth Amount,Net Worth Currency,Label,Description,TxHash";
+
+/// Mouvements réglés d'un nœud entre `from` et `to`, du plus ancien au plus récent.
+///
+/// Paiements, factures et transactions on-chain viennent du nœud, les relais
+/// de l'historique enregistré. Les transactions en attente ou échouées sont ignorées.
+#[instrument(skip(service, history), err)]
+pub async fn collect_ledger(
+    service: &LightningService,
+    history: &HistoryStore,
+    node: &str,
+    from: i64,
+    to: i64,
+) -> Result<Vec<LedgerEntry>> {
+    let transactions = service.list_transactions().await?;
+    let forwards = history.forwards(node, from, to, None, u32::MAX).await?;
+
+    let mut entries: Vec<LedgerEntry> = transactions
+        .into_iter()
+        .filter(|t| t.status == TransactionStatus::Completed)
+        .filter(|t| (from..=to).contains(&t.timestamp))
+        .map(|t| {
+            let kind = match t.type_ {
+                TransactionType::Payment => EntryKind::Payment,
+                TransactionType::Invoice => EntryKind::Invoice,
+                TransactionType::Keysend => EntryKind::Keysend,
+                TransactionType::ChannelOpen => EntryKind::ChannelOpen,
+                TransactionType::ChannelClose => EntryKind::ChannelClose,
+                TransactionType::Onchain => EntryKind::Onchain,
+            };
+            let amount_msat = (t.amount * 1000) as i64;
+            LedgerEntry {
+                timestamp: t.timestamp,
+                node: node.to_string(),
+                kind,
+                amount_msat: if t.outgoing { -amount_msat } else { amount_msat },
+                fee_msat: t.fee * 1000,
+                reference: t.id,
+                description: t.description,
+                fiat_price: None,
+            }
+        })
+        .collect();
+    entries.extend(forwards.forwards.into_iter().map(|f| LedgerEntry {
+        timestamp: f.timestamp,
+        node: node.to_string(),
+        kind: EntryKind::Forward,
+        amount_msat: f.fee_msat as i64,
+        fee_msat: 0,
+        reference: format!("{}->{}", f.chan_id_in, f.chan_id_out),
+        description: Some(format!("Relais de {} msat", f.amt_out_msat)),
+        fiat_price: None,
+    }));
+    entries.sort_by_key(|e| e.timestamp);
+    Ok(entries)
+}
+
+/// Produit le fichier d'export d'un ou de tous les nœuds enregistrés
+#[instrument(skip(registry, prices), err)]
+pub async fn export(
+    registry: &NodeRegistry,
+    prices: &PriceClient,
+    request: &ExportRequest,
+) -> Result<String> {
+    let nodes = match &request.node {
+        Some(node) => vec![node.clone()],
+        None => registry.list().await.into_iter().map(|n| n.id).collect(),
+    };
+    let mut entries = Vec::new();
+    for node in nodes {
+        let service = registry
+            .get(&node)
+            .await
+            .with_context(|| format!("Nœud inconnu: {}", node))?;
+        let ledger =
+            collect_ledger(&service, registry.history(), &node, request.from, request.to).await?;
+        entries.extend(ledger);
+    }
+    entries.sort_by_key(|e| e.timestamp);
+
+    if let Some(currency) = &request.currency {
+        let daily = prices
+            .daily_prices(currency, entries.iter().map(|e| e.timestamp))
+            .await?;
+        for entry in &mut entries {
+            entry.fiat_price = daily.get(&day_of(entry.timestamp)).copied();
+        }
+    }
+
+    info!(format = %request.format, count = entries.len(), "Export généré");
+    Ok(render(request.format, &entries, request.currency.as_deref()))
+}
+
+pub fn render(format: ExportFormat, entries: &[LedgerEntry], currency: Option<&str>) -> String {
+    match format {
+        ExportFormat::Csv => render_ledger(entries, currency),
+        ExportFormat::Koinly => render_koinly(entries, currency),
+        ExportFormat::Bip329 => render_bip329(entries),
+    }
+}
+
+/// Grand livre générique : montants signés en sats et en msats, valorisation optionnelle
+fn render_ledger(entries: &[LedgerEntry], currency: Option<&str>) -> String {
+    let mut out = String::from(
+        "date,timestamp,node,kind,amount_sat,amount_msat,fee_sat,fee_msat,reference,description",
+    );
+    if let Some(currency) = currency {
+        let currency = currency.to_ascii_lowercase();
+        out.push_str(&format!(",price_{0},amount_{0},fee_{0}", currency));
+    }
+    out.push('\n');
+
+    for e in entries {
+        let mut row = vec![
+            format!("{}Z", format_utc(e.timestamp).replace(' ', "T")),
+            e.timestamp.to_string(),
+            csv_field(&e.node),
+            e.kind.as_str().to_string(),
+            (e.amount_msat / 1000).to_string(),
+            e.amount_msat.to_string(),
+            (e.fee_msat / 1000).to_string(),
+            e.fee_msat.to_string(),
+            csv_field(&e.reference),
+            csv_field(e.description.as_deref().unwrap_or_default()),
+        ];
+        if currency.is_some() {
+            row.push(format_fiat(e.fiat_price));
+            row.push(format_fiat(e.fiat_value(e.amount_msat)));
+            row.push(format_fiat(e.fiat_value(e.fee_msat as i64)));
+        }
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Format universel de Koinly, montants en BTC.
+///
+/// Les ouvertures et fermetures de canal déplacent des fonds entre nos propres
+/// portefeuilles : seuls leurs frais on-chain sont exportés, comme un coût.
+fn render_koinly(entries: &[LedgerEntry], currency: Option<&str>) -> String {
+    let mut out = format!("{}\n", KOINLY_HEADER);
+    for e in entries {
+        let transfer = matches!(e.kind, EntryKind::ChannelOpen | EntryKind::ChannelClose);
+        let (sent, received, fee, worth, label) = if transfer || e.amount_msat == 0 {
+            if e.fee_msat == 0 {
+                continue;
+            }
+            (e.fee_msat, 0, 0, e.fee_msat, "cost")
+        } else if e.amount_msat < 0 {
+            let sent = e.amount_msat.unsigned_abs();
+            (sent, 0, e.fee_msat, sent, "")
+        } else {
+            let received = e.amount_msat as u64;
+            let label = if e.kind == EntryKind::Forward { "income" } else { "" };
+            (0, received, e.fee_msat, received, label)
+        };
+        let amount = |msat: u64| {
+            if msat == 0 {
+                (String::new(), "")
+            } else {
+                (format_btc(msat), "BTC")
+            }
+        };
+        let (sent, sent_currency) = amount(sent);
+        let (received, received_currency) = amount(received);
+        let (fee, fee_currency) = amount(fee);
+        let worth = e.fiat_value(worth as i64);
+        let description = match &e.description {
+            Some(description) => format!("{} ({})", e.kind.as_str(), description),
+            None => e.kind.as_str().to_string(),
+        };
+        let row = [
+            format!("{} UTC", format_utc(e.timestamp)),
+            sent,
+            sent_currency.to_string(),
+            received,
+            received_currency.to_string(),
+            fee,
+            fee_currency.to_string(),
+            format_fiat(worth),
+            if worth.is_some() { currency.unwrap_or_default().to_string() } else { String::new() },
+            label.to_string(),
+            csv_field(&description),
+            csv_field(&e.reference),
+        ];
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Étiquettes BIP-329 des transactions on-chain, une par txid
+fn render_bip329(entries: &[LedgerEntry]) -> String {
+    let mut seen = HashSet::new();
+    let mut out = String::new();
+    for e in entries.iter().filter(|e| e.kind.is_onchain()) {
+        let label = match (e.kind, e.description.as_deref()) {
+            (_, Some(description)) if !description.is_empty() => description,
+            (EntryKind::ChannelOpen, _) => "Ouverture de canal Lightning",
+            (EntryKind::ChannelClose, _) => "Fermeture de canal Lightning",
+            _ => continue,
+        };
+        if !seen.insert(e.reference.as_str()) {
+            continue;
+        }
+        let line = Bip329Label {
+            type_: "tx",
+            reference: &e.reference,
+            label,
+        };
+        out.push_str(&serde_json::to_string(&line).unwrap_or_default());
+        out.push('\n');
+    }
+    out
+}
+
+/// Champ CSV échappé ; les textes commençant par une formule sont neutralisés
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+/// Montant en BTC, au msat près
+fn format_btc(msat: u64) -> String {
+    let btc = format!("{}.{:011}", msat / 100_000_000_000, msat % 100_000_000_000);
+    btc.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// Contre-valeur fiat, au dix-millième pour ne pas effacer les petits relais
+fn format_fiat(value: Option<f64>) -> String {
+    value.map(|v| format!("{:.4}", v)).unwrap_or_default()
+}
+
+/// Date UTC au format `AAAA-MM-JJ HH:MM:SS`
+fn format_utc(timestamp: i64) -> String {
+    let days = timestamp.div_euclid(86_400);
+    let seconds = timestamp.rem_euclid(86_400);
+    // Conversion jours → date civile (algorithme de Howard Hinnant)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
@@ -0,0 +1,273 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::{debug, info, instrument, warn};
+
+use crate::models::fees::{FeeChange, FeePolicy, FeeStrategy, SparkseerMode, SuggestedFee};
+use crate::models::lightning::{Channel, ChannelStatus, PolicyUpdate};
+use crate::services::history::HistoryStore;
+use crate::services::lightning::LightningService;
+
+/// Données lues une fois par passe pour la stratégie du nœud
+enum Inputs {
+    Nothing,
+    /// Première suggestion Sparkseer de chaque pair
+    Suggestions(HashMap<String, SuggestedFee>),
+    /// Volume sortant de chaque canal sur la fenêtre précédente et sur la fenêtre en cours
+    Volumes {
+        previous: HashMap<String, u64>,
+        current: HashMap<String, u64>,
+    },
+}
+
+/// Taux proposé pour un canal et sa justification
+struct Proposal {
+    fee_rate_ppm: u32,
+    reason: String,
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// Montant sorti par chaque canal de l'historique de routage, en msats
+async fn outgoing_volumes(
+    history: &HistoryStore,
+    node: &str,
+    from: i64,
+    to: i64,
+) -> Result<HashMap<String, u64>> {
+    let report = history.forwards(node, from, to, None, 0).await?;
+    Ok(report
+        .channels
+        .into_iter()
+        .map(|c| (c.channel_id, c.amt_out_msat))
+        .collect())
+}
+
+/// Les relais désignent les canaux par leur short channel id quand le backend en fournit un
+fn volume_of(volumes: &HashMap<String, u64>, channel: &Channel) -> u64 {
+    channel
+        .short_channel_id
+        .as_deref()
+        .and_then(|scid| volumes.get(scid))
+        .or_else(|| volumes.get(&channel.id))
+        .copied()
+        .unwrap_or_default()
+}
+
+async fn gather(
+    strategy: &FeeStrategy,
+    service: &LightningService,
+    history: &HistoryStore,
+    node: &str,
+    api_key: Option<&str>,
+    now: i64,
+) -> Result<Inputs> {
+    match strategy {
+        FeeStrategy::Sparkseer { .. } => {
+            let api_key = api_key.context("Clé d'API Sparkseer manquante (sparkseer_api_key)")?;
+            let suggestions = service.suggested_fees(api_key).await?;
+            Ok(Inputs::Suggestions(
+                suggestions
+                    .channel_peers
+                    .into_iter()
+                    .filter_map(|p| Some((p.pubkey, *p.suggested_fees.first()?)))
+                    .collect(),
+            ))
+        }
+        FeeStrategy::Balance { .. } => Ok(Inputs::Nothing),
+        FeeStrategy::Volume { window_secs, .. } => {
+            let window = *window_secs as i64;
+            Ok(Inputs::Volumes {
+                previous: outgoing_volumes(history, node, now - 2 * window, now - window).await?,
+                current: outgoing_volumes(history, node, now - window, now).await?,
+            })
+        }
+    }
+}
+
+/// Taux que la stratégie attribue à `channel`, `None` si elle n'a pas d'avis
+fn propose(
+    strategy: &FeeStrategy,
+    inputs: &Inputs,
+    channel: &Channel,
+    current_ppm: u32,
+) -> Option<Proposal> {
+    match (strategy, inputs) {
+        (FeeStrategy::Sparkseer { mode }, Inputs::Suggestions(suggestions)) => {
+            let suggestion = suggestions.get(&channel.remote_pubkey)?;
+            let (fee_rate_ppm, label) = match mode {
+                SparkseerMode::Passive => (suggestion.passive_fee_rate, "passive"),
+                SparkseerMode::Active => (suggestion.active_fee_rate, "active"),
+            };
+            Some(Proposal {
+                fee_rate_ppm,
+                reason: format!("Suggestion Sparkseer {} du pair: {} ppm", label, fee_rate_ppm),
+            })
+        }
+        (FeeStrategy::Balance { min_ppm, max_ppm }, _) if channel.capacity > 0 => {
+            let ratio = channel.local_balance as f64 / channel.capacity as f64;
+            let spread = max_ppm.saturating_sub(*min_ppm) as f64;
+            let fee_rate_ppm = max_ppm.saturating_sub((spread * ratio).round() as u32);
+            Some(Proposal {
+                fee_rate_ppm,
+                reason: format!(
+                    "Part locale de {:.0} %: {} ppm entre {} et {} ppm",
+                    ratio * 100.0,
+                    fee_rate_ppm,
+                    min_ppm,
+                    max_ppm
+                ),
+            })
+        }
+        (
+            FeeStrategy::Volume {
+                min_ppm,
+                max_ppm,
+                step_percent,
+                ..
+            },
+            Inputs::Volumes { previous, current },
+        ) => {
+            let (before, after) = (volume_of(previous, channel), volume_of(current, channel));
+            let step = (current_ppm as u64 * *step_percent as u64 / 100).max(1) as u32;
+            let (fee_rate_ppm, reason) = if before == 0 && after == 0 {
+                (
+                    current_ppm.saturating_sub(step),
+                    "Canal inactif: aucun volume sortant sur les deux fenêtres".to_string(),
+                )
+            } else if after != before {
+                let (fee_rate_ppm, trend) = if after > before {
+                    (current_ppm.saturating_add(step), "en hausse")
+                } else {
+                    (current_ppm.saturating_sub(step), "en baisse")
+                };
+                let reason = format!(
+                    "Volume sortant {}: {} sats puis {} sats",
+                    trend,
+                    before / 1000,
+                    after / 1000
+                );
+                (fee_rate_ppm, reason)
+            } else {
+                return None;
+            };
+            Some(Proposal {
+                fee_rate_ppm: fee_rate_ppm.clamp(*min_ppm, (*max_ppm).max(*min_ppm)),
+                reason,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Calcule les nouveaux taux de frais des canaux actifs et les applique, ou les consigne
+/// seulement en mode `dry_run`.
+///
+/// Un canal n'est modifié que si l'écart atteint `min_step_ppm` et que sa dernière
+/// modification enregistrée date de plus de `cooldown_secs` ; en simulation, les
+/// modifications consignées comptent aussi, pour reproduire le rythme réel.
+#[instrument(skip(service, history, policy, api_key), err)]
+pub async fn run_cycle(
+    service: &LightningService,
+    history: &HistoryStore,
+    node: &str,
+    policy: &FeePolicy,
+    api_key: Option<&str>,
+) -> Result<Vec<FeeChange>> {
+    let now = now();
+    let channels = service.list_channels().await?;
+    let inputs = gather(&policy.strategy, service, history, node, api_key, now).await?;
+
+    let mut changes = Vec::new();
+    for channel in channels.iter().filter(|c| c.status == ChannelStatus::Active) {
+        let Some(current_ppm) = channel.local_policy.as_ref().map(|p| p.fee_rate_ppm) else {
+            continue;
+        };
+        let Some(proposal) = propose(&policy.strategy, &inputs, channel, current_ppm) else {
+            continue;
+        };
+        if proposal.fee_rate_ppm.abs_diff(current_ppm) < policy.min_step_ppm.max(1) {
+            continue;
+        }
+        if let Some(last) = history.last_fee_change(node, &channel.id).await? {
+            if now - last < policy.cooldown_secs as i64 {
+                debug!(channel_id = %channel.id, last, "Frais modifiés trop récemment");
+                continue;
+            }
+        }
+
+        let mut change = FeeChange {
+            timestamp: now,
+            channel_id: channel.id.clone(),
+            remote_pubkey: channel.remote_pubkey.clone(),
+            strategy: policy.strategy.as_str().to_string(),
+            old_fee_rate_ppm: current_ppm,
+            new_fee_rate_ppm: proposal.fee_rate_ppm,
+            reason: proposal.reason,
+            applied: false,
+        };
+        if !policy.dry_run {
+            let update = PolicyUpdate {
+                fee_rate_ppm: Some(change.new_fee_rate_ppm),
+                ..Default::default()
+            };
+            match service.update_channel_policy(&channel.id, &update).await {
+                Ok(Some(_)) => change.applied = true,
+                Ok(None) => continue,
+                Err(e) => {
+                    warn!(channel_id = %channel.id, error = %e, "Frais du canal non modifiés");
+                    continue;
+                }
+            }
+        }
+        info!(
+            channel_id = %change.channel_id,
+            old_fee_rate_ppm = change.old_fee_rate_ppm,
+            new_fee_rate_ppm = change.new_fee_rate_ppm,
+            applied = change.applied,
+            reason = %change.reason,
+            "Nouveau taux de frais"
+        );
+        if let Err(e) = history.record_fee_change(node, change.clone()).await {
+            warn!(error = %e, "Modification de frais non enregistrée");
+        }
+        changes.push(change);
+    }
+    Ok(changes)
+}
+
+/// Applique `policy` au nœud en tâche de fond, toutes les `policy.interval_secs`.
+///
+/// Comme pour le rééquilibrage, la première passe attend une période complète.
+pub fn spawn(
+    node: &str,
+    service: LightningService,
+    history: HistoryStore,
+    policy: FeePolicy,
+    api_key: Option<String>,
+) -> JoinHandle<()> {
+    let node = node.to_string();
+    tokio::spawn(async move {
+        let period = Duration::from_secs(policy.interval_secs.max(60));
+        let mut ticker = tokio::time::interval_at(Instant::now() + period, period);
+        loop {
+            ticker.tick().await;
+            match run_cycle(&service, &history, &node, &policy, api_key.as_deref()).await {
+                Ok(changes) => info!(
+                    node = %node,
+                    count = changes.len(),
+                    dry_run = policy.dry_run,
+                    "Passe du gestionnaire de frais terminée"
+                ),
+                Err(e) => warn!(node = %node, error = %e, "Gestion des frais impossible"),
+            }
+        }
+    })
+}
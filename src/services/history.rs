@@ -0,0 +1,752 @@
+use anyhow::{anyhow, Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+use tracing::{info, instrument, warn};
+
+use crate::models::history::{
+    ChannelForwards, ChannelSnapshot, ForwardsReport, HistoryRange, NodeSnapshot,
+};
+use crate::models::fees::FeeChange;
+use crate::models::lightning::{
+    Channel, ChannelStatus, ForwardingEvent, NodeInfo, TransactionStatus,
+};
+use crate::models::rebalance::{RebalanceAttempt, RebalanceTrigger};
+use crate::services::lightning::LightningService;
+
+/// Schéma de la base, une entrée par version.
+///
+/// Les migrations déjà appliquées sont repérées par `PRAGMA user_version` :
+/// une nouvelle version s'ajoute en fin de liste, sans modifier les précédentes.
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE node_snapshots (
+        node_id TEXT NOT NULL,
+        timestamp INTEGER NOT NULL,
+        pubkey TEXT NOT NULL,
+        alias TEXT NOT NULL,
+        capacity INTEGER NOT NULL,
+        channels INTEGER NOT NULL,
+        local_balance INTEGER NOT NULL,
+        remote_balance INTEGER NOT NULL,
+        stats TEXT,
+        PRIMARY KEY (node_id, timestamp)
+    );
+    CREATE TABLE channel_snapshots (
+        node_id TEXT NOT NULL,
+        channel_id TEXT NOT NULL,
+        timestamp INTEGER NOT NULL,
+        remote_pubkey TEXT NOT NULL,
+        capacity INTEGER NOT NULL,
+        local_balance INTEGER NOT NULL,
+        remote_balance INTEGER NOT NULL,
+        status TEXT NOT NULL,
+        base_fee_msat INTEGER,
+        fee_rate_ppm INTEGER,
+        remote_base_fee_msat INTEGER,
+        remote_fee_rate_ppm INTEGER,
+        PRIMARY KEY (node_id, channel_id, timestamp)
+    );",
+    "CREATE TABLE forwards (
+        id INTEGER PRIMARY KEY,
+        node_id TEXT NOT NULL,
+        timestamp INTEGER NOT NULL,
+        chan_id_in TEXT NOT NULL,
+        chan_id_out TEXT NOT NULL,
+        amt_in_msat INTEGER NOT NULL,
+        amt_out_msat INTEGER NOT NULL,
+        fee_msat INTEGER NOT NULL
+    );
+    CREATE INDEX forwards_by_time ON forwards (node_id, timestamp);
+    CREATE TABLE forward_cursors (
+        node_id TEXT PRIMARY KEY,
+        next_offset INTEGER NOT NULL
+    );",
+    "CREATE TABLE rebalances (
+        id INTEGER PRIMARY KEY,
+        node_id TEXT NOT NULL,
+        timestamp INTEGER NOT NULL,
+        trigger TEXT NOT NULL,
+        outgoing_channel TEXT NOT NULL,
+        incoming_channel TEXT NOT NULL,
+        amount INTEGER NOT NULL,
+        max_fee_ppm INTEGER NOT NULL,
+        status TEXT NOT NULL,
+        fee_msat INTEGER NOT NULL,
+        payment_hash TEXT,
+        failure_reason TEXT
+    );
+    CREATE INDEX rebalances_by_time ON rebalances (node_id, timestamp);",
+    "CREATE TABLE fee_changes (
+        id INTEGER PRIMARY KEY,
+        node_id TEXT NOT NULL,
+        timestamp INTEGER NOT NULL,
+        channel_id TEXT NOT NULL,
+        remote_pubkey TEXT NOT NULL,
+        strategy TEXT NOT NULL,
+        old_fee_rate_ppm INTEGER NOT NULL,
+        new_fee_rate_ppm INTEGER NOT NULL,
+        reason TEXT NOT NULL,
+        applied INTEGER NOT NULL
+    );
+    CREATE INDEX fee_changes_by_channel ON fee_changes (node_id, channel_id, timestamp);
+    CREATE INDEX fee_changes_by_time ON fee_changes (node_id, timestamp);",
+];
+
+/// Taille des pages lues dans l'historique de routage du nœud
+const FORWARDS_PAGE_SIZE: u32 = 1000;
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// Durée d'une fenêtre exprimée en heures (`h`) ou en jours (`d`)
+fn parse_range(range: &str) -> Result<i64, String> {
+    let invalid = || format!("Fenêtre invalide: {} (attendu par exemple 24h ou 7d)", range);
+    let (count, unit) = range.split_at(range.len().saturating_sub(1));
+    let count: i64 = count.parse().map_err(|_| invalid())?;
+    if count <= 0 {
+        return Err(invalid());
+    }
+    match unit {
+        "h" => Ok(count * 3600),
+        "d" => Ok(count * 24 * 3600),
+        _ => Err(invalid()),
+    }
+}
+
+/// Bornes d'une fenêtre : `from` et `to` explicites, sinon `range` terminée maintenant
+pub fn resolve_window(
+    range: &Option<String>,
+    from: Option<i64>,
+    to: Option<i64>,
+    default_range: &str,
+) -> Result<(i64, i64), String> {
+    let to = to.unwrap_or_else(now);
+    let from = match from {
+        Some(from) => from,
+        None => to - parse_range(range.as_deref().unwrap_or(default_range))?,
+    };
+    if from >= to {
+        return Err("Le début de la fenêtre doit précéder sa fin".to_string());
+    }
+    Ok((from, to))
+}
+
+fn migrate(conn: &mut Connection) -> Result<()> {
+    let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration)
+            .with_context(|| format!("Échec de la migration {}", index + 1))?;
+        tx.pragma_update(None, "user_version", index + 1)?;
+        tx.commit()?;
+        info!(version = index + 1, "Migration de l'historique appliquée");
+    }
+    Ok(())
+}
+
+fn status_to_sql(status: ChannelStatus) -> &'static str {
+    match status {
+        ChannelStatus::Active => "active",
+        ChannelStatus::Inactive => "inactive",
+        ChannelStatus::Pending => "pending",
+    }
+}
+
+fn status_from_sql(status: &str) -> ChannelStatus {
+    match status {
+        "active" => ChannelStatus::Active,
+        "pending" => ChannelStatus::Pending,
+        _ => ChannelStatus::Inactive,
+    }
+}
+
+fn payment_status_to_sql(status: TransactionStatus) -> &'static str {
+    match status {
+        TransactionStatus::Completed => "completed",
+        TransactionStatus::Pending => "pending",
+        TransactionStatus::Failed => "failed",
+    }
+}
+
+fn payment_status_from_sql(status: &str) -> TransactionStatus {
+    match status {
+        "completed" => TransactionStatus::Completed,
+        "pending" => TransactionStatus::Pending,
+        _ => TransactionStatus::Failed,
+    }
+}
+
+/// Historique des relevés et des relais des nœuds, conservé dans une base SQLite embarquée.
+///
+/// Les accès à la base sont synchrones et passent par `spawn_blocking`.
+#[derive(Clone)]
+pub struct HistoryStore {
+    conn: Arc<Mutex<Connection>>,
+    /// Période entre deux relevés d'un même nœud
+    interval: Duration,
+}
+
+impl HistoryStore {
+    pub fn open(path: &Path, interval: Duration) -> Result<Self> {
+        let mut conn = Connection::open(path)
+            .with_context(|| format!("Impossible d'ouvrir la base {}", path.display()))?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        migrate(&mut conn)?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+            interval,
+        })
+    }
+
+    async fn with_conn<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Connection) -> Result<T> + Send + 'static,
+    {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = conn
+                .lock()
+                .map_err(|_| anyhow!("Connexion à l'historique indisponible"))?;
+            f(&mut conn)
+        })
+        .await?
+    }
+
+    /// Enregistre un relevé du nœud et de tous ses canaux, en une transaction
+    pub async fn record(
+        &self,
+        node_id: &str,
+        timestamp: i64,
+        info: NodeInfo,
+        channels: Vec<Channel>,
+        stats: Option<Value>,
+    ) -> Result<()> {
+        let node_id = node_id.to_string();
+        self.with_conn(move |conn| {
+            let tx = conn.transaction()?;
+            tx.execute(
+                "INSERT OR REPLACE INTO node_snapshots
+                 (node_id, timestamp, pubkey, alias, capacity, channels,
+                  local_balance, remote_balance, stats)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    node_id,
+                    timestamp,
+                    info.pubkey,
+                    info.alias,
+                    info.capacity as i64,
+                    info.channels,
+                    channels.iter().map(|c| c.local_balance).sum::<u64>() as i64,
+                    channels.iter().map(|c| c.remote_balance).sum::<u64>() as i64,
+                    stats.map(|s| s.to_string()),
+                ],
+            )?;
+            {
+                let mut insert = tx.prepare(
+                    "INSERT OR REPLACE INTO channel_snapshots
+                     (node_id, channel_id, timestamp, remote_pubkey, capacity, local_balance,
+                      remote_balance, status, base_fee_msat, fee_rate_ppm,
+                      remote_base_fee_msat, remote_fee_rate_ppm)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                )?;
+                for channel in &channels {
+                    let local = channel.local_policy.as_ref();
+                    let remote = channel.remote_policy.as_ref();
+                    insert.execute(params![
+                        node_id,
+                        channel.id,
+                        timestamp,
+                        channel.remote_pubkey,
+                        channel.capacity as i64,
+                        channel.local_balance as i64,
+                        channel.remote_balance as i64,
+                        status_to_sql(channel.status),
+                        local.map(|p| p.base_fee_msat as i64),
+                        local.map(|p| p.fee_rate_ppm),
+                        remote.map(|p| p.base_fee_msat as i64),
+                        remote.map(|p| p.fee_rate_ppm),
+                    ])?;
+                }
+            }
+            tx.commit()?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Relevés du nœud sur `range`, regroupés en au plus `range.points` intervalles.
+    ///
+    /// SQLite renvoie les colonnes hors agrégat de la ligne retenue par `MAX` :
+    /// chaque point porte ainsi les statistiques du dernier relevé de son intervalle.
+    pub async fn node_history(
+        &self,
+        node_id: &str,
+        range: HistoryRange,
+    ) -> Result<Vec<NodeSnapshot>> {
+        let node_id = node_id.to_string();
+        self.with_conn(move |conn| {
+            let mut query = conn.prepare(
+                "SELECT MAX(timestamp), CAST(AVG(capacity) AS INTEGER), channels,
+                        CAST(AVG(local_balance) AS INTEGER), CAST(AVG(remote_balance) AS INTEGER),
+                        stats
+                 FROM node_snapshots
+                 WHERE node_id = ?1 AND timestamp BETWEEN ?2 AND ?3
+                 GROUP BY (timestamp - ?2) / ?4
+                 ORDER BY 1",
+            )?;
+            let rows = query.query_map(
+                params![node_id, range.from, range.to, range.bucket()],
+                |row| {
+                    let stats: Option<String> = row.get(5)?;
+                    Ok(NodeSnapshot {
+                        timestamp: row.get(0)?,
+                        capacity: row.get::<_, i64>(1)? as u64,
+                        channels: row.get(2)?,
+                        local_balance: row.get::<_, i64>(3)? as u64,
+                        remote_balance: row.get::<_, i64>(4)? as u64,
+                        stats: stats.and_then(|s| serde_json::from_str(&s).ok()),
+                    })
+                },
+            )?;
+            Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+        })
+        .await
+    }
+
+    /// Relevés d'un canal sur `range`, regroupés comme ceux du nœud.
+    ///
+    /// `None` si le canal n'a jamais été relevé pour ce nœud.
+    pub async fn channel_history(
+        &self,
+        node_id: &str,
+        channel_id: &str,
+        range: HistoryRange,
+    ) -> Result<Option<Vec<ChannelSnapshot>>> {
+        let node_id = node_id.to_string();
+        let channel_id = channel_id.to_string();
+        self.with_conn(move |conn| {
+            let known = conn
+                .query_row(
+                    "SELECT 1 FROM channel_snapshots
+                     WHERE node_id = ?1 AND channel_id = ?2 LIMIT 1",
+                    params![node_id, channel_id],
+                    |_| Ok(()),
+                )
+                .optional()?;
+            if known.is_none() {
+                return Ok(None);
+            }
+
+            let mut query = conn.prepare(
+                "SELECT MAX(timestamp), CAST(AVG(capacity) AS INTEGER),
+                        CAST(AVG(local_balance) AS INTEGER), CAST(AVG(remote_balance) AS INTEGER),
+                        status, base_fee_msat, fee_rate_ppm,
+                        remote_base_fee_msat, remote_fee_rate_ppm
+                 FROM channel_snapshots
+                 WHERE node_id = ?1 AND channel_id = ?2 AND timestamp BETWEEN ?3 AND ?4
+                 GROUP BY (timestamp - ?3) / ?5
+                 ORDER BY 1",
+            )?;
+            let rows = query.query_map(
+                params![node_id, channel_id, range.from, range.to, range.bucket()],
+                |row| {
+                    let status: String = row.get(4)?;
+                    Ok(ChannelSnapshot {
+                        timestamp: row.get(0)?,
+                        capacity: row.get::<_, i64>(1)? as u64,
+                        local_balance: row.get::<_, i64>(2)? as u64,
+                        remote_balance: row.get::<_, i64>(3)? as u64,
+                        status: status_from_sql(&status),
+                        base_fee_msat: row.get::<_, Option<i64>>(5)?.map(|v| v as u64),
+                        fee_rate_ppm: row.get(6)?,
+                        remote_base_fee_msat: row.get::<_, Option<i64>>(7)?.map(|v| v as u64),
+                        remote_fee_rate_ppm: row.get(8)?,
+                    })
+                },
+            )?;
+            Ok(Some(rows.collect::<rusqlite::Result<Vec<_>>>()?))
+        })
+        .await
+    }
+
+    /// Solde local moyen de chaque canal relevé entre `from` et `to`
+    pub async fn average_local_balances(
+        &self,
+        node_id: &str,
+        from: i64,
+        to: i64,
+    ) -> Result<HashMap<String, u64>> {
+        let node_id = node_id.to_string();
+        self.with_conn(move |conn| {
+            let mut query = conn.prepare(
+                "SELECT channel_id, CAST(AVG(local_balance) AS INTEGER)
+                 FROM channel_snapshots
+                 WHERE node_id = ?1 AND timestamp BETWEEN ?2 AND ?3
+                 GROUP BY channel_id",
+            )?;
+            let balances = query
+                .query_map(params![node_id, from, to], |row| {
+                    Ok((row.get(0)?, row.get::<_, i64>(1)? as u64))
+                })?
+                .collect::<rusqlite::Result<HashMap<_, _>>>()?;
+            Ok(balances)
+        })
+        .await
+    }
+
+    /// Position de reprise de l'historique de routage du nœud
+    async fn forward_cursor(&self, node_id: &str) -> Result<u64> {
+        let node_id = node_id.to_string();
+        self.with_conn(move |conn| {
+            let offset: Option<i64> = conn
+                .query_row(
+                    "SELECT next_offset FROM forward_cursors WHERE node_id = ?1",
+                    params![node_id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            Ok(offset.unwrap_or(0) as u64)
+        })
+        .await
+    }
+
+    /// Enregistre une page de relais et avance le curseur dans la même transaction :
+    /// une page n'est jamais enregistrée deux fois, même après un redémarrage.
+    async fn store_forwards(
+        &self,
+        node_id: &str,
+        events: Vec<ForwardingEvent>,
+        next_offset: u64,
+    ) -> Result<()> {
+        let node_id = node_id.to_string();
+        self.with_conn(move |conn| {
+            let tx = conn.transaction()?;
+            {
+                let mut insert = tx.prepare(
+                    "INSERT INTO forwards
+                     (node_id, timestamp, chan_id_in, chan_id_out,
+                      amt_in_msat, amt_out_msat, fee_msat)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                )?;
+                for event in &events {
+                    insert.execute(params![
+                        node_id,
+                        event.timestamp,
+                        event.chan_id_in,
+                        event.chan_id_out,
+                        event.amt_in_msat as i64,
+                        event.amt_out_msat as i64,
+                        event.fee_msat as i64,
+                    ])?;
+                }
+            }
+            tx.execute(
+                "INSERT INTO forward_cursors (node_id, next_offset) VALUES (?1, ?2)
+                 ON CONFLICT (node_id) DO UPDATE SET next_offset = excluded.next_offset",
+                params![node_id, next_offset as i64],
+            )?;
+            tx.commit()?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Lit l'historique de routage du nœud depuis le dernier curseur enregistré
+    #[instrument(skip(self, service), err)]
+    async fn ingest_forwards(&self, node_id: &str, service: &LightningService) -> Result<()> {
+        let mut offset = self.forward_cursor(node_id).await?;
+        let mut total = 0;
+        loop {
+            // Curseur absolu : l'historique est toujours lu depuis son origine
+            let page = service
+                .forwarding_history(0, offset, FORWARDS_PAGE_SIZE)
+                .await?;
+            if page.next_offset <= offset {
+                break;
+            }
+            total += page.events.len();
+            self.store_forwards(node_id, page.events, page.next_offset)
+                .await?;
+            offset = page.next_offset;
+        }
+        if total > 0 {
+            info!(count = total, "Relais enregistrés");
+        }
+        Ok(())
+    }
+
+    /// Relais du nœud entre `from` et `to`, éventuellement limités à ceux qui
+    /// empruntent `channel`, avec les totaux de chaque canal.
+    pub async fn forwards(
+        &self,
+        node_id: &str,
+        from: i64,
+        to: i64,
+        channel: Option<String>,
+        limit: u32,
+    ) -> Result<ForwardsReport> {
+        let node_id = node_id.to_string();
+        self.with_conn(move |conn| {
+            let filter = "node_id = ?1 AND timestamp BETWEEN ?2 AND ?3
+                          AND (?4 IS NULL OR chan_id_in = ?4 OR chan_id_out = ?4)";
+            let (count, total_fee_msat, total_amt_out_msat): (i64, i64, i64) = conn.query_row(
+                &format!(
+                    "SELECT COUNT(*), COALESCE(SUM(fee_msat), 0), COALESCE(SUM(amt_out_msat), 0)
+                     FROM forwards WHERE {}",
+                    filter
+                ),
+                params![node_id, from, to, channel],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )?;
+
+            let mut query = conn.prepare(&format!(
+                "SELECT timestamp, chan_id_in, chan_id_out, amt_in_msat, amt_out_msat, fee_msat
+                 FROM forwards WHERE {}
+                 ORDER BY timestamp DESC, id DESC LIMIT ?5",
+                filter
+            ))?;
+            let forwards = query
+                .query_map(params![node_id, from, to, channel, limit], |row| {
+                    Ok(ForwardingEvent {
+                        timestamp: row.get(0)?,
+                        chan_id_in: row.get(1)?,
+                        chan_id_out: row.get(2)?,
+                        amt_in_msat: row.get::<_, i64>(3)? as u64,
+                        amt_out_msat: row.get::<_, i64>(4)? as u64,
+                        fee_msat: row.get::<_, i64>(5)? as u64,
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+
+            // Chaque relais compte pour son canal d'entrée et pour son canal de sortie
+            let mut query = conn.prepare(
+                "SELECT channel_id, SUM(forwards_in), SUM(forwards_out), SUM(amt_in),
+                        SUM(amt_out), SUM(fee_out), SUM(fee_in)
+                 FROM (
+                     SELECT chan_id_in AS channel_id, 1 AS forwards_in, 0 AS forwards_out,
+                            amt_in_msat AS amt_in, 0 AS amt_out, 0 AS fee_out,
+                            fee_msat AS fee_in
+                     FROM forwards WHERE node_id = ?1 AND timestamp BETWEEN ?2 AND ?3
+                     UNION ALL
+                     SELECT chan_id_out, 0, 1, 0, amt_out_msat, fee_msat, 0
+                     FROM forwards WHERE node_id = ?1 AND timestamp BETWEEN ?2 AND ?3
+                 )
+                 WHERE ?4 IS NULL OR channel_id = ?4
+                 GROUP BY channel_id
+                 ORDER BY SUM(fee_out) DESC, channel_id",
+            )?;
+            let channels = query
+                .query_map(params![node_id, from, to, channel], |row| {
+                    Ok(ChannelForwards {
+                        channel_id: row.get(0)?,
+                        forwards_in: row.get::<_, i64>(1)? as u64,
+                        forwards_out: row.get::<_, i64>(2)? as u64,
+                        amt_in_msat: row.get::<_, i64>(3)? as u64,
+                        amt_out_msat: row.get::<_, i64>(4)? as u64,
+                        fee_msat: row.get::<_, i64>(5)? as u64,
+                        inbound_fee_msat: row.get::<_, i64>(6)? as u64,
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+
+            Ok(ForwardsReport {
+                from,
+                to,
+                count: count as u64,
+                total_fee_msat: total_fee_msat as u64,
+                total_amt_out_msat: total_amt_out_msat as u64,
+                forwards,
+                channels,
+            })
+        })
+        .await
+    }
+
+    /// Enregistre une tentative de rééquilibrage, réussie ou non
+    pub async fn record_rebalance(&self, node_id: &str, attempt: RebalanceAttempt) -> Result<()> {
+        let node_id = node_id.to_string();
+        self.with_conn(move |conn| {
+            conn.execute(
+                "INSERT INTO rebalances
+                 (node_id, timestamp, trigger, outgoing_channel, incoming_channel, amount,
+                  max_fee_ppm, status, fee_msat, payment_hash, failure_reason)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                params![
+                    node_id,
+                    attempt.timestamp,
+                    attempt.trigger.as_str(),
+                    attempt.outgoing_channel,
+                    attempt.incoming_channel,
+                    attempt.amount as i64,
+                    attempt.max_fee_ppm,
+                    payment_status_to_sql(attempt.status),
+                    attempt.fee_msat as i64,
+                    attempt.payment_hash,
+                    attempt.failure_reason,
+                ],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Tentatives de rééquilibrage enregistrées sur une fenêtre, les plus récentes d'abord
+    pub async fn rebalances(
+        &self,
+        node_id: &str,
+        from: i64,
+        to: i64,
+    ) -> Result<Vec<RebalanceAttempt>> {
+        let node_id = node_id.to_string();
+        self.with_conn(move |conn| {
+            let mut query = conn.prepare(
+                "SELECT timestamp, trigger, outgoing_channel, incoming_channel, amount,
+                        max_fee_ppm, status, fee_msat, payment_hash, failure_reason
+                 FROM rebalances
+                 WHERE node_id = ?1 AND timestamp BETWEEN ?2 AND ?3
+                 ORDER BY timestamp DESC, id DESC",
+            )?;
+            let attempts = query
+                .query_map(params![node_id, from, to], |row| {
+                    let trigger: String = row.get(1)?;
+                    let status: String = row.get(6)?;
+                    Ok(RebalanceAttempt {
+                        timestamp: row.get(0)?,
+                        trigger: if trigger == "policy" {
+                            RebalanceTrigger::Policy
+                        } else {
+                            RebalanceTrigger::Manual
+                        },
+                        outgoing_channel: row.get(2)?,
+                        incoming_channel: row.get(3)?,
+                        amount: row.get::<_, i64>(4)? as u64,
+                        max_fee_ppm: row.get(5)?,
+                        status: payment_status_from_sql(&status),
+                        fee_msat: row.get::<_, i64>(7)? as u64,
+                        payment_hash: row.get(8)?,
+                        failure_reason: row.get(9)?,
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(attempts)
+        })
+        .await
+    }
+
+    /// Enregistre une modification de frais, appliquée ou simulée
+    pub async fn record_fee_change(&self, node_id: &str, change: FeeChange) -> Result<()> {
+        let node_id = node_id.to_string();
+        self.with_conn(move |conn| {
+            conn.execute(
+                "INSERT INTO fee_changes
+                 (node_id, timestamp, channel_id, remote_pubkey, strategy, old_fee_rate_ppm,
+                  new_fee_rate_ppm, reason, applied)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    node_id,
+                    change.timestamp,
+                    change.channel_id,
+                    change.remote_pubkey,
+                    change.strategy,
+                    change.old_fee_rate_ppm,
+                    change.new_fee_rate_ppm,
+                    change.reason,
+                    change.applied,
+                ],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Date de la dernière modification de frais enregistrée pour `channel_id`
+    pub async fn last_fee_change(&self, node_id: &str, channel_id: &str) -> Result<Option<i64>> {
+        let (node_id, channel_id) = (node_id.to_string(), channel_id.to_string());
+        self.with_conn(move |conn| {
+            let timestamp = conn.query_row(
+                "SELECT MAX(timestamp) FROM fee_changes WHERE node_id = ?1 AND channel_id = ?2",
+                params![node_id, channel_id],
+                |row| row.get(0),
+            )?;
+            Ok(timestamp)
+        })
+        .await
+    }
+
+    /// Modifications de frais enregistrées sur une fenêtre, les plus récentes d'abord
+    pub async fn fee_changes(&self, node_id: &str, from: i64, to: i64) -> Result<Vec<FeeChange>> {
+        let node_id = node_id.to_string();
+        self.with_conn(move |conn| {
+            let mut query = conn.prepare(
+                "SELECT timestamp, channel_id, remote_pubkey, strategy, old_fee_rate_ppm,
+                        new_fee_rate_ppm, reason, applied
+                 FROM fee_changes
+                 WHERE node_id = ?1 AND timestamp BETWEEN ?2 AND ?3
+                 ORDER BY timestamp DESC, id DESC",
+            )?;
+            let changes = query
+                .query_map(params![node_id, from, to], |row| {
+                    Ok(FeeChange {
+                        timestamp: row.get(0)?,
+                        channel_id: row.get(1)?,
+                        remote_pubkey: row.get(2)?,
+                        strategy: row.get(3)?,
+                        old_fee_rate_ppm: row.get(4)?,
+                        new_fee_rate_ppm: row.get(5)?,
+                        reason: row.get(6)?,
+                        applied: row.get(7)?,
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(changes)
+        })
+        .await
+    }
+
+    /// Relève un nœud : informations générales, canaux et statistiques Sparkseer
+    #[instrument(skip(self, service), err)]
+    async fn snapshot(&self, node_id: &str, service: &LightningService) -> Result<()> {
+        let info = service.get_node_info().await?;
+        let channels = service.list_channels().await?;
+        // Les statistiques Sparkseer complètent le relevé sans le rendre impossible
+        let stats = match service.get_node_stats(&info.pubkey).await {
+            Ok(stats) => Some(stats),
+            Err(e) => {
+                warn!(error = %e, "Statistiques Sparkseer indisponibles");
+                None
+            }
+        };
+        let count = channels.len();
+        self.record(node_id, now(), info, channels, stats).await?;
+        info!(channels = count, "Relevé du nœud enregistré");
+        Ok(())
+    }
+
+    /// Relève `service` et enregistre ses nouveaux relais en tâche de fond,
+    /// toutes les `interval`
+    pub fn spawn(&self, node: &str, service: LightningService) -> JoinHandle<()> {
+        let store = self.clone();
+        let node = node.to_string();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(store.interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = store.ingest_forwards(&node, &service).await {
+                    warn!(node = %node, error = %e, "Historique de routage illisible");
+                }
+                if let Err(e) = store.snapshot(&node, &service).await {
+                    warn!(node = %node, error = %e, "Relevé du nœud impossible");
+                }
+            }
+        })
+    }
+}
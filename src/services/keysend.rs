@@ -0,0 +1,49 @@
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+/// Enregistrement portant la preimage d'un keysend
+pub const KEYSEND_PREIMAGE_RECORD: u64 = 5_482_373_484;
+/// Message texte libre (convention WhatSat, reprise par la plupart des portefeuilles)
+pub const MESSAGE_RECORD: u64 = 34_349_334;
+/// Boostagram Podcasting 2.0 : objet JSON dont le champ `message` est affiché
+pub const BOOSTAGRAM_RECORD: u64 = 7_629_169;
+/// Premier type disponible pour un enregistrement personnalisé (BOLT 1)
+pub const MIN_CUSTOM_RECORD: u64 = 65_536;
+
+/// Tire une preimage aléatoire et renvoie `(preimage, payment_hash)`
+pub fn new_preimage() -> ([u8; 32], [u8; 32]) {
+    let preimage: [u8; 32] = rand::random();
+    (preimage, Sha256::digest(preimage).into())
+}
+
+/// Convertit les enregistrements reçus d'un backend pour `Transaction::custom_records`.
+///
+/// La preimage d'un keysend n'est pas recopiée.
+pub fn custom_records<I, V>(records: I) -> BTreeMap<u64, String>
+where
+    I: IntoIterator<Item = (u64, V)>,
+    V: AsRef<[u8]>,
+{
+    records
+        .into_iter()
+        .filter(|(record, _)| *record != KEYSEND_PREIMAGE_RECORD)
+        .map(|(record, value)| (record, hex::encode(value)))
+        .collect()
+}
+
+/// Message lisible joint au paiement : texte libre, ou à défaut message d'un boostagram
+pub fn decode_message(records: &BTreeMap<u64, String>) -> Option<String> {
+    let utf8 = |record: u64| {
+        records
+            .get(&record)
+            .and_then(|value| hex::decode(value).ok())
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+    };
+    utf8(MESSAGE_RECORD).or_else(|| {
+        utf8(BOOSTAGRAM_RECORD)
+            .and_then(|json| serde_json::from_str::<Value>(&json).ok())
+            .and_then(|boost| boost["message"].as_str().map(str::to_string))
+    })
+}
@@ -1,45 +1,81 @@
 use crate::models::lightning::{
-    Channel, NodeInfo, Transaction,
-    ChannelStatus, TransactionType, TransactionStatus
+    BatchOpenRequest, Channel, ChannelCloseResult, ChannelOpenResult, ChannelPolicy,
+    ChannelRequest, CloseEstimate, CloseOptions, Invoice, Network, NodeInfo, Offer, PaymentOptions,
+    PendingChannels, PsbtFunding, PaymentResult, PolicyChange, PolicyUpdate, RouteQuote, Transaction,
 };
-use anyhow::Result;
+use crate::models::onchain::{
+    AddressType, OnchainAddress, OnchainSendRequest, OnchainSendResult, Utxo, WalletBalance,
+};
+use crate::models::fees::SuggestedFees;
+use crate::models::pnl::ChannelCost;
+use crate::services::backends::{
+    BackendKind, ForwardingPage, NodeBackend, DEFAULT_CLOSE_TARGET_CONF,
+};
+use crate::services::events::EventSink;
+use crate::services::keysend::MESSAGE_RECORD;
+use anyhow::{Context, Result};
 use reqwest::Client;
-use serde_json::{json, Value};
-use anyhow::Error;
-use tracing::{instrument, info, error};
+use serde_json::Value;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+use tracing::{instrument, info, error, warn};
+
+/// Taille d'une fermeture coopérative : entrée 2-sur-2 et deux sorties P2WPKH
+const COOPERATIVE_CLOSE_VSIZE: u64 = 169;
+/// Taille de la transaction récupérant notre sortie après une fermeture forcée ;
+/// les frais de la transaction d'engagement sont déjà déduits de notre solde
+const FORCE_CLOSE_SWEEP_VSIZE: u64 = 122;
 
 #[derive(Clone)]
 pub struct LightningService {
     client: Client,
-    base_url: String,
+    backend: Arc<dyn NodeBackend>,
+    network: Arc<OnceCell<Option<Network>>>,
     sparkseer_url: String,
+    /// URL de 1ML, configurée mais pas encore interrogée
+    #[allow(dead_code)]
     ml_url: String,
 }
 
 impl LightningService {
-    pub fn new(base_url: String, sparkseer_url: String, ml_url: String) -> Self {
-        info!(base_url = %base_url, "Création du service Lightning");
+    pub fn new(backend: Arc<dyn NodeBackend>, sparkseer_url: String, ml_url: String) -> Self {
+        info!(backend = %backend.kind(), "Création du service Lightning");
         Self {
             client: Client::new(),
-            base_url,
+            backend,
+            network: Arc::new(OnceCell::new()),
             sparkseer_url,
             ml_url,
         }
     }
 
+    /// Type de nœud derrière le service
+    pub fn backend_kind(&self) -> BackendKind {
+        self.backend.kind()
+    }
+
+    /// Réseau du nœud, interrogé au premier appel puis conservé
+    pub async fn network(&self) -> Result<Option<Network>> {
+        let network = self
+            .network
+            .get_or_try_init(|| async { Ok::<_, anyhow::Error>(self.get_node_info().await?.network) })
+            .await?;
+        Ok(*network)
+    }
+
     #[instrument(skip(self), err)]
     pub async fn get_node_info(&self) -> Result<NodeInfo> {
         info!("Récupération des informations du nœud");
-        let url = format!("{}/v1/getinfo", self.base_url);
-        match self.client.get(&url).send().await {
-            Ok(response) => {
-                let info = response.json::<NodeInfo>().await?;
-                info!(pubkey = %info.identity_pubkey, "Informations du nœud récupérées");
+        match self.backend.get_node_info().await {
+            Ok(info) => {
+                info!(pubkey = %info.pubkey, "Informations du nœud récupérées");
                 Ok(info)
             }
             Err(e) => {
                 error!(error = %e, "Erreur lors de la récupération des informations du nœud");
-                Err(e.into())
+                Err(e)
             }
         }
     }
@@ -47,35 +83,90 @@ impl LightningService {
     #[instrument(skip(self), err)]
     pub async fn list_channels(&self) -> Result<Vec<Channel>> {
         info!("Récupération de la liste des canaux");
-        let url = format!("{}/v1/channels", self.base_url);
-        match self.client.get(&url).send().await {
-            Ok(response) => {
-                let channels = response.json::<Vec<Channel>>().await?;
+        match self.backend.list_channels().await {
+            Ok(channels) => {
                 info!(count = channels.len(), "Canaux récupérés");
                 Ok(channels)
             }
             Err(e) => {
                 error!(error = %e, "Erreur lors de la récupération des canaux");
-                Err(e.into())
+                Err(e)
             }
         }
     }
 
+    #[instrument(skip(self), err)]
+    pub async fn list_pending_channels(&self) -> Result<PendingChannels> {
+        let pending = self.backend.list_pending_channels().await?;
+        info!(
+            count = pending.channels.len(),
+            block_height = pending.block_height,
+            "Canaux en attente récupérés"
+        );
+        Ok(pending)
+    }
+
     #[instrument(skip(self), err)]
     pub async fn list_transactions(&self) -> Result<Vec<Transaction>> {
         info!("Récupération de la liste des transactions");
-        let url = format!("{}/v1/transactions", self.base_url);
-        match self.client.get(&url).send().await {
-            Ok(response) => {
-                let transactions = response.json::<Vec<Transaction>>().await?;
-                info!(count = transactions.len(), "Transactions récupérées");
-                Ok(transactions)
-            }
+        let mut transactions = match self.backend.list_transactions().await {
+            Ok(transactions) => transactions,
             Err(e) => {
                 error!(error = %e, "Erreur lors de la récupération des transactions");
-                Err(e.into())
+                return Err(e);
             }
+        };
+        // Les transactions on-chain complètent la liste sans la rendre indisponible
+        match self.backend.list_onchain_transactions().await {
+            Ok(onchain) => transactions.extend(onchain),
+            Err(e) => warn!(error = %e, "Transactions on-chain indisponibles"),
         }
+        transactions.sort_by_key(|t| Reverse(t.timestamp));
+        info!(count = transactions.len(), "Transactions récupérées");
+        Ok(transactions)
+    }
+
+    /// Coûts imputés aux canaux depuis `since`, pour le compte de résultat
+    #[instrument(skip(self), err)]
+    pub async fn channel_costs(&self, since: i64) -> Result<Vec<ChannelCost>> {
+        let costs = self.backend.channel_costs(since).await?;
+        info!(count = costs.len(), "Coûts des canaux récupérés");
+        Ok(costs)
+    }
+
+    #[instrument(skip(self), err)]
+    pub async fn wallet_balance(&self) -> Result<WalletBalance> {
+        self.backend.wallet_balance().await
+    }
+
+    #[instrument(skip(self), err)]
+    pub async fn list_utxos(&self) -> Result<Vec<Utxo>> {
+        let utxos = self.backend.list_utxos().await?;
+        info!(count = utxos.len(), "UTXO récupérés");
+        Ok(utxos)
+    }
+
+    #[instrument(skip(self), err)]
+    pub async fn new_address(&self, address_type: AddressType) -> Result<OnchainAddress> {
+        let address = self.backend.new_address(address_type).await?;
+        info!(address = %address, "Nouvelle adresse générée");
+        Ok(OnchainAddress {
+            address,
+            address_type,
+        })
+    }
+
+    #[instrument(skip(self, request), err)]
+    pub async fn send_onchain(&self, request: &OnchainSendRequest) -> Result<OnchainSendResult> {
+        info!(
+            address = %request.address,
+            amount = ?request.amount,
+            send_all = request.send_all,
+            "Envoi on-chain"
+        );
+        let txid = self.backend.send_onchain(request).await?;
+        info!(txid = %txid, "Transaction on-chain diffusée");
+        Ok(OnchainSendResult { txid })
     }
 
     #[instrument(skip(self), err)]
@@ -95,54 +186,352 @@ impl LightningService {
         }
     }
 
-    pub async fn send_payment(&self, payment_request: &str) -> Result<Value, Error> {
-        let response = self.client
-            .post(format!("{}/v1/channels/transactions", self.base_url))
-            .json(&json!({
-                "payment_request": payment_request,
-            }))
+    /// Statistiques Sparkseer d'un nœud (rangs de centralité, frais médians, etc.)
+    #[instrument(skip(self), err)]
+    pub async fn get_node_stats(&self, pubkey: &str) -> Result<Value> {
+        let url = format!("{}/v1/node/current-stats/{}", self.sparkseer_url, pubkey);
+        let stats = self
+            .client
+            .get(&url)
             .send()
             .await?
-            .json()
+            .error_for_status()?
+            .json::<Value>()
             .await?;
-        Ok(response)
+        Ok(stats)
     }
 
-    pub async fn create_invoice(&self, amount: u64, description: &str) -> Result<Value, Error> {
-        let response = self.client
-            .post(format!("{}/v1/invoices", self.base_url))
-            .json(&json!({
-                "amount": amount,
-                "description": description,
-            }))
+    /// Frais suggérés par Sparkseer pour les pairs du nœud associé à `api_key`
+    #[instrument(skip(self, api_key), err)]
+    pub async fn suggested_fees(&self, api_key: &str) -> Result<SuggestedFees> {
+        let url = format!("{}/v1/services/suggested-fees", self.sparkseer_url);
+        let fees = self
+            .client
+            .get(&url)
+            .header("api-key", api_key)
             .send()
             .await?
-            .json()
+            .error_for_status()?
+            .json::<SuggestedFees>()
             .await?;
-        Ok(response)
+        Ok(fees)
     }
 
-    pub async fn open_channel(&self, pubkey: &str, amount: u64) -> Result<Value, Error> {
-        let response = self.client
-            .post(format!("{}/v1/channels", self.base_url))
-            .json(&json!({
-                "node_pubkey": pubkey,
-                "amount": amount,
-            }))
-            .send()
-            .await?
-            .json()
+    #[instrument(skip(self), err)]
+    pub async fn forwarding_history(
+        &self,
+        start_time: i64,
+        offset: u64,
+        limit: u32,
+    ) -> Result<ForwardingPage> {
+        self.backend.forwarding_history(start_time, offset, limit).await
+    }
+
+    /// Devis de frais pour envoyer `amount` sats à `destination`, sans rien payer
+    #[instrument(skip(self), err)]
+    pub async fn query_routes(&self, destination: &str, amount: u64) -> Result<Vec<RouteQuote>> {
+        info!("Recherche de routes");
+        let routes = self.backend.query_routes(destination, amount).await?;
+        info!(count = routes.len(), "Routes trouvées");
+        Ok(routes)
+    }
+
+    /// Suit les événements du nœud ; ne rend la main qu'en cas d'erreur du flux
+    pub async fn subscribe_events(&self, sink: &EventSink) -> Result<()> {
+        self.backend.subscribe_events(sink).await
+    }
+
+    #[instrument(skip(self), err)]
+    pub async fn send_payment(
+        &self,
+        payment_request: &str,
+        options: &PaymentOptions,
+    ) -> Result<PaymentResult> {
+        info!("Envoi d'un paiement");
+        let result = self.backend.send_payment(payment_request, options).await?;
+        info!(
+            payment_hash = %result.payment_hash,
+            status = ?result.status,
+            attempts = result.attempts,
+            "Paiement traité"
+        );
+        Ok(result)
+    }
+
+    /// Paie une facture de notre propre nœud, pour un rééquilibrage circulaire
+    #[instrument(skip(self), err)]
+    pub async fn send_circular_payment(
+        &self,
+        payment_request: &str,
+        options: &PaymentOptions,
+    ) -> Result<PaymentResult> {
+        info!("Envoi d'un paiement circulaire");
+        let result = self
+            .backend
+            .send_circular_payment(payment_request, options)
             .await?;
-        Ok(response)
+        info!(
+            payment_hash = %result.payment_hash,
+            status = ?result.status,
+            attempts = result.attempts,
+            "Paiement circulaire traité"
+        );
+        Ok(result)
     }
 
-    pub async fn close_channel(&self, channel_id: &str) -> Result<Value, Error> {
-        let response = self.client
-            .delete(format!("{}/v1/channels/{}", self.base_url, channel_id))
-            .send()
-            .await?
-            .json()
+    /// Paiement spontané de `amount` sats vers `pubkey` ; `message` est transmis
+    /// dans l'enregistrement texte habituel des portefeuilles.
+    #[instrument(skip(self, custom_records, message), err)]
+    pub async fn send_keysend(
+        &self,
+        pubkey: &str,
+        amount: u64,
+        mut custom_records: BTreeMap<u64, Vec<u8>>,
+        message: Option<&str>,
+    ) -> Result<PaymentResult> {
+        info!(pubkey = %pubkey, amount = amount, records = custom_records.len(), "Envoi d'un keysend");
+        if let Some(message) = message {
+            custom_records.insert(MESSAGE_RECORD, message.as_bytes().to_vec());
+        }
+        let result = self
+            .backend
+            .send_keysend(pubkey, amount, &custom_records)
             .await?;
-        Ok(response)
+        info!(payment_hash = %result.payment_hash, status = ?result.status, "Keysend traité");
+        Ok(result)
+    }
+
+    #[instrument(skip(self), err)]
+    pub async fn create_invoice(
+        &self,
+        amount: u64,
+        description: &str,
+        expiry: Option<u64>,
+    ) -> Result<Invoice> {
+        info!(amount = amount, "Création d'une facture");
+        let invoice = self.backend.create_invoice(amount, description, expiry).await?;
+        info!(payment_hash = %invoice.payment_hash, "Facture créée");
+        Ok(invoice)
+    }
+
+    #[instrument(skip(self), err)]
+    pub async fn create_offer(&self, amount: Option<u64>, description: &str) -> Result<Offer> {
+        info!(amount = ?amount, "Création d'une offre BOLT12");
+        let offer = self.backend.create_offer(amount, description).await?;
+        info!(offer_id = ?offer.offer_id, "Offre créée");
+        Ok(offer)
+    }
+
+    #[instrument(skip(self), err)]
+    pub async fn pay_offer(
+        &self,
+        offer: &str,
+        amount: Option<u64>,
+        payer_note: Option<&str>,
+    ) -> Result<PaymentResult> {
+        info!(amount = ?amount, "Paiement d'une offre BOLT12");
+        let result = self.backend.pay_offer(offer, amount, payer_note).await?;
+        info!(payment_hash = %result.payment_hash, status = ?result.status, "Paiement traité");
+        Ok(result)
+    }
+
+    #[instrument(skip(self), err)]
+    pub async fn open_channel(&self, pubkey: &str, amount: u64) -> Result<ChannelOpenResult> {
+        info!(pubkey = %pubkey, amount = amount, "Ouverture d'un canal");
+        let result = self.backend.open_channel(pubkey, amount).await?;
+        info!(funding_txid = %result.funding_txid, "Canal en cours d'ouverture");
+        Ok(result)
+    }
+
+    #[instrument(skip(self, request), err)]
+    pub async fn open_channels(
+        &self,
+        request: &BatchOpenRequest,
+    ) -> Result<Vec<ChannelOpenResult>> {
+        info!(count = request.channels.len(), "Ouverture groupée de canaux");
+        let results = self.backend.open_channels(request).await?;
+        let funding_txid = results.first().map(|r| &r.funding_txid);
+        info!(funding_txid = ?funding_txid, "Canaux en cours d'ouverture");
+        Ok(results)
+    }
+
+    #[instrument(skip(self, request), err)]
+    pub async fn start_psbt_funding(&self, request: &ChannelRequest) -> Result<PsbtFunding> {
+        info!(
+            pubkey = %request.pubkey,
+            amount = request.amount,
+            "Ouverture d'un canal financé par PSBT"
+        );
+        let funding = self.backend.start_psbt_funding(request).await?;
+        info!(funding_id = %funding.funding_id, "Financement PSBT en attente");
+        Ok(funding)
+    }
+
+    #[instrument(skip(self, psbt), err)]
+    pub async fn verify_psbt_funding(&self, funding_id: &str, psbt: &str) -> Result<()> {
+        self.backend.verify_psbt_funding(funding_id, psbt).await?;
+        info!(funding_id = %funding_id, "PSBT de financement vérifiée");
+        Ok(())
+    }
+
+    #[instrument(skip(self, signed_psbt), err)]
+    pub async fn finalize_psbt_funding(
+        &self,
+        funding_id: &str,
+        signed_psbt: &str,
+    ) -> Result<ChannelOpenResult> {
+        let result = self
+            .backend
+            .finalize_psbt_funding(funding_id, signed_psbt)
+            .await?;
+        info!(funding_txid = %result.funding_txid, "Transaction de financement diffusée");
+        Ok(result)
+    }
+
+    #[instrument(skip(self), err)]
+    pub async fn cancel_psbt_funding(&self, funding_id: &str) -> Result<()> {
+        self.backend.cancel_psbt_funding(funding_id).await?;
+        info!(funding_id = %funding_id, "Financement PSBT annulé");
+        Ok(())
     }
-} 
\ No newline at end of file
+
+    #[instrument(skip(self), err)]
+    pub async fn close_channel(
+        &self,
+        channel_id: &str,
+        options: &CloseOptions,
+    ) -> Result<ChannelCloseResult> {
+        info!(channel_id = %channel_id, force = options.force, "Fermeture d'un canal");
+        // Le délai est lu avant la fermeture : le canal quitte ensuite la liste
+        let csv_delay = if options.force {
+            self.find_channel(channel_id).await?.and_then(|c| c.csv_delay)
+        } else {
+            None
+        };
+        let mut result = self.backend.close_channel(channel_id, options).await?;
+        result.csv_delay = csv_delay;
+        info!(closing_txid = ?result.closing_txid, "Canal en cours de fermeture");
+        Ok(result)
+    }
+
+    /// Estime le coût on-chain d'une fermeture ; `None` si le canal est inconnu
+    #[instrument(skip(self), err)]
+    pub async fn estimate_close(
+        &self,
+        channel_id: &str,
+        options: &CloseOptions,
+    ) -> Result<Option<CloseEstimate>> {
+        let Some(channel) = self.find_channel(channel_id).await? else {
+            return Ok(None);
+        };
+        let mut sat_per_vbyte = match options.sat_per_vbyte {
+            Some(rate) => rate,
+            None => {
+                let target_conf = options.target_conf.unwrap_or(DEFAULT_CLOSE_TARGET_CONF);
+                self.backend.estimate_fee_rate(target_conf).await?
+            }
+        };
+        if let Some(max_fee) = options.max_fee_per_vbyte {
+            sat_per_vbyte = sat_per_vbyte.min(max_fee);
+        }
+        let vsize = if options.force {
+            FORCE_CLOSE_SWEEP_VSIZE
+        } else {
+            COOPERATIVE_CLOSE_VSIZE
+        };
+        let fee = sat_per_vbyte * vsize;
+        Ok(Some(CloseEstimate {
+            channel_id: channel.id,
+            force: options.force,
+            sat_per_vbyte,
+            vsize,
+            fee,
+            local_balance: channel.local_balance.saturating_sub(fee),
+            csv_delay: if options.force { channel.csv_delay } else { None },
+        }))
+    }
+
+    async fn find_channel(&self, channel_id: &str) -> Result<Option<Channel>> {
+        let channels = self.backend.list_channels().await?;
+        Ok(channels.into_iter().find(|c| c.id == channel_id))
+    }
+
+    /// Modifie la politique locale de `channel_id` ; `None` si le canal est inconnu
+    #[instrument(skip(self, update), err)]
+    pub async fn update_channel_policy(
+        &self,
+        channel_id: &str,
+        update: &PolicyUpdate,
+    ) -> Result<Option<PolicyChange>> {
+        let Some(channel) = self.find_channel(channel_id).await? else {
+            return Ok(None);
+        };
+        let before = channel.local_policy.with_context(|| {
+            format!("Politique du canal {} inconnue (canal en attente ?)", channel_id)
+        })?;
+        Ok(self
+            .apply_policies(vec![(channel.id, before)], update)
+            .await?
+            .pop())
+    }
+
+    /// Modifie la politique locale de tous les canaux dont la politique est connue.
+    ///
+    /// Un canal refusé par le nœud n'interrompt pas les suivants : son erreur figure
+    /// dans la modification renvoyée.
+    #[instrument(skip(self, update), err)]
+    pub async fn update_all_policies(&self, update: &PolicyUpdate) -> Result<Vec<PolicyChange>> {
+        let targets = self
+            .backend
+            .list_channels()
+            .await?
+            .into_iter()
+            .filter_map(|c| Some((c.id, c.local_policy?)))
+            .collect();
+        self.apply_policies(targets, update).await
+    }
+
+    async fn apply_policies(
+        &self,
+        targets: Vec<(String, ChannelPolicy)>,
+        update: &PolicyUpdate,
+    ) -> Result<Vec<PolicyChange>> {
+        let mut failures = Vec::new();
+        for (channel_id, before) in &targets {
+            match self
+                .backend
+                .set_channel_policy(channel_id, update, &update.apply(before))
+                .await
+            {
+                Ok(()) => info!(channel_id = %channel_id, "Politique du canal modifiée"),
+                Err(e) => {
+                    warn!(channel_id = %channel_id, error = %e, "Politique du canal non modifiée");
+                    failures.push((channel_id.clone(), e));
+                }
+            }
+        }
+        // Aucun canal modifié : l'erreur remonte telle quelle (backend non pris en charge…)
+        if !targets.is_empty() && failures.len() == targets.len() {
+            return Err(failures.swap_remove(0).1);
+        }
+        let mut errors: BTreeMap<String, String> = failures
+            .into_iter()
+            .map(|(channel_id, e)| (channel_id, format!("{:#}", e)))
+            .collect();
+
+        // La politique est relue sur le nœud pour montrer celle qu'il applique réellement
+        let channels = self.backend.list_channels().await?;
+        Ok(targets
+            .into_iter()
+            .map(|(channel_id, before)| PolicyChange {
+                after: channels
+                    .iter()
+                    .find(|c| c.id == channel_id)
+                    .and_then(|c| c.local_policy.clone()),
+                error: errors.remove(&channel_id),
+                channel_id,
+                before,
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,604 @@
+use anyhow::{bail, Context, Result};
+use reqwest::{Client, Url};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+use tracing::{info, instrument};
+
+use crate::models::lightning::{
+    DecodedInvoice, Invoice, LnurlPayRequest, LnurlRequest, LnurlWithdrawRequest, SuccessAction,
+};
+use crate::services::bolt11::{self, bech32_decode, words_to_bytes};
+use crate::services::lightning::LightningService;
+
+/// Délai maximal de réponse d'un serveur LNURL
+const LNURL_TIMEOUT: Duration = Duration::from_secs(30);
+/// Schémas LUD-17 qui désignent directement l'URL à interroger
+const LUD17_SCHEMES: [&str; 3] = ["lnurlp", "lnurlw", "lnurlc"];
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WirePayRequest {
+    callback: String,
+    min_sendable: u64,
+    max_sendable: u64,
+    metadata: String,
+    #[serde(default)]
+    comment_allowed: u16,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WireWithdrawRequest {
+    callback: String,
+    k1: String,
+    #[serde(default)]
+    default_description: String,
+    min_withdrawable: u64,
+    max_withdrawable: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WireInvoice {
+    pr: String,
+    success_action: Option<SuccessAction>,
+}
+
+/// Facture obtenue auprès du callback d'une requête LNURL-pay, déjà vérifiée
+#[derive(Debug)]
+pub struct LnurlInvoice {
+    pub payment_request: String,
+    pub invoice: DecodedInvoice,
+    pub success_action: Option<SuccessAction>,
+}
+
+fn is_onion(url: &Url) -> bool {
+    url.host_str()
+        .is_some_and(|host| host.ends_with(".onion"))
+}
+
+/// Convertit une Lightning Address `nom@domaine` en URL LUD-16
+fn lightning_address_url(address: &str) -> Result<Url> {
+    let (name, domain) = address
+        .split_once('@')
+        .context("Lightning Address invalide")?;
+    let valid_name = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "-_.+".contains(c));
+    if !valid_name || domain.is_empty() {
+        bail!("Lightning Address invalide: {}", address);
+    }
+    let scheme = if domain.ends_with(".onion") {
+        "http"
+    } else {
+        "https"
+    };
+    Url::parse(&format!(
+        "{}://{}/.well-known/lnurlp/{}",
+        scheme, domain, name
+    ))
+    .with_context(|| format!("Lightning Address invalide: {}", address))
+}
+
+/// Retrouve l'URL à interroger pour un LNURL bech32 (LUD-01), une URL LUD-17
+/// (`lnurlp://...`) ou une Lightning Address (LUD-16).
+///
+/// Le préfixe `lightning:` des URI est accepté.
+pub fn parse_target(target: &str) -> Result<Url> {
+    let target = bolt11::strip_scheme(target);
+
+    if let Some((scheme, rest)) = target.split_once("://") {
+        if !LUD17_SCHEMES.contains(&scheme.to_lowercase().as_str()) {
+            bail!("Schéma LNURL inconnu: {}", scheme);
+        }
+        let mut url = Url::parse(&format!("https://{}", rest)).context("LNURL invalide")?;
+        if is_onion(&url) {
+            url = Url::parse(&format!("http://{}", rest)).context("LNURL invalide")?;
+        }
+        return Ok(url);
+    }
+
+    if target.contains('@') {
+        return lightning_address_url(&target.to_lowercase());
+    }
+
+    let (hrp, words) = bech32_decode(target, "LNURL")?;
+    if hrp != "lnurl" {
+        bail!("LNURL invalide: préfixe « {} » inattendu", hrp);
+    }
+    let url = String::from_utf8(words_to_bytes(&words, false))
+        .context("LNURL invalide: URL non UTF-8")?;
+    Url::parse(&url).context("LNURL invalide: URL illisible")
+}
+
+/// Lit la description et l'identifiant dans les métadonnées d'une requête LNURL-pay
+fn parse_metadata(metadata: &str) -> Result<(String, Option<String>)> {
+    let entries: Vec<Vec<Value>> =
+        serde_json::from_str(metadata).context("Métadonnées LNURL illisibles")?;
+    let find = |kinds: &[&str]| {
+        entries.iter().find_map(|entry| match entry.as_slice() {
+            [kind, Value::String(content), ..] if kinds.iter().any(|k| kind == k) => {
+                Some(content.clone())
+            }
+            _ => None,
+        })
+    };
+    let description =
+        find(&["text/plain"]).context("Métadonnées LNURL sans description text/plain")?;
+    Ok((description, find(&["text/identifier", "text/email"])))
+}
+
+/// Client LNURL : résolution des requêtes pay et withdraw, et échanges avec leurs callbacks.
+///
+/// Seuls HTTPS et les services `.onion` en HTTP sont acceptés, comme l'exige LUD-01.
+#[derive(Clone)]
+pub struct LnurlClient {
+    client: Client,
+    allow_http: bool,
+}
+
+impl Default for LnurlClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LnurlClient {
+    pub fn new() -> Self {
+        Self {
+            client: Client::new(),
+            allow_http: false,
+        }
+    }
+
+    /// Accepte les serveurs LNURL en HTTP clair, pour un serveur local de test ;
+    /// les Lightning Addresses et URL LUD-17 sont alors elles aussi résolues en HTTP
+    pub fn allow_http(mut self) -> Self {
+        self.allow_http = true;
+        self
+    }
+
+    /// Interroge un serveur LNURL, dont les erreurs sont signalées par `{"status": "ERROR"}`
+    async fn get<T: DeserializeOwned>(&self, url: Url) -> Result<T> {
+        let secure = url.scheme() == "https" || (url.scheme() == "http" && is_onion(&url));
+        if !(secure || self.allow_http && url.scheme() == "http") {
+            bail!("Serveur LNURL refusé, HTTPS requis: {}", url);
+        }
+
+        let response: Value = self
+            .client
+            .get(url)
+            .timeout(LNURL_TIMEOUT)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        if response["status"].as_str() == Some("ERROR") {
+            bail!(
+                "Erreur du serveur LNURL: {}",
+                response["reason"].as_str().unwrap_or("raison inconnue")
+            );
+        }
+        Ok(serde_json::from_value(response)?)
+    }
+
+    #[instrument(skip(self), err)]
+    pub async fn resolve(&self, target: &str) -> Result<LnurlRequest> {
+        let mut url = parse_target(target)?;
+        if self.allow_http && url.scheme() == "https" {
+            let _ = url.set_scheme("http");
+        }
+        let domain = url.host_str().unwrap_or_default().to_string();
+        info!(domain = %domain, "Résolution d'une requête LNURL");
+
+        let response: Value = self.get(url).await?;
+        match response["tag"].as_str() {
+            Some("payRequest") => {
+                let pay: WirePayRequest = serde_json::from_value(response)?;
+                if pay.min_sendable == 0 || pay.min_sendable > pay.max_sendable {
+                    bail!(
+                        "Requête LNURL-pay invalide: bornes {} - {} msat",
+                        pay.min_sendable,
+                        pay.max_sendable
+                    );
+                }
+                let (description, identifier) = parse_metadata(&pay.metadata)?;
+                Ok(LnurlRequest::Pay(LnurlPayRequest {
+                    domain,
+                    callback: pay.callback,
+                    min_sendable: pay.min_sendable,
+                    max_sendable: pay.max_sendable,
+                    metadata: pay.metadata,
+                    description,
+                    identifier,
+                    comment_allowed: pay.comment_allowed,
+                }))
+            }
+            Some("withdrawRequest") => {
+                let withdraw: WireWithdrawRequest = serde_json::from_value(response)?;
+                if withdraw.min_withdrawable > withdraw.max_withdrawable {
+                    bail!(
+                        "Requête LNURL-withdraw invalide: bornes {} - {} msat",
+                        withdraw.min_withdrawable,
+                        withdraw.max_withdrawable
+                    );
+                }
+                Ok(LnurlRequest::Withdraw(LnurlWithdrawRequest {
+                    domain,
+                    callback: withdraw.callback,
+                    k1: withdraw.k1,
+                    default_description: withdraw.default_description,
+                    min_withdrawable: withdraw.min_withdrawable,
+                    max_withdrawable: withdraw.max_withdrawable,
+                }))
+            }
+            Some(tag) => bail!("Requête LNURL « {} » non prise en charge", tag),
+            None => bail!("Réponse LNURL sans champ tag"),
+        }
+    }
+
+    /// Demande une facture au callback d'une requête LNURL-pay.
+    ///
+    /// La facture n'est acceptée que si son montant est celui demandé et si son
+    /// hash de description est celui des métadonnées de la requête.
+    #[instrument(skip(self, pay), fields(domain = %pay.domain), err)]
+    pub async fn request_invoice(
+        &self,
+        pay: &LnurlPayRequest,
+        amount_msat: u64,
+        comment: Option<&str>,
+    ) -> Result<LnurlInvoice> {
+        if !(pay.min_sendable..=pay.max_sendable).contains(&amount_msat) {
+            bail!(
+                "Montant de {} msat hors des bornes du destinataire ({} - {} msat)",
+                amount_msat,
+                pay.min_sendable,
+                pay.max_sendable
+            );
+        }
+
+        let mut url = Url::parse(&pay.callback).context("Callback LNURL invalide")?;
+        url.query_pairs_mut()
+            .append_pair("amount", &amount_msat.to_string());
+        if let Some(comment) = comment {
+            if comment.chars().count() > pay.comment_allowed as usize {
+                bail!(
+                    "Commentaire trop long ({} caractères acceptés par le destinataire)",
+                    pay.comment_allowed
+                );
+            }
+            url.query_pairs_mut().append_pair("comment", comment);
+        }
+
+        let response: WireInvoice = self.get(url).await?;
+        let invoice = bolt11::decode(&response.pr)?;
+        if invoice.amount_msat != Some(amount_msat) {
+            bail!(
+                "Facture LNURL de {:?} msat au lieu des {} msat demandés",
+                invoice.amount_msat,
+                amount_msat
+            );
+        }
+        let metadata_hash = hex::encode(Sha256::digest(pay.metadata.as_bytes()));
+        if invoice.description_hash.as_deref() != Some(metadata_hash.as_str()) {
+            bail!("Hash de description de la facture LNURL différent de celui des métadonnées");
+        }
+
+        info!(payment_hash = %invoice.payment_hash, "Facture LNURL obtenue");
+        Ok(LnurlInvoice {
+            payment_request: response.pr,
+            invoice,
+            success_action: response.success_action,
+        })
+    }
+
+    /// Crée une facture sur le nœud et la transmet au callback d'une requête
+    /// LNURL-withdraw ; `amount` en sats.
+    #[instrument(skip(self, service, withdraw), fields(domain = %withdraw.domain), err)]
+    pub async fn withdraw(
+        &self,
+        service: &LightningService,
+        withdraw: &LnurlWithdrawRequest,
+        amount: u64,
+    ) -> Result<Invoice> {
+        let amount_msat = amount * 1000;
+        if !(withdraw.min_withdrawable..=withdraw.max_withdrawable).contains(&amount_msat) {
+            bail!(
+                "Montant de {} msat hors des bornes du service ({} - {} msat)",
+                amount_msat,
+                withdraw.min_withdrawable,
+                withdraw.max_withdrawable
+            );
+        }
+
+        let invoice = service
+            .create_invoice(amount, &withdraw.default_description, None)
+            .await?;
+        let mut url = Url::parse(&withdraw.callback).context("Callback LNURL invalide")?;
+        url.query_pairs_mut()
+            .append_pair("k1", &withdraw.k1)
+            .append_pair("pr", &invoice.payment_request);
+        let _: Value = self.get(url).await?;
+
+        info!(payment_hash = %invoice.payment_hash, "Retrait LNURL accepté");
+        Ok(invoice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::backends::EclairBackend;
+    use crate::services::bolt11::tests::{
+        bech32_encode, description_hash_field, encode, payment_hash_field,
+    };
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    const METADATA: &str = r#"[["text/plain","Un café"],["text/identifier","alice@lightdash"]]"#;
+    const PAYMENT_HASH: &str = "0001020304050607080900010203040506070809000102030405060708090102";
+
+    /// Serveur LNURL local en HTTP : une réponse JSON par chemin, query ignorée.
+    ///
+    /// Chaque requête reçue est consignée (`GET /chemin?query`), pour vérifier ce que
+    /// le client transmet aux callbacks.
+    struct StandIn {
+        base: String,
+        requests: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl StandIn {
+        /// `routes` reçoit l'URL de base du serveur, pour y faire pointer les callbacks
+        async fn start(routes: impl FnOnce(&str) -> Vec<(&'static str, Value)>) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let base = format!("http://{}", listener.local_addr().unwrap());
+            let routes: Arc<HashMap<&str, Value>> = Arc::new(routes(&base).into_iter().collect());
+            let requests = Arc::new(Mutex::new(Vec::new()));
+            let log = requests.clone();
+            tokio::spawn(async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    tokio::spawn(Self::serve(stream, routes.clone(), log.clone()));
+                }
+            });
+            Self { base, requests }
+        }
+
+        async fn serve(
+            mut stream: TcpStream,
+            routes: Arc<HashMap<&str, Value>>,
+            log: Arc<Mutex<Vec<String>>>,
+        ) {
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 4096];
+            let head_end = loop {
+                let read = stream.read(&mut buffer).await.unwrap();
+                request.extend_from_slice(&buffer[..read]);
+                if let Some(end) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                    break end + 4;
+                }
+            };
+            let head = String::from_utf8_lossy(&request[..head_end]).to_string();
+            let length = head
+                .lines()
+                .find_map(|line| {
+                    let line = line.to_lowercase();
+                    line.strip_prefix("content-length:")?.trim().parse().ok()
+                })
+                .unwrap_or(0usize);
+            while request.len() < head_end + length {
+                let read = stream.read(&mut buffer).await.unwrap();
+                request.extend_from_slice(&buffer[..read]);
+            }
+
+            let mut request_line = head.split_whitespace();
+            let method = request_line.next().unwrap_or_default();
+            let target = request_line.next().unwrap_or_default();
+            let path = target.split('?').next().unwrap_or_default();
+            log.lock().unwrap().push(format!("{} {}", method, target));
+
+            let (status, body) = match routes.get(path) {
+                Some(body) => ("200 OK", body.to_string()),
+                None => ("404 Not Found", "{}".to_string()),
+            };
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+                 Connection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+        }
+
+        /// LNURL bech32 (LUD-01) désignant `path` sur ce serveur
+        fn lnurl(&self, path: &str) -> String {
+            bech32_encode("lnurl", format!("{}{}", self.base, path).as_bytes())
+        }
+
+        fn requests(&self) -> Vec<String> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    fn client() -> LnurlClient {
+        LnurlClient::new().allow_http()
+    }
+
+    fn pay_request(base: &str) -> Value {
+        serde_json::json!({
+            "tag": "payRequest",
+            "callback": format!("{}/callback", base),
+            "minSendable": 1_000,
+            "maxSendable": 1_000_000,
+            "metadata": METADATA,
+            "commentAllowed": 32,
+        })
+    }
+
+    /// Facture signée que le callback renvoie, de `hrp` et décrite par `description`
+    fn invoice(hrp: &str, description: &str) -> Value {
+        let fields = [payment_hash_field(PAYMENT_HASH), description_hash_field(description)];
+        serde_json::json!({ "pr": encode(hrp, &fields), "routes": [] })
+    }
+
+    async fn resolve_pay(client: &LnurlClient, target: &str) -> LnurlPayRequest {
+        match client.resolve(target).await.unwrap() {
+            LnurlRequest::Pay(pay) => pay,
+            LnurlRequest::Withdraw(_) => panic!("requête LNURL-pay attendue"),
+        }
+    }
+
+    #[tokio::test]
+    async fn pays_when_invoice_matches_metadata_and_amount() {
+        let server = StandIn::start(|base| {
+            vec![
+                ("/lnurlp", pay_request(base)),
+                ("/callback", invoice("lnbc1u", METADATA)),
+            ]
+        })
+        .await;
+        let client = client();
+
+        let pay = resolve_pay(&client, &server.lnurl("/lnurlp")).await;
+        assert_eq!(pay.description, "Un café");
+        assert_eq!(pay.identifier.as_deref(), Some("alice@lightdash"));
+        assert_eq!((pay.min_sendable, pay.max_sendable), (1_000, 1_000_000));
+
+        let invoice = client.request_invoice(&pay, 100_000, Some("Merci")).await.unwrap();
+        assert_eq!(invoice.invoice.amount_msat, Some(100_000));
+        assert_eq!(invoice.invoice.payment_hash, PAYMENT_HASH);
+        assert_eq!(
+            server.requests(),
+            vec!["GET /lnurlp", "GET /callback?amount=100000&comment=Merci"]
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_invoice_with_another_description_hash() {
+        let server = StandIn::start(|base| {
+            vec![
+                ("/lnurlp", pay_request(base)),
+                ("/callback", invoice("lnbc1u", "Autres métadonnées")),
+            ]
+        })
+        .await;
+        let client = client();
+
+        let pay = resolve_pay(&client, &server.lnurl("/lnurlp")).await;
+        let error = client.request_invoice(&pay, 100_000, None).await.unwrap_err();
+        assert!(error.to_string().contains("Hash de description"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn rejects_invoice_with_another_amount() {
+        let server = StandIn::start(|base| {
+            vec![
+                ("/lnurlp", pay_request(base)),
+                ("/callback", invoice("lnbc2u", METADATA)),
+            ]
+        })
+        .await;
+        let client = client();
+
+        let pay = resolve_pay(&client, &server.lnurl("/lnurlp")).await;
+        let error = client.request_invoice(&pay, 100_000, None).await.unwrap_err();
+        assert!(error.to_string().contains("au lieu des 100000 msat"), "{}", error);
+        // Hors des bornes du destinataire, le callback n'est même pas appelé
+        assert!(client.request_invoice(&pay, 500, None).await.is_err());
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn resolves_lightning_address() {
+        let server = StandIn::start(|base| {
+            vec![("/.well-known/lnurlp/alice", pay_request(base))]
+        })
+        .await;
+        let address = format!("Alice@{}", server.base.trim_start_matches("http://"));
+
+        let pay = resolve_pay(&client(), &address).await;
+        assert_eq!(pay.callback, format!("{}/callback", server.base));
+        assert_eq!(server.requests(), vec!["GET /.well-known/lnurlp/alice"]);
+    }
+
+    #[tokio::test]
+    async fn withdraws_to_an_invoice_created_on_the_node() {
+        let payment_request = invoice("lnbc1u", "Retrait")["pr"].clone();
+        let server = StandIn::start(|base| {
+            vec![
+                (
+                    "/lnurlw",
+                    serde_json::json!({
+                        "tag": "withdrawRequest",
+                        "callback": format!("{}/withdraw", base),
+                        "k1": "secret",
+                        "defaultDescription": "Retrait",
+                        "minWithdrawable": 1_000,
+                        "maxWithdrawable": 500_000,
+                    }),
+                ),
+                // Eclair, derrière le service Lightning
+                (
+                    "/createinvoice",
+                    serde_json::json!({
+                        "paymentHash": PAYMENT_HASH,
+                        "serialized": payment_request,
+                    }),
+                ),
+                ("/withdraw", serde_json::json!({ "status": "OK" })),
+            ]
+        })
+        .await;
+        let backend = EclairBackend::new(server.base.clone(), "password".to_string());
+        let service = LightningService::new(Arc::new(backend), String::new(), String::new());
+        let client = client();
+
+        let withdraw = match client.resolve(&server.lnurl("/lnurlw")).await.unwrap() {
+            LnurlRequest::Withdraw(withdraw) => withdraw,
+            LnurlRequest::Pay(_) => panic!("requête LNURL-withdraw attendue"),
+        };
+        assert_eq!(withdraw.k1, "secret");
+        assert!(client.withdraw(&service, &withdraw, 600).await.is_err());
+
+        let invoice = client.withdraw(&service, &withdraw, 100).await.unwrap();
+        assert_eq!(invoice.payment_hash, PAYMENT_HASH);
+        assert_eq!(
+            server.requests(),
+            vec![
+                "GET /lnurlw".to_string(),
+                "POST /createinvoice".to_string(),
+                format!("GET /withdraw?k1=secret&pr={}", payment_request.as_str().unwrap()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn surfaces_error_status() {
+        let server = StandIn::start(|_| {
+            vec![(
+                "/lnurlp",
+                serde_json::json!({ "status": "ERROR", "reason": "Service indisponible" }),
+            )]
+        })
+        .await;
+
+        let error = client().resolve(&server.lnurl("/lnurlp")).await.unwrap_err();
+        assert_eq!(error.to_string(), "Erreur du serveur LNURL: Service indisponible");
+    }
+
+    #[tokio::test]
+    async fn refuses_plain_http_by_default() {
+        let server = StandIn::start(|base| vec![("/lnurlp", pay_request(base))]).await;
+
+        let error = LnurlClient::new().resolve(&server.lnurl("/lnurlp")).await.unwrap_err();
+        assert!(error.to_string().contains("HTTPS requis"), "{}", error);
+        assert!(server.requests().is_empty());
+    }
+}
@@ -1 +1,16 @@
-pub mod lightning; 
\ No newline at end of file
+pub mod backends;
+pub mod bolt11;
+pub mod bolt12;
+pub mod events;
+pub mod export;
+pub mod fees;
+pub mod history;
+pub mod keysend;
+pub mod lightning;
+pub mod lnurl;
+pub mod pnl;
+pub mod policy;
+pub mod prices;
+pub mod rebalance;
+pub mod registry;
+pub mod yields;
@@ -0,0 +1,125 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::models::history::ChannelForwards;
+use crate::models::lightning::{Channel, ChannelStatus};
+use crate::models::pnl::{ChannelCost, ChannelPnl, CostKind, PeerPnl, PnlReport};
+
+/// Bénéfice net rapporté au capital, en pourcentage
+fn roi(net_profit_msat: i64, capital: u64) -> Option<f64> {
+    (capital > 0).then(|| net_profit_msat as f64 / 1000.0 / capital as f64 * 100.0)
+}
+
+impl ChannelPnl {
+    fn costs_msat(&self) -> u64 {
+        self.rebalance_fee_msat
+            + self.opening_fee_msat
+            + self.closing_fee_msat
+            + self.lease_fee_msat
+    }
+}
+
+/// Compte de résultat par canal et par pair entre `from` et `to`.
+///
+/// Relais et coûts sont rattachés aux canaux ouverts par leur short channel id,
+/// à défaut par leur identifiant ; ceux qui ne correspondent à aucun canal ouvert
+/// forment les lignes des canaux fermés depuis, sans capital engagé.
+pub fn compute_pnl(
+    from: i64,
+    to: i64,
+    channels: &[Channel],
+    forwards: &[ChannelForwards],
+    costs: &[ChannelCost],
+    capital: &HashMap<String, u64>,
+) -> PnlReport {
+    let mut rows: Vec<ChannelPnl> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for c in channels.iter().filter(|c| c.status != ChannelStatus::Pending) {
+        let row = rows.len();
+        index.insert(c.id.clone(), row);
+        if let Some(scid) = &c.short_channel_id {
+            index.insert(scid.clone(), row);
+        }
+        rows.push(ChannelPnl {
+            channel_id: c.id.clone(),
+            remote_pubkey: c.remote_pubkey.clone(),
+            open: true,
+            capital: capital.get(&c.id).copied().unwrap_or(c.local_balance),
+            ..Default::default()
+        });
+    }
+    let mut row_for = |channel_id: &str, rows: &mut Vec<ChannelPnl>| {
+        *index.entry(channel_id.to_string()).or_insert_with(|| {
+            rows.push(ChannelPnl {
+                channel_id: channel_id.to_string(),
+                ..Default::default()
+            });
+            rows.len() - 1
+        })
+    };
+
+    for f in forwards {
+        let row = row_for(&f.channel_id, &mut rows);
+        rows[row].routing_fee_msat += f.fee_msat;
+    }
+    for cost in costs.iter().filter(|c| (from..=to).contains(&c.timestamp)) {
+        let row = row_for(&cost.channel_id, &mut rows);
+        let row = &mut rows[row];
+        if row.remote_pubkey.is_empty() {
+            row.remote_pubkey = cost.remote_pubkey.clone().unwrap_or_default();
+        }
+        let total = match cost.kind {
+            CostKind::OpeningFee => &mut row.opening_fee_msat,
+            CostKind::ClosingFee => &mut row.closing_fee_msat,
+            CostKind::Rebalance => &mut row.rebalance_fee_msat,
+            CostKind::Lease => &mut row.lease_fee_msat,
+        };
+        *total += cost.amount_msat;
+    }
+
+    for row in &mut rows {
+        row.net_profit_msat = row.routing_fee_msat as i64 - row.costs_msat() as i64;
+        row.roi = roi(row.net_profit_msat, row.capital);
+    }
+    rows.sort_by_key(|row| Reverse(row.net_profit_msat));
+
+    // Un canal fermé dont le pair est inconnu n'est rattaché à aucun pair
+    let mut peers: BTreeMap<&str, PeerPnl> = BTreeMap::new();
+    for c in rows.iter().filter(|c| !c.remote_pubkey.is_empty()) {
+        let peer = peers.entry(&c.remote_pubkey).or_insert_with(|| PeerPnl {
+            remote_pubkey: c.remote_pubkey.clone(),
+            ..Default::default()
+        });
+        peer.channels += 1;
+        peer.capital += c.capital;
+        peer.routing_fee_msat += c.routing_fee_msat;
+        peer.costs_msat += c.costs_msat();
+        peer.net_profit_msat += c.net_profit_msat;
+    }
+    let mut peers: Vec<PeerPnl> = peers
+        .into_values()
+        .map(|mut p| {
+            p.roi = roi(p.net_profit_msat, p.capital);
+            p
+        })
+        .collect();
+    peers.sort_by_key(|peer| Reverse(peer.net_profit_msat));
+
+    let sum = |field: fn(&ChannelPnl) -> u64| rows.iter().map(field).sum::<u64>();
+    let capital = sum(|c| c.capital);
+    let net_profit_msat = rows.iter().map(|c| c.net_profit_msat).sum();
+    PnlReport {
+        from,
+        to,
+        capital,
+        routing_fee_msat: sum(|c| c.routing_fee_msat),
+        rebalance_fee_msat: sum(|c| c.rebalance_fee_msat),
+        opening_fee_msat: sum(|c| c.opening_fee_msat),
+        closing_fee_msat: sum(|c| c.closing_fee_msat),
+        lease_fee_msat: sum(|c| c.lease_fee_msat),
+        net_profit_msat,
+        roi: roi(net_profit_msat, capital),
+        channels: rows,
+        peers,
+    }
+}
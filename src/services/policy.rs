@@ -0,0 +1,109 @@
+use crate::models::fees::{FeePolicy, FeeStrategy};
+use crate::models::rebalance::{RebalancePolicy, RetryStrategy};
+
+/// Montant maximal d'un rééquilibrage en sats (0,1 BTC), le plafond d'un paiement sortant
+pub const MAX_REBALANCE_AMOUNT: u64 = 10_000_000;
+/// Frais maximaux d'un rééquilibrage (1 %), au-delà il coûte plus qu'il ne rapporte
+pub const MAX_REBALANCE_FEE_PPM: u32 = 10_000;
+/// Nombre maximal de tentatives d'un rééquilibrage
+pub const MAX_REBALANCE_ATTEMPTS: u32 = 10;
+/// Période minimale de la tâche de rééquilibrage automatique, en secondes
+pub const MIN_REBALANCE_INTERVAL: u64 = 60;
+/// Période minimale du gestionnaire de frais, en secondes
+pub const MIN_FEE_MANAGER_INTERVAL: u64 = 60;
+/// Taux de frais proportionnel maximal (100 %)
+const MAX_FEE_PPM: u32 = 1_000_000;
+
+/// Plafond de frais et tentatives, communs aux rééquilibrages manuels et automatiques
+pub fn validate_rebalance_limits(max_fee_ppm: u32, retry: &RetryStrategy) -> Result<(), String> {
+    if max_fee_ppm > MAX_REBALANCE_FEE_PPM {
+        return Err(format!(
+            "Plafond de frais de rééquilibrage invalide: {} ppm ({} maximum)",
+            max_fee_ppm, MAX_REBALANCE_FEE_PPM
+        ));
+    }
+    if retry.attempts == 0 || retry.attempts > MAX_REBALANCE_ATTEMPTS {
+        return Err(format!(
+            "Nombre de tentatives invalide: {} (attendu entre 1 et {})",
+            retry.attempts, MAX_REBALANCE_ATTEMPTS
+        ));
+    }
+    Ok(())
+}
+
+/// Objectif de rééquilibrage automatique : une fourchette de ratios non vide dans [0, 1]
+pub fn validate_rebalance_policy(policy: &RebalancePolicy) -> Result<(), String> {
+    let (min, max) = (policy.min_local_ratio, policy.max_local_ratio);
+    if !(0.0..=1.0).contains(&min) || !(0.0..=1.0).contains(&max) || min >= max {
+        return Err(format!(
+            "Fourchette de ratio local invalide: {} à {} (attendu 0 ≤ min < max ≤ 1)",
+            min, max
+        ));
+    }
+    if policy.max_amount == 0 || policy.max_amount > MAX_REBALANCE_AMOUNT {
+        return Err(format!(
+            "Montant invalide: {} sats (attendu entre 1 et {})",
+            policy.max_amount, MAX_REBALANCE_AMOUNT
+        ));
+    }
+    validate_rebalance_limits(policy.max_fee_ppm, &policy.retry)?;
+    if policy.interval_secs < MIN_REBALANCE_INTERVAL {
+        return Err(format!(
+            "Période de rééquilibrage invalide: {} s ({} minimum)",
+            policy.interval_secs, MIN_REBALANCE_INTERVAL
+        ));
+    }
+    Ok(())
+}
+
+/// Gestionnaire de frais : bornes cohérentes et clé Sparkseer pour la stratégie qui l'exige
+pub fn validate_fee_policy(
+    policy: &FeePolicy,
+    sparkseer_api_key: Option<&str>,
+) -> Result<(), String> {
+    match &policy.strategy {
+        FeeStrategy::Sparkseer { .. } if sparkseer_api_key.is_none() => {
+            return Err("La stratégie sparkseer requiert sparkseer_api_key".to_string());
+        }
+        FeeStrategy::Sparkseer { .. } => {}
+        FeeStrategy::Balance { min_ppm, max_ppm } | FeeStrategy::Volume { min_ppm, max_ppm, .. }
+            if min_ppm > max_ppm =>
+        {
+            return Err(format!(
+                "Bornes de frais invalides: {} à {} ppm (min > max)",
+                min_ppm, max_ppm
+            ));
+        }
+        FeeStrategy::Balance { max_ppm, .. } | FeeStrategy::Volume { max_ppm, .. } => {
+            if *max_ppm > MAX_FEE_PPM {
+                return Err(format!(
+                    "Taux de frais invalide: {} ppm (1 000 000 maximum)",
+                    max_ppm
+                ));
+            }
+        }
+    }
+    if let FeeStrategy::Volume {
+        step_percent,
+        window_secs,
+        ..
+    } = &policy.strategy
+    {
+        if *step_percent == 0 || *step_percent > 100 {
+            return Err(format!(
+                "Pas de variation invalide: {} % (attendu entre 1 et 100)",
+                step_percent
+            ));
+        }
+        if *window_secs == 0 {
+            return Err("La fenêtre de volume doit être positive".to_string());
+        }
+    }
+    if policy.interval_secs < MIN_FEE_MANAGER_INTERVAL {
+        return Err(format!(
+            "Période du gestionnaire de frais invalide: {} s ({} minimum)",
+            policy.interval_secs, MIN_FEE_MANAGER_INTERVAL
+        ));
+    }
+    Ok(())
+}
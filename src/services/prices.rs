@@ -0,0 +1,80 @@
+use anyhow::{bail, Context, Result};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use tracing::{info, instrument};
+
+/// Devises proposées par l'API de cours historiques de mempool.space
+pub const FIAT_CURRENCIES: [&str; 7] = ["USD", "EUR", "GBP", "CAD", "CHF", "AUD", "JPY"];
+
+const SECONDS_PER_DAY: i64 = 24 * 3600;
+
+#[derive(Debug, Deserialize)]
+struct HistoricalPrices {
+    #[serde(default)]
+    prices: Vec<HashMap<String, Value>>,
+}
+
+/// Cours historiques du bitcoin, interrogés auprès d'une instance mempool.space
+pub struct PriceClient {
+    client: Client,
+    base_url: String,
+}
+
+impl PriceClient {
+    pub fn new(base_url: String) -> Self {
+        Self {
+            client: Client::new(),
+            base_url,
+        }
+    }
+
+    /// Cours d'un bitcoin dans `currency` pour chaque jour (minuit UTC) couvrant `timestamps`
+    #[instrument(skip(self, timestamps), err)]
+    pub async fn daily_prices(
+        &self,
+        currency: &str,
+        timestamps: impl IntoIterator<Item = i64>,
+    ) -> Result<HashMap<i64, f64>> {
+        if !FIAT_CURRENCIES.contains(&currency) {
+            bail!(
+                "Devise non prise en charge: {} (attendu {})",
+                currency,
+                FIAT_CURRENCIES.join(", ")
+            );
+        }
+        let mut days: Vec<i64> = timestamps.into_iter().map(day_of).collect();
+        days.sort_unstable();
+        days.dedup();
+
+        let mut prices = HashMap::new();
+        for day in days {
+            let url = format!(
+                "{}/api/v1/historical-price?currency={}&timestamp={}",
+                self.base_url, currency, day
+            );
+            let response: HistoricalPrices = self
+                .client
+                .get(&url)
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+            let price = response
+                .prices
+                .first()
+                .and_then(|p| p.get(currency)?.as_f64())
+                .with_context(|| format!("Cours {} introuvable pour le {}", currency, day))?;
+            prices.insert(day, price);
+        }
+        info!(currency, count = prices.len(), "Cours historiques récupérés");
+        Ok(prices)
+    }
+}
+
+/// Début du jour UTC contenant `timestamp`
+pub fn day_of(timestamp: i64) -> i64 {
+    timestamp.div_euclid(SECONDS_PER_DAY) * SECONDS_PER_DAY
+}
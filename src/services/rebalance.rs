@@ -0,0 +1,282 @@
+use std::cmp::Reverse;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::{info, instrument, warn};
+
+use crate::models::lightning::{Channel, ChannelStatus, PaymentOptions, TransactionStatus};
+use crate::models::rebalance::{
+    RebalanceAttempt, RebalancePolicy, RebalanceRequest, RebalanceResult, RebalanceTrigger,
+};
+use crate::services::backends::Unsupported;
+use crate::services::history::HistoryStore;
+use crate::services::lightning::LightningService;
+
+/// Mémo des factures que le nœud se paie à lui-même
+const REBALANCE_DESCRIPTION: &str = "Rééquilibrage Lightdash";
+/// Validité des factures de rééquilibrage, en secondes
+const REBALANCE_INVOICE_EXPIRY: u64 = 3600;
+
+#[derive(Debug)]
+pub enum RebalanceError {
+    /// Demande incompatible avec l'état des canaux
+    InvalidRequest(String),
+    Node(anyhow::Error),
+}
+
+impl fmt::Display for RebalanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RebalanceError::InvalidRequest(message) => f.write_str(message),
+            RebalanceError::Node(e) => write!(f, "{:#}", e),
+        }
+    }
+}
+
+impl std::error::Error for RebalanceError {}
+
+impl From<anyhow::Error> for RebalanceError {
+    fn from(e: anyhow::Error) -> Self {
+        RebalanceError::Node(e)
+    }
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// Canal désigné par son identifiant ou par son short channel id
+fn find_channel<'a>(channels: &'a [Channel], id: &str) -> Option<&'a Channel> {
+    channels
+        .iter()
+        .find(|c| c.id == id || c.short_channel_id.as_deref() == Some(id))
+}
+
+/// Vérifie que la liquidité demandée peut quitter `outgoing` et entrer par `incoming`
+fn check_channels<'a>(
+    channels: &'a [Channel],
+    request: &RebalanceRequest,
+) -> Result<(&'a Channel, &'a Channel), String> {
+    let outgoing = find_channel(channels, &request.outgoing_channel)
+        .ok_or_else(|| format!("Canal de sortie inconnu: {}", request.outgoing_channel))?;
+    let incoming = find_channel(channels, &request.incoming_channel)
+        .ok_or_else(|| format!("Canal d'entrée inconnu: {}", request.incoming_channel))?;
+    if outgoing.remote_pubkey == incoming.remote_pubkey {
+        return Err("Les canaux de sortie et d'entrée doivent relier des pairs différents".into());
+    }
+    if let Some(c) = [outgoing, incoming].iter().find(|c| c.status != ChannelStatus::Active) {
+        return Err(format!("Canal inactif: {}", c.id));
+    }
+    if outgoing.local_balance < request.amount {
+        return Err(format!(
+            "Solde local insuffisant sur {}: {} sats",
+            outgoing.id, outgoing.local_balance
+        ));
+    }
+    if incoming.remote_balance < request.amount {
+        return Err(format!(
+            "Liquidité entrante insuffisante sur {}: {} sats",
+            incoming.id, incoming.remote_balance
+        ));
+    }
+    Ok((outgoing, incoming))
+}
+
+/// Paie une facture de notre nœud en sortant par `outgoing` et en revenant par le pair
+/// de `incoming`. Seule une fonctionnalité absente du backend interrompt le rééquilibrage :
+/// les autres erreurs de paiement sont consignées comme un échec de la tentative.
+async fn attempt(
+    service: &LightningService,
+    outgoing: &Channel,
+    incoming: &Channel,
+    amount: u64,
+    request: &RebalanceRequest,
+    trigger: RebalanceTrigger,
+) -> anyhow::Result<RebalanceAttempt> {
+    let invoice = service
+        .create_invoice(amount, REBALANCE_DESCRIPTION, Some(REBALANCE_INVOICE_EXPIRY))
+        .await?;
+    let options = PaymentOptions {
+        max_fee_ppm: Some(request.max_fee_ppm),
+        timeout: request.timeout,
+        outgoing_channel: Some(outgoing.id.clone()),
+        last_hop: Some(incoming.remote_pubkey.clone()),
+        ..Default::default()
+    };
+    let mut attempt = RebalanceAttempt {
+        timestamp: now(),
+        trigger,
+        outgoing_channel: outgoing.id.clone(),
+        incoming_channel: incoming.id.clone(),
+        amount,
+        max_fee_ppm: request.max_fee_ppm,
+        status: TransactionStatus::Failed,
+        fee_msat: 0,
+        payment_hash: Some(invoice.payment_hash),
+        failure_reason: None,
+    };
+    match service
+        .send_circular_payment(&invoice.payment_request, &options)
+        .await
+    {
+        Ok(result) => {
+            attempt.status = result.status;
+            attempt.failure_reason = result.failure_reason;
+            if result.status == TransactionStatus::Completed {
+                attempt.fee_msat = if result.routes.is_empty() {
+                    result.fee * 1000
+                } else {
+                    result.routes.iter().map(|r| r.fee_msat).sum()
+                };
+            }
+        }
+        Err(e) if e.downcast_ref::<Unsupported>().is_some() => return Err(e),
+        Err(e) => attempt.failure_reason = Some(format!("{:#}", e)),
+    }
+    Ok(attempt)
+}
+
+/// Déplace `request.amount` sats d'un canal vers un autre par un paiement circulaire.
+///
+/// Chaque tentative est enregistrée dans l'historique avec son coût. Après un échec,
+/// la stratégie de `request.retry` s'applique ; un paiement resté en attente arrête
+/// les tentatives, puisqu'il peut encore aboutir.
+#[instrument(skip(service, history), err)]
+pub async fn rebalance(
+    service: &LightningService,
+    history: &HistoryStore,
+    node: &str,
+    request: &RebalanceRequest,
+    trigger: RebalanceTrigger,
+) -> Result<RebalanceResult, RebalanceError> {
+    let channels = service.list_channels().await?;
+    let (outgoing, incoming) =
+        check_channels(&channels, request).map_err(RebalanceError::InvalidRequest)?;
+
+    let mut amount = request.amount;
+    let mut attempts = Vec::new();
+    for _ in 0..request.retry.attempts.max(1) {
+        let attempt = attempt(service, outgoing, incoming, amount, request, trigger).await?;
+        if let Err(e) = history.record_rebalance(node, attempt.clone()).await {
+            warn!(error = %e, "Tentative de rééquilibrage non enregistrée");
+        }
+        let status = attempt.status;
+        info!(amount, status = ?status, fee_msat = attempt.fee_msat, "Tentative de rééquilibrage");
+        attempts.push(attempt);
+        if status != TransactionStatus::Failed {
+            break;
+        }
+        if request.retry.halve_on_failure {
+            if amount / 2 < request.retry.min_amount {
+                break;
+            }
+            amount /= 2;
+        }
+    }
+
+    let status = attempts.last().map_or(TransactionStatus::Failed, |a| a.status);
+    let completed = attempts.iter().filter(|a| a.status == TransactionStatus::Completed);
+    Ok(RebalanceResult {
+        status,
+        amount: completed.clone().map(|a| a.amount).sum(),
+        fee_msat: completed.map(|a| a.fee_msat).sum(),
+        attempts,
+    })
+}
+
+/// Rééquilibrages qui ramènent les canaux hors de la fourchette de `policy` vers son milieu.
+///
+/// Chaque canal trop vide reçoit de l'excédent du canal le plus plein restant,
+/// dans la limite de `policy.max_amount`.
+pub fn plan(channels: &[Channel], policy: &RebalancePolicy) -> Vec<RebalanceRequest> {
+    let target = (policy.min_local_ratio + policy.max_local_ratio) / 2.0;
+    let ratio = |c: &Channel| c.local_balance as f64 / c.capacity as f64;
+    let target_balance = |c: &Channel| (c.capacity as f64 * target) as u64;
+    let active = channels
+        .iter()
+        .filter(|c| c.status == ChannelStatus::Active && c.capacity > 0);
+
+    let mut sources: Vec<(&Channel, u64)> = active
+        .clone()
+        .filter(|c| ratio(c) > policy.max_local_ratio)
+        .map(|c| (c, c.local_balance.saturating_sub(target_balance(c))))
+        .collect();
+    let mut sinks: Vec<(&Channel, u64)> = active
+        .filter(|c| ratio(c) < policy.min_local_ratio)
+        .map(|c| (c, target_balance(c).saturating_sub(c.local_balance)))
+        .collect();
+    sinks.sort_by_key(|(_, deficit)| Reverse(*deficit));
+
+    let mut requests = Vec::new();
+    for (sink, deficit) in sinks {
+        sources.sort_by_key(|(_, excess)| Reverse(*excess));
+        let Some((source, excess)) = sources
+            .iter_mut()
+            .find(|(source, _)| source.remote_pubkey != sink.remote_pubkey)
+        else {
+            continue;
+        };
+        let amount = deficit.min(*excess).min(policy.max_amount);
+        if amount < policy.retry.min_amount {
+            continue;
+        }
+        *excess -= amount;
+        requests.push(RebalanceRequest {
+            node: None,
+            outgoing_channel: source.id.clone(),
+            incoming_channel: sink.id.clone(),
+            amount,
+            max_fee_ppm: policy.max_fee_ppm,
+            retry: policy.retry.clone(),
+            timeout: None,
+        });
+    }
+    requests
+}
+
+/// Applique `policy` au nœud en tâche de fond, toutes les `policy.interval_secs`.
+///
+/// La première passe attend une période complète : un redémarrage ou une mise à jour
+/// du nœud ne déclenche pas de rééquilibrage immédiat.
+pub fn spawn(
+    node: &str,
+    service: LightningService,
+    history: HistoryStore,
+    policy: RebalancePolicy,
+) -> JoinHandle<()> {
+    let node = node.to_string();
+    tokio::spawn(async move {
+        let period = Duration::from_secs(policy.interval_secs.max(60));
+        let mut ticker = tokio::time::interval_at(Instant::now() + period, period);
+        loop {
+            ticker.tick().await;
+            let channels = match service.list_channels().await {
+                Ok(channels) => channels,
+                Err(e) => {
+                    warn!(node = %node, error = %e, "Canaux illisibles, rééquilibrage reporté");
+                    continue;
+                }
+            };
+            for request in plan(&channels, &policy) {
+                let trigger = RebalanceTrigger::Policy;
+                match rebalance(&service, &history, &node, &request, trigger).await {
+                    Ok(result) => info!(
+                        node = %node,
+                        outgoing = %request.outgoing_channel,
+                        incoming = %request.incoming_channel,
+                        amount = result.amount,
+                        fee_msat = result.fee_msat,
+                        "Rééquilibrage automatique terminé"
+                    ),
+                    Err(e) => {
+                        warn!(node = %node, error = %e, "Rééquilibrage automatique impossible")
+                    }
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,521 @@
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tracing::{error, info, instrument, warn};
+
+use crate::models::lightning::{
+    NodePortfolio, NodeSummary, Portfolio, TransactionStatus, TransactionType,
+};
+use crate::models::fees::FeePolicy;
+use crate::models::rebalance::RebalancePolicy;
+use crate::services::backends::{
+    BackendKind, ClnRestBackend, ClnRpcBackend, EclairBackend, LndCredentials, LndGrpcBackend,
+    LndRestBackend, NodeBackend,
+};
+use crate::services::events::EventBus;
+use crate::services::history::HistoryStore;
+use crate::services::lightning::LightningService;
+use crate::services::policy::{validate_fee_policy, validate_rebalance_policy};
+use crate::services::{fees, rebalance};
+
+/// Fenêtre utilisée pour sommer les frais de routage du portefeuille
+const PORTFOLIO_FEES_WINDOW_SECS: i64 = 30 * 24 * 3600;
+/// Taille des pages lues dans l'historique de routage
+const FORWARDING_PAGE_SIZE: u32 = 1000;
+
+/// Déclaration d'un nœud, telle qu'elle apparaît dans le fichier de configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeConfig {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    pub backend: BackendKind,
+    /// URL de l'API, ou chemin de la socket pour `cln-rpc`
+    pub url: String,
+    /// LND : macaroon en hexadécimal ou chemin de fichier
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub macaroon: Option<String>,
+    /// LND : certificat PEM ou chemin de fichier
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cert: Option<String>,
+    /// Core Lightning REST : rune d'accès
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rune: Option<String>,
+    /// Eclair : mot de passe de l'API
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// Sparkseer : clé d'API associée au nœud, requise par les frais suggérés
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sparkseer_api_key: Option<String>,
+    /// Rééquilibrage automatique des canaux, désactivé si absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rebalance: Option<RebalancePolicy>,
+    /// Gestion automatique des frais des canaux, désactivée si absente
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fees: Option<FeePolicy>,
+}
+
+impl NodeConfig {
+    /// Nœud unique décrit par les variables `LIGHTNING_*` historiques
+    pub fn from_env() -> Result<Self> {
+        let backend: BackendKind = env::var("LIGHTNING_BACKEND")
+            .unwrap_or_else(|_| "lnd".to_string())
+            .parse()?;
+        let url = match backend {
+            BackendKind::ClnRpc => {
+                env::var("CLN_RPC_PATH").map_err(|_| anyhow!("CLN_RPC_PATH non définie"))?
+            }
+            _ => env::var("LIGHTNING_URL").unwrap_or_else(|_| "http://localhost:8080".to_string()),
+        };
+        Ok(Self {
+            id: "default".to_string(),
+            name: "Nœud principal".to_string(),
+            backend,
+            url,
+            macaroon: env::var("LIGHTNING_MACAROON").ok(),
+            cert: env::var("LIGHTNING_CERT").ok(),
+            rune: env::var("CLN_RUNE").ok(),
+            password: env::var("ECLAIR_PASSWORD").ok(),
+            sparkseer_api_key: env::var("SPARKSEER_API_KEY").ok(),
+            rebalance: None,
+            fees: None,
+        })
+    }
+
+    /// Vérifie les politiques automatiques de la déclaration, qu'elle vienne du fichier
+    /// des nœuds ou de l'API
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(policy) = &self.rebalance {
+            validate_rebalance_policy(policy)?;
+        }
+        if let Some(policy) = &self.fees {
+            validate_fee_policy(policy, self.sparkseer_api_key.as_deref())?;
+        }
+        Ok(())
+    }
+
+    fn summary(&self) -> NodeSummary {
+        NodeSummary {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            backend: self.backend.to_string(),
+            url: self.url.clone(),
+        }
+    }
+
+    fn lnd_credentials(&self) -> Result<LndCredentials> {
+        let macaroon = self
+            .macaroon
+            .as_deref()
+            .ok_or_else(|| anyhow!("Macaroon manquant pour le nœud {}", self.id))?;
+        let cert = self
+            .cert
+            .as_deref()
+            .ok_or_else(|| anyhow!("Certificat TLS manquant pour le nœud {}", self.id))?;
+        LndCredentials::from_values(macaroon, cert)
+            .with_context(|| format!("Identifiants LND invalides pour le nœud {}", self.id))
+    }
+
+    /// Instancie le backend correspondant à cette déclaration
+    pub async fn connect(&self) -> Result<Arc<dyn NodeBackend>> {
+        let backend: Arc<dyn NodeBackend> = match self.backend {
+            BackendKind::Lnd => Arc::new(LndRestBackend::new(
+                self.url.clone(),
+                &self.lnd_credentials()?,
+            )?),
+            BackendKind::LndGrpc => Arc::new(
+                LndGrpcBackend::connect(self.url.clone(), &self.lnd_credentials()?).await?,
+            ),
+            BackendKind::Cln => Arc::new(ClnRestBackend::new(
+                self.url.clone(),
+                self.rune.clone().unwrap_or_default(),
+            )),
+            BackendKind::ClnRpc => Arc::new(ClnRpcBackend::new(self.url.clone().into())?),
+            BackendKind::Eclair => Arc::new(EclairBackend::new(
+                self.url.clone(),
+                self.password.clone().unwrap_or_default(),
+            )),
+        };
+        Ok(backend)
+    }
+}
+
+#[derive(Debug)]
+pub enum RegistryError {
+    NotFound(String),
+    AlreadyExists(String),
+    Backend(anyhow::Error),
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistryError::NotFound(id) => write!(f, "Nœud inconnu: {}", id),
+            RegistryError::AlreadyExists(id) => write!(f, "Le nœud {} existe déjà", id),
+            RegistryError::Backend(e) => write!(f, "{:#}", e),
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+impl From<anyhow::Error> for RegistryError {
+    fn from(e: anyhow::Error) -> Self {
+        RegistryError::Backend(e)
+    }
+}
+
+struct RegisteredNode {
+    config: NodeConfig,
+    service: LightningService,
+    /// Événements, relevés, rééquilibrage et frais ; vide pour un registre passif
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl Drop for RegisteredNode {
+    // Un nœud retiré ou remplacé cesse d'alimenter le flux d'événements
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+/// Ensemble des nœuds gérés par Lightdash.
+///
+/// Les nœuds sont lus depuis `LIGHTNING_NODES_FILE` (tableau JSON de
+/// `NodeConfig`) et le fichier est réécrit à chaque modification via l'API.
+pub struct NodeRegistry {
+    nodes: RwLock<Vec<RegisteredNode>>,
+    config_path: Option<PathBuf>,
+    sparkseer_url: String,
+    ml_url: String,
+    events: EventBus,
+    history: HistoryStore,
+}
+
+impl NodeRegistry {
+    /// Charge les nœuds et lance leurs tâches de fond, pour le serveur
+    pub async fn load(
+        config_path: Option<PathBuf>,
+        sparkseer_url: String,
+        ml_url: String,
+        history: HistoryStore,
+    ) -> Result<Self> {
+        Self::open(config_path, sparkseer_url, ml_url, history, true).await
+    }
+
+    /// Charge et connecte les nœuds sans lancer la moindre tâche de fond : ni flux
+    /// d'événements, ni relevés, ni rééquilibrage, ni gestion des frais.
+    ///
+    /// Réservé aux commandes ponctuelles de la CLI, qui lisent l'historique et
+    /// interrogent les nœuds sans les surveiller.
+    pub async fn load_passive(
+        config_path: Option<PathBuf>,
+        sparkseer_url: String,
+        ml_url: String,
+        history: HistoryStore,
+    ) -> Result<Self> {
+        Self::open(config_path, sparkseer_url, ml_url, history, false).await
+    }
+
+    async fn open(
+        config_path: Option<PathBuf>,
+        sparkseer_url: String,
+        ml_url: String,
+        history: HistoryStore,
+        background: bool,
+    ) -> Result<Self> {
+        let configs = match &config_path {
+            Some(path) => {
+                let content = std::fs::read_to_string(path).with_context(|| {
+                    format!("Impossible de lire la liste des nœuds {}", path.display())
+                })?;
+                serde_json::from_str::<Vec<NodeConfig>>(&content)
+                    .with_context(|| format!("Liste des nœuds invalide: {}", path.display()))?
+            }
+            None => vec![NodeConfig::from_env()?],
+        };
+
+        let registry = Self {
+            nodes: RwLock::new(Vec::new()),
+            config_path,
+            sparkseer_url,
+            ml_url,
+            events: EventBus::new(),
+            history,
+        };
+        for config in configs {
+            let service = registry.build_service(&config).await?;
+            let node = if background {
+                registry.start(config, service)
+            } else {
+                RegisteredNode {
+                    config,
+                    service,
+                    tasks: Vec::new(),
+                }
+            };
+            info!(node = %node.config.id, backend = %node.config.backend, "Nœud enregistré");
+            registry.nodes.write().await.push(node);
+        }
+        Ok(registry)
+    }
+
+    async fn build_service(&self, config: &NodeConfig) -> Result<LightningService> {
+        config
+            .validate()
+            .map_err(|e| anyhow!("Configuration invalide pour le nœud {}: {}", config.id, e))?;
+        let backend = config
+            .connect()
+            .await
+            .with_context(|| format!("Connexion impossible au nœud {}", config.id))?;
+        Ok(LightningService::new(
+            backend,
+            self.sparkseer_url.clone(),
+            self.ml_url.clone(),
+        ))
+    }
+
+    /// Lance les tâches de fond d'un nœud déjà connecté
+    fn start(&self, config: NodeConfig, service: LightningService) -> RegisteredNode {
+        let mut tasks = vec![
+            self.events.spawn(&config.id, service.clone()),
+            self.history.spawn(&config.id, service.clone()),
+        ];
+        if let Some(policy) = config.rebalance.clone() {
+            let history = self.history.clone();
+            tasks.push(rebalance::spawn(&config.id, service.clone(), history, policy));
+        }
+        if let Some(policy) = config.fees.clone() {
+            let api_key = config.sparkseer_api_key.clone();
+            let history = self.history.clone();
+            tasks.push(fees::spawn(&config.id, service.clone(), history, policy, api_key));
+        }
+        RegisteredNode {
+            config,
+            service,
+            tasks,
+        }
+    }
+
+    async fn persist(&self) -> Result<()> {
+        let Some(path) = &self.config_path else {
+            return Ok(());
+        };
+        let configs: Vec<NodeConfig> = self
+            .nodes
+            .read()
+            .await
+            .iter()
+            .map(|n| n.config.clone())
+            .collect();
+        std::fs::write(path, serde_json::to_string_pretty(&configs)?)
+            .with_context(|| format!("Impossible d'écrire {}", path.display()))
+    }
+
+    pub async fn list(&self) -> Vec<NodeSummary> {
+        self.nodes
+            .read()
+            .await
+            .iter()
+            .map(|n| n.config.summary())
+            .collect()
+    }
+
+    pub async fn get(&self, id: &str) -> Option<LightningService> {
+        self.nodes
+            .read()
+            .await
+            .iter()
+            .find(|n| n.config.id == id)
+            .map(|n| n.service.clone())
+    }
+
+    /// Flux des événements de l'ensemble des nœuds
+    pub fn events(&self) -> &EventBus {
+        &self.events
+    }
+
+    /// Historique des relevés de l'ensemble des nœuds
+    pub fn history(&self) -> &HistoryStore {
+        &self.history
+    }
+
+    /// Premier nœud déclaré, utilisé par les routes non préfixées par `/api/nodes/{id}`
+    pub async fn default_node(&self) -> Option<LightningService> {
+        self.nodes.read().await.first().map(|n| n.service.clone())
+    }
+
+    /// Identifiant du nœud par défaut
+    pub async fn default_node_id(&self) -> Option<String> {
+        self.nodes.read().await.first().map(|n| n.config.id.clone())
+    }
+
+    /// La connexion au nœud précède le verrou ; l'unicité de l'identifiant est vérifiée
+    /// sous le verrou d'écriture, avant de lancer la moindre tâche de fond.
+    #[instrument(skip(self, config), fields(node = %config.id), err)]
+    pub async fn add(&self, config: NodeConfig) -> Result<NodeSummary, RegistryError> {
+        let summary = config.summary();
+        let service = self.build_service(&config).await?;
+        {
+            let mut nodes = self.nodes.write().await;
+            if nodes.iter().any(|n| n.config.id == config.id) {
+                return Err(RegistryError::AlreadyExists(config.id));
+            }
+            nodes.push(self.start(config, service));
+        }
+        self.persist().await?;
+        info!("Nœud ajouté");
+        Ok(summary)
+    }
+
+    /// Les tâches de l'ancienne déclaration s'arrêtent quand elle est remplacée
+    #[instrument(skip(self, config), err)]
+    pub async fn update(
+        &self,
+        id: &str,
+        mut config: NodeConfig,
+    ) -> Result<NodeSummary, RegistryError> {
+        config.id = id.to_string();
+        let summary = config.summary();
+        let service = self.build_service(&config).await?;
+        {
+            let mut nodes = self.nodes.write().await;
+            let node = nodes
+                .iter_mut()
+                .find(|n| n.config.id == id)
+                .ok_or_else(|| RegistryError::NotFound(id.to_string()))?;
+            *node = self.start(config, service);
+        }
+        self.persist().await?;
+        info!("Nœud mis à jour");
+        Ok(summary)
+    }
+
+    #[instrument(skip(self), err)]
+    pub async fn remove(&self, id: &str) -> Result<(), RegistryError> {
+        {
+            let mut nodes = self.nodes.write().await;
+            let before = nodes.len();
+            nodes.retain(|n| n.config.id != id);
+            if nodes.len() == before {
+                return Err(RegistryError::NotFound(id.to_string()));
+            }
+        }
+        self.persist().await?;
+        info!("Nœud supprimé");
+        Ok(())
+    }
+
+    /// Agrège capacité, soldes et frais de tous les nœuds.
+    ///
+    /// Un nœud injoignable n'empêche pas le calcul : il apparaît avec son erreur.
+    #[instrument(skip(self))]
+    pub async fn portfolio(&self) -> Portfolio {
+        let nodes: Vec<(NodeConfig, LightningService)> = self
+            .nodes
+            .read()
+            .await
+            .iter()
+            .map(|n| (n.config.clone(), n.service.clone()))
+            .collect();
+
+        let mut portfolio = Portfolio {
+            nodes: Vec::with_capacity(nodes.len()),
+            total_capacity: 0,
+            total_local_balance: 0,
+            total_remote_balance: 0,
+            total_fees_paid: 0,
+            total_fees_earned_msat: 0,
+        };
+
+        for (config, service) in nodes {
+            let node = match node_portfolio(&config, &service).await {
+                Ok(node) => node,
+                Err(e) => {
+                    error!(node = %config.id, error = %e, "Nœud exclu du portefeuille");
+                    NodePortfolio {
+                        id: config.id,
+                        name: config.name,
+                        pubkey: None,
+                        capacity: 0,
+                        local_balance: 0,
+                        remote_balance: 0,
+                        fees_paid: 0,
+                        fees_earned_msat: 0,
+                        error: Some(format!("{:#}", e)),
+                    }
+                }
+            };
+            portfolio.total_capacity += node.capacity;
+            portfolio.total_local_balance += node.local_balance;
+            portfolio.total_remote_balance += node.remote_balance;
+            portfolio.total_fees_paid += node.fees_paid;
+            portfolio.total_fees_earned_msat += node.fees_earned_msat;
+            portfolio.nodes.push(node);
+        }
+
+        portfolio
+    }
+}
+
+async fn node_portfolio(config: &NodeConfig, service: &LightningService) -> Result<NodePortfolio> {
+    let info = service.get_node_info().await?;
+    let channels = service.list_channels().await?;
+    let fees_paid = service
+        .list_transactions()
+        .await?
+        .iter()
+        .filter(|t| {
+            matches!(t.type_, TransactionType::Payment)
+                && matches!(t.status, TransactionStatus::Completed)
+        })
+        .map(|t| t.fee)
+        .sum();
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default();
+    let fees_earned_msat = match fees_earned_since(service, now - PORTFOLIO_FEES_WINDOW_SECS).await {
+        Ok(fees) => fees,
+        Err(e) => {
+            warn!(node = %config.id, error = %e, "Frais de routage indisponibles");
+            0
+        }
+    };
+
+    Ok(NodePortfolio {
+        id: config.id.clone(),
+        name: config.name.clone(),
+        pubkey: Some(info.pubkey),
+        capacity: channels.iter().map(|c| c.capacity).sum(),
+        local_balance: channels.iter().map(|c| c.local_balance).sum(),
+        remote_balance: channels.iter().map(|c| c.remote_balance).sum(),
+        fees_paid,
+        fees_earned_msat,
+        error: None,
+    })
+}
+
+async fn fees_earned_since(service: &LightningService, start_time: i64) -> Result<u64> {
+    let mut offset = 0;
+    let mut total = 0;
+    loop {
+        let page = service
+            .forwarding_history(start_time, offset, FORWARDING_PAGE_SIZE)
+            .await?;
+        total += page.events.iter().map(|e| e.fee_msat).sum::<u64>();
+        if page.next_offset <= offset {
+            return Ok(total);
+        }
+        offset = page.next_offset;
+    }
+}
@@ -0,0 +1,103 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::models::history::ChannelForwards;
+use crate::models::lightning::{Channel, ChannelStatus};
+use crate::models::yields::{ChannelYield, PeerYield, YieldReport};
+
+const SECONDS_PER_YEAR: f64 = 365.0 * 24.0 * 3600.0;
+
+/// Rendement annualisé, en pourcentage, de `fee_msat` gagnés en `seconds` sur `capital` sats
+pub fn annualized_return(fee_msat: u64, capital: u64, seconds: i64) -> Option<f64> {
+    if capital == 0 || seconds <= 0 {
+        return None;
+    }
+    let fee = fee_msat as f64 / 1000.0;
+    Some(fee / capital as f64 * SECONDS_PER_YEAR / seconds as f64 * 100.0)
+}
+
+/// Rendement réalisé par canal et par pair entre `from` et `to`.
+///
+/// Les relais sont rattachés aux canaux par leur short channel id. Le capital
+/// engagé est le solde local moyen relevé sur la fenêtre (`capital`, par
+/// identifiant de canal), à défaut le solde local actuel.
+pub fn compute_yields(
+    from: i64,
+    to: i64,
+    channels: &[Channel],
+    forwards: &[ChannelForwards],
+    capital: &HashMap<String, u64>,
+) -> YieldReport {
+    let seconds = to - from;
+    let mut forwards: HashMap<&str, &ChannelForwards> =
+        forwards.iter().map(|f| (f.channel_id.as_str(), f)).collect();
+
+    let mut channel_yields: Vec<ChannelYield> = channels
+        .iter()
+        .filter(|c| c.status != ChannelStatus::Pending)
+        .map(|c| {
+            let activity = c
+                .short_channel_id
+                .as_deref()
+                .and_then(|scid| forwards.remove(scid))
+                .or_else(|| forwards.remove(c.id.as_str()));
+            let capital = capital.get(&c.id).copied().unwrap_or(c.local_balance);
+            let fee_msat = activity.map_or(0, |a| a.fee_msat);
+            ChannelYield {
+                channel_id: c.id.clone(),
+                short_channel_id: c.short_channel_id.clone(),
+                remote_pubkey: c.remote_pubkey.clone(),
+                active: c.status == ChannelStatus::Active,
+                capacity: c.capacity,
+                capital,
+                fee_msat,
+                inbound_fee_msat: activity.map_or(0, |a| a.inbound_fee_msat),
+                forwards: activity.map_or(0, |a| a.forwards_out),
+                amt_out_msat: activity.map_or(0, |a| a.amt_out_msat),
+                annualized_return: annualized_return(fee_msat, capital, seconds),
+            }
+        })
+        .collect();
+    channel_yields.sort_by_key(|c| Reverse(c.fee_msat));
+
+    // Les relais restants concernent des canaux fermés depuis
+    let closed_channels_fee_msat: u64 = forwards.values().map(|f| f.fee_msat).sum();
+
+    let mut peers: BTreeMap<&str, PeerYield> = BTreeMap::new();
+    for c in &channel_yields {
+        let peer = peers.entry(&c.remote_pubkey).or_insert_with(|| PeerYield {
+            remote_pubkey: c.remote_pubkey.clone(),
+            channels: 0,
+            capital: 0,
+            fee_msat: 0,
+            forwards: 0,
+            annualized_return: None,
+        });
+        peer.channels += 1;
+        peer.capital += c.capital;
+        peer.fee_msat += c.fee_msat;
+        peer.forwards += c.forwards;
+    }
+    let mut peers: Vec<PeerYield> = peers
+        .into_values()
+        .map(|mut p| {
+            p.annualized_return = annualized_return(p.fee_msat, p.capital, seconds);
+            p
+        })
+        .collect();
+    peers.sort_by_key(|peer| Reverse(peer.fee_msat));
+
+    let total_capital = channel_yields.iter().map(|c| c.capital).sum();
+    let fee_msat =
+        channel_yields.iter().map(|c| c.fee_msat).sum::<u64>() + closed_channels_fee_msat;
+    YieldReport {
+        from,
+        to,
+        capital: total_capital,
+        fee_msat,
+        closed_channels_fee_msat,
+        annualized_return: annualized_return(fee_msat, total_capital, seconds),
+        channels: channel_yields,
+        peers,
+    }
+}
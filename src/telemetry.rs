@@ -1,5 +1,5 @@
 use opentelemetry::sdk::trace::{self, Sampler};
-use tracing_actix_web::TracingLogger;
+use tracing_actix_web::{DefaultRootSpanBuilder, TracingLogger};
 use tracing_bunyan_formatter::{BunyanFormattingLayer, JsonStorageLayer};
 use tracing_subscriber::{layer::SubscriberExt, EnvFilter, Registry};
 use tracing::subscriber::set_global_default;
@@ -44,6 +44,6 @@ pub fn init_telemetry(app_name: &str) {
 }
 
 /// Retourne le middleware de tracing pour Actix-web
-pub fn get_tracing_middleware() -> TracingLogger<tracing_bunyan_formatter::JsonStorageLayer> {
+pub fn get_tracing_middleware() -> TracingLogger<DefaultRootSpanBuilder> {
     TracingLogger::default()
 } 
\ No newline at end of file
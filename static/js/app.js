@@ -21,11 +21,24 @@ const utils = {
     }
 };
 
+// Nœud choisi dans le sélecteur du tableau de bord, partagé via le localStorage
+const SELECTED_NODE_KEY = 'lightdash_selected_node';
+
+// Ajoute le nœud sélectionné à l'URL, sans quoi le serveur vise le nœud par défaut
+function nodeUrl(endpoint) {
+    const node = localStorage.getItem(SELECTED_NODE_KEY);
+    if (!node) {
+        return `/api/${endpoint}`;
+    }
+    const separator = endpoint.includes('?') ? '&' : '?';
+    return `/api/${endpoint}${separator}node=${encodeURIComponent(node)}`;
+}
+
 // Gestionnaire d'API
 const api = {
     async fetch(endpoint, options = {}) {
         try {
-            const response = await fetch(`/api/${endpoint}`, {
+            const response = await fetch(nodeUrl(endpoint), {
                 ...options,
                 headers: {
                     'Content-Type': 'application/json',
@@ -62,6 +75,14 @@ const api = {
         });
     },
 
+    async getPendingChannels() {
+        return this.fetch('channels/pending');
+    },
+
+    async estimateClose(channelId) {
+        return this.fetch(`channels/${channelId}/close`);
+    },
+
     async closeChannel(channelId) {
         return this.fetch(`channels/${channelId}`, {
             method: 'DELETE'